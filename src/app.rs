@@ -1,22 +1,32 @@
 //! Application state and main event loop.
 
-use crate::config::ProjectConfig;
+use crate::config::{CommitScope, HookConfig, ProjectConfig};
 use crate::domain::{
-    ArtifactType, ExecutionState, Project, Specification, WorkflowCommand, WorkflowCommandType,
-    Worktree, WorktreeStatus,
+    AgentEvent, ArtifactSnapshot, ArtifactType, Batch, CancelOutcome, DiffScope, ExecutionState,
+    GitOp, GitRun, HookPhase, HookRun, Pipeline, Project, RunRecord, SpecArtifacts, SpecId,
+    Specification, StepState, UsageSummary, WorkflowCommand, WorkflowCommandType, Worktree,
+    WorktreeStatus,
+};
+use crate::error::{AppError, GitError, Result};
+use crate::services::{
+    CancelPolicy, FsWatcher, GitService, ProcessHandle, ProcessOutput, RunHistory, SpecService,
+    StatusUpdate, StatusWorker, WatchChanges, WorkflowContext, WorkflowRunner,
 };
-use crate::error::{AppError, Result};
-use crate::services::{GitService, ProcessHandle, ProcessOutput, SpecService, WorkflowRunner};
 use crate::ui::input::{Action, InputHandler, InputMode};
+use crate::ui::widgets::commit::{CommitFocus, CommitViewState};
+use crate::ui::widgets::commit_log::CommitLogState;
+use crate::ui::widgets::diff_view::DiffViewState;
 use crate::ui::widgets::editor::{EditorAction, EditorState};
 use crate::ui::widgets::help::HelpViewState;
 use crate::ui::widgets::output_panel::OutputBuffer;
 use crate::ui::widgets::spec_detail::DocumentViewerState;
 use crate::ui::widgets::text_input::{TextInputAction, TextInputState};
+use crate::ui::widgets::usage_summary::UsageViewState;
 use crate::ui::widgets::worktree_list::WorktreeManagementState;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
 use ratatui::prelude::*;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 /// Application view state
@@ -29,16 +39,28 @@ pub enum AppView {
     SpecDetail(String),
     /// Worktree management
     WorktreeManagement,
+    /// Diff of a worktree's changes
+    WorktreeDiff,
+    /// Staging and committing a worktree's changes
+    CommitView,
+    /// Diff of a commit from a spec's commit log
+    CommitDiff,
     /// Viewing a document
     DocumentView(DocType),
     /// Editing a document
     DocumentEdit(DocType),
+    /// Editing a file changed by a workflow run
+    FileEdit,
+    /// Reviewing artifact changes made by a workflow run
+    ArtifactReview,
     /// Workflow command output
     CommandOutput,
     /// Workflow command selection menu
     WorkflowMenu,
     /// Creating a new specification
     NewSpec,
+    /// Token usage and cost summary
+    UsageSummary,
     /// Help view showing keybindings
     Help,
 }
@@ -52,6 +74,18 @@ pub enum DocType {
     Research,
 }
 
+/// Auto-commit of a finished run, held back while its changes are reviewed
+struct PendingCommit {
+    /// Worktree to commit in
+    worktree: PathBuf,
+    /// Path the commit is limited to, if any
+    pathspec: Option<PathBuf>,
+    /// Commit message
+    message: String,
+    /// History entry for the run, written once the commit is made
+    record: Option<RunRecord>,
+}
+
 /// Main application state
 pub struct App {
     /// Project context
@@ -85,8 +119,11 @@ pub struct App {
 
     // Services
     spec_service: SpecService,
-    git_service: Option<GitService>,
+    git_service: Option<Arc<GitService>>,
+    status_worker: Option<StatusWorker>,
+    fs_watcher: Option<FsWatcher>,
     workflow_runner: Option<WorkflowRunner>,
+    run_history: RunHistory,
 
     // Input handler
     input_handler: InputHandler,
@@ -100,6 +137,34 @@ pub struct App {
     pub selected_workflow_index: usize,
     /// Available workflow commands for selection
     pub available_workflows: Vec<WorkflowCommandType>,
+    /// Last agent session of each available command, if it can be continued
+    pub workflow_sessions: Vec<Option<String>>,
+    /// Input line forwarded to the running command's stdin
+    pub stdin_input: TextInputState,
+    /// Whether the stdin input line has focus
+    pub stdin_focused: bool,
+    /// Context of the most recent workflow run
+    pub active_context: Option<WorkflowContext>,
+    /// Pipeline being run, if any
+    pub pipeline: Option<Pipeline>,
+    /// Batch being run, if any
+    pub batch: Option<Batch>,
+    /// Specs marked in the spec list for a batch run
+    pub marked_specs: Vec<SpecId>,
+    /// Hooks currently running around the active command
+    pub hook_run: Option<HookRun>,
+    /// Fetch, pull or push shown in the output panel instead of a command
+    pub git_run: Option<GitRun>,
+    /// Post-run hooks configured for the active command
+    post_hooks: Vec<HookConfig>,
+    /// When the next attempt of a failed command starts, if one is queued
+    retry_at: Option<Instant>,
+    /// Spec directory contents from before the current run
+    artifact_snapshot: Option<ArtifactSnapshot>,
+    /// Artifact changes awaiting review
+    pub artifact_review: DiffViewState,
+    /// Auto-commit waiting for the artifact review to finish
+    pending_commit: Option<PendingCommit>,
 
     // Document viewing/editing state
     /// Current document content being viewed
@@ -110,6 +175,8 @@ pub struct App {
     pub editor_state: EditorState,
     /// Currently selected document type for viewing
     pub current_doc_type: Option<DocType>,
+    /// View to return to when the editor closes
+    editor_return_view: Option<AppView>,
 
     // Worktree management state
     /// State for worktree management view
@@ -117,6 +184,22 @@ pub struct App {
     /// Sync status cache for worktrees
     pub worktree_sync_statuses:
         std::collections::HashMap<String, crate::domain::WorktreeSyncStatus>,
+    /// Worktree shown in the diff view
+    pub diff_worktree: Option<Worktree>,
+    /// Changes shown in the diff view
+    pub worktree_diff: DiffViewState,
+    /// Which changes the diff view compares
+    pub worktree_diff_scope: DiffScope,
+    /// Worktree shown in the commit view
+    pub commit_worktree: Option<Worktree>,
+    /// Files and message of the commit view
+    pub commit_view: CommitViewState,
+    /// View to return to when the commit view or commit diff closes
+    commit_return_view: Option<AppView>,
+    /// Commits of the spec shown in the spec detail view
+    pub commit_log: CommitLogState,
+    /// Changes of the commit opened from the commit log
+    pub commit_diff: DiffViewState,
 
     // New spec creation state
     /// Text input state for new spec name
@@ -128,6 +211,10 @@ pub struct App {
     /// State for help view (scroll position)
     pub help_view_state: HelpViewState,
 
+    // Usage summary state
+    /// Token usage aggregated from the run history
+    pub usage_view_state: UsageViewState,
+
     /// Should quit the application
     pub should_quit: bool,
 }
@@ -140,12 +227,27 @@ impl App {
 
         let spec_service = SpecService::new(project.specs_directory.clone());
 
-        let git_service =
-            GitService::new(project_root.clone(), project.worktree_directory.clone()).ok();
+        let git_service = GitService::new(project_root.clone(), project.worktree_directory.clone())
+            .ok()
+            .map(Arc::new);
+        let status_worker = git_service.as_ref().map(|git| {
+            StatusWorker::new(
+                Arc::clone(git),
+                project.config.git.status_jobs,
+                Duration::from_millis(project.config.git.status_debounce_ms),
+            )
+        });
+
+        let run_history = RunHistory::new(project_root.join(".speck").join("history.jsonl"));
 
         // Create workflow runner with log directory
         let log_dir = project_root.join(".speck").join("logs");
-        let workflow_runner = Some(WorkflowRunner::default_with_log_dir(log_dir));
+        let workflow_runner = Some(
+            WorkflowRunner::from_agent_config(log_dir, &project.config.agent)
+                .with_log_retention(project.config.logs.clone())
+                .with_cancel_policy(CancelPolicy::from_config(&project.config.workflow))
+                .with_retry_policies(&project.config.workflow),
+        );
 
         Ok(Self {
             project,
@@ -162,32 +264,168 @@ impl App {
             worktree_statuses: std::collections::HashMap::new(),
             spec_service,
             git_service,
+            status_worker,
+            fs_watcher: None,
             workflow_runner,
+            run_history,
             input_handler: InputHandler::new(true), // vim navigation enabled
             process_handle: None,
             output_buffer: OutputBuffer::new(),
             selected_workflow_index: 0,
             available_workflows: Vec::new(),
+            workflow_sessions: Vec::new(),
+            stdin_input: TextInputState::new(),
+            stdin_focused: false,
+            active_context: None,
+            pipeline: None,
+            batch: None,
+            marked_specs: Vec::new(),
+            hook_run: None,
+            git_run: None,
+            post_hooks: Vec::new(),
+            retry_at: None,
+            artifact_snapshot: None,
+            artifact_review: DiffViewState::default(),
+            pending_commit: None,
             document_content: None,
             document_viewer_state: DocumentViewerState::new(),
             editor_state: EditorState::new(),
             current_doc_type: None,
+            editor_return_view: None,
             worktree_management_state: WorktreeManagementState::new(),
             worktree_sync_statuses: std::collections::HashMap::new(),
+            diff_worktree: None,
+            worktree_diff: DiffViewState::default(),
+            worktree_diff_scope: DiffScope::default(),
+            commit_worktree: None,
+            commit_view: CommitViewState::default(),
+            commit_return_view: None,
+            commit_log: CommitLogState::default(),
+            commit_diff: DiffViewState::default(),
             new_spec_input: TextInputState::new(),
             new_spec_error: None,
             help_view_state: HelpViewState::new(),
+            usage_view_state: UsageViewState::default(),
             should_quit: false,
         })
     }
 
     /// Initialize the application (load initial data)
     pub fn init(&mut self) -> Result<()> {
+        self.start_watching();
         self.refresh_specs()?;
         self.refresh_worktrees();
+        self.prune_logs();
         Ok(())
     }
 
+    /// Watch the specs directory and worktrees for changes
+    fn start_watching(&mut self) {
+        let watch = &self.project.config.watch;
+        if !watch.enabled {
+            return;
+        }
+        let Some(ref git) = self.git_service else {
+            return;
+        };
+
+        match FsWatcher::new(
+            self.project.specs_directory.clone(),
+            git.worktrees_dir(),
+            Duration::from_millis(watch.debounce_ms),
+        ) {
+            Ok(watcher) => self.fs_watcher = Some(watcher),
+            Err(e) => tracing::warn!("Filesystem watching disabled: {}", e),
+        }
+    }
+
+    /// Apply filesystem changes collected by the watcher
+    pub fn poll_fs_changes(&mut self) {
+        let Some(changes) = self.fs_watcher.as_mut().and_then(|w| w.poll()) else {
+            return;
+        };
+        tracing::debug!("Filesystem changes: {:?}", changes);
+        self.apply_fs_changes(changes);
+    }
+
+    /// Update specs and worktree statuses affected by filesystem changes
+    fn apply_fs_changes(&mut self, changes: WatchChanges) {
+        let mut rescan = changes.specs_changed;
+        let mut changed_specs = Vec::new();
+        for name in &changes.spec_dirs {
+            match self.specs.iter().position(|s| s.id.as_str() == name) {
+                Some(idx) => changed_specs.push(idx),
+                None => rescan = true,
+            }
+        }
+        // Agents write a spec's artifacts in its worktree
+        for wt in self
+            .worktrees
+            .iter()
+            .filter(|w| !w.is_main && changes.worktrees.contains(&w.path))
+        {
+            changed_specs.extend(self.specs.iter().position(|s| s.branch == wt.branch));
+        }
+
+        if rescan {
+            if let Err(e) = self.refresh_specs() {
+                tracing::warn!("Failed to refresh specs: {}", e);
+            }
+        } else {
+            for idx in changed_specs {
+                let dir = self.spec_artifact_dir(&self.specs[idx]);
+                self.specs[idx].refresh_artifacts_from(&dir);
+            }
+        }
+
+        // Keep an open diff in step with the files it shows
+        let diff_changed = self
+            .diff_worktree
+            .as_ref()
+            .is_some_and(|wt| changes.worktrees.contains(&wt.path));
+        if diff_changed && self.view == AppView::WorktreeDiff {
+            if let Err(e) = self.load_worktree_diff() {
+                tracing::warn!("Failed to refresh diff: {}", e);
+            }
+        }
+        let commit_changed = self
+            .commit_worktree
+            .as_ref()
+            .is_some_and(|wt| changes.worktrees.contains(&wt.path));
+        if commit_changed && self.view == AppView::CommitView {
+            if let Err(e) = self.load_commit_files() {
+                tracing::warn!("Failed to refresh changed files: {}", e);
+            }
+        }
+
+        if changes.worktree_list_changed {
+            self.refresh_worktrees();
+        } else if let Some(ref mut worker) = self.status_worker {
+            let changed: Vec<Worktree> = self
+                .worktrees
+                .iter()
+                .filter(|w| changes.worktrees.contains(&w.path))
+                .cloned()
+                .collect();
+            if !changed.is_empty() {
+                worker.request(&changed, false);
+            }
+        }
+    }
+
+    /// Enforce log retention on the workflow log directory
+    pub fn prune_logs(&mut self) {
+        if let Some(ref runner) = self.workflow_runner {
+            match runner.prune_logs() {
+                Ok(report) if report.removed > 0 || report.compressed > 0 => {
+                    tracing::info!("Log retention: {}", report);
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to prune logs: {}", e),
+            }
+        }
+    }
+
     /// Refresh the specifications list
     pub fn refresh_specs(&mut self) -> Result<()> {
         self.is_loading = true;
@@ -195,6 +433,7 @@ impl App {
             Ok(specs) => {
                 self.specs = specs;
                 self.is_loading = false;
+                self.refresh_worktree_artifacts();
                 // Adjust selection if needed
                 if self.selected_spec_index >= self.specs.len() && !self.specs.is_empty() {
                     self.selected_spec_index = self.specs.len() - 1;
@@ -208,12 +447,50 @@ impl App {
         Ok(())
     }
 
+    /// Directory holding a spec's artifacts: its copy in the spec's
+    /// worktree, where workflows write, if there is one, otherwise the spec
+    /// directory in the main checkout
+    pub fn spec_artifact_dir(&self, spec: &Specification) -> PathBuf {
+        self.find_worktree_for_spec(spec)
+            .map(|w| {
+                w.path
+                    .join(self.project.relative_spec_path(&spec.directory))
+            })
+            .filter(|dir| dir.is_dir())
+            .unwrap_or_else(|| spec.directory.clone())
+    }
+
+    /// Take the artifacts and phase of specs with a worktree from the
+    /// worktree's copy of the spec directory
+    fn refresh_worktree_artifacts(&mut self) {
+        for idx in 0..self.specs.len() {
+            let dir = self.spec_artifact_dir(&self.specs[idx]);
+            if dir != self.specs[idx].directory {
+                self.specs[idx].refresh_artifacts_from(&dir);
+            }
+        }
+    }
+
+    /// Read an artifact from the spec's artifact directory
+    fn read_spec_artifact(
+        &self,
+        spec: &Specification,
+        artifact_type: ArtifactType,
+    ) -> std::io::Result<String> {
+        std::fs::read_to_string(self.spec_artifact_dir(spec).join(artifact_type.filename()))
+    }
+
     /// Refresh the worktrees list
     pub fn refresh_worktrees(&mut self) {
         if let Some(ref git) = self.git_service {
             match git.list_worktrees() {
                 Ok(worktrees) => {
                     self.worktrees = worktrees;
+                    if let Some(ref mut watcher) = self.fs_watcher {
+                        watcher
+                            .set_worktrees(self.worktrees.iter().map(|w| w.path.clone()).collect());
+                    }
+                    self.refresh_worktree_artifacts();
                     self.refresh_worktree_statuses();
                 }
                 Err(e) => {
@@ -223,30 +500,60 @@ impl App {
         }
     }
 
-    /// Refresh worktree status cache
+    /// Refresh worktree status cache in the background. Cached statuses stay
+    /// visible until the new ones arrive.
     pub fn refresh_worktree_statuses(&mut self) {
-        self.worktree_statuses.clear();
-        if let Some(ref git) = self.git_service {
-            for wt in &self.worktrees {
-                if let Ok(status) = git.worktree_status(&wt.path) {
-                    self.worktree_statuses.insert(wt.path.clone(), status);
-                }
+        let worktrees = &self.worktrees;
+        self.worktree_statuses
+            .retain(|path, _| worktrees.iter().any(|w| &w.path == path));
+        if let Some(ref mut worker) = self.status_worker {
+            worker.request(&self.worktrees, false);
+        }
+    }
+
+    /// Apply status results computed by the background worker
+    pub fn poll_status_updates(&mut self) {
+        let Some(ref mut worker) = self.status_worker else {
+            return;
+        };
+
+        for update in worker.poll() {
+            match update {
+                StatusUpdate::Status { path, status } => match status {
+                    Some(status) => {
+                        self.worktree_statuses.insert(path, status);
+                    }
+                    None => {
+                        self.worktree_statuses.remove(&path);
+                    }
+                },
+                StatusUpdate::Sync { branch, sync } => match sync {
+                    Some(sync) => {
+                        self.worktree_sync_statuses.insert(branch, sync);
+                    }
+                    None => {
+                        self.worktree_sync_statuses.remove(&branch);
+                    }
+                },
             }
         }
     }
 
+    /// Get the background status worker, if git is available
+    pub fn status_worker(&self) -> Option<&StatusWorker> {
+        self.status_worker.as_ref()
+    }
+
     /// Get worktree status for a path
     pub fn get_worktree_status(&self, path: &PathBuf) -> Option<&WorktreeStatus> {
         self.worktree_statuses.get(path)
     }
 
-    /// Find worktree for a spec (by branch name)
+    /// Find the worktree checked out on a spec's branch, never the main checkout
     pub fn find_worktree_for_spec(&self, spec: &Specification) -> Option<&Worktree> {
-        self.worktrees.iter().find(|w| {
-            w.branch == spec.branch
-                || w.branch.contains(&spec.branch)
-                || spec.branch.contains(&w.branch)
-        })
+        self.worktrees
+            .iter()
+            .find(|w| !w.is_main && w.branch == spec.branch)
     }
 
     /// Switch to a spec's worktree, creating it if necessary
@@ -256,15 +563,23 @@ impl App {
             None => return Ok(None),
         };
 
+        self.ensure_worktree_for_spec(&spec).map(Some)
+    }
+
+    /// Resolve a spec's worktree, creating the branch and worktree if necessary
+    pub fn ensure_worktree_for_spec(
+        &mut self,
+        spec: &Specification,
+    ) -> std::result::Result<PathBuf, String> {
         let git = match &self.git_service {
             Some(g) => g,
             None => return Err("Git service not available".to_string()),
         };
 
         // Check if worktree already exists for this spec
-        if let Some(wt) = self.find_worktree_for_spec(&spec) {
+        if let Some(wt) = self.find_worktree_for_spec(spec) {
             // Worktree exists, return the path
-            return Ok(Some(wt.path.clone()));
+            return Ok(wt.path.clone());
         }
 
         // Need to create a worktree
@@ -297,7 +612,7 @@ impl App {
                 self.loading_message = None;
                 // Refresh worktrees list
                 self.refresh_worktrees();
-                Ok(Some(worktree_path))
+                Ok(worktree_path)
             }
             Err(e) => {
                 self.is_loading = false;
@@ -312,27 +627,161 @@ impl App {
         self.specs.get(self.selected_spec_index)
     }
 
-    /// Get available workflow commands for the selected spec
+    /// Get available workflow commands for the selected spec, or the
+    /// commands available to every marked spec
     pub fn get_available_workflows(&self) -> Vec<WorkflowCommandType> {
+        if !self.marked_specs.is_empty() {
+            let mut marked = self.specs.iter().filter(|s| self.is_marked(&s.id));
+            let Some(first) = marked.next() else {
+                return Vec::new();
+            };
+            let mut commands = first.phase.available_commands();
+            for spec in marked {
+                let available = spec.phase.available_commands();
+                commands.retain(|c| available.contains(c));
+            }
+            return commands;
+        }
+
         match self.selected_spec() {
             Some(spec) => spec.phase.available_commands(),
             None => Vec::new(),
         }
     }
 
+    /// Check if a spec is marked for a batch run
+    pub fn is_marked(&self, spec_id: &SpecId) -> bool {
+        self.marked_specs.contains(spec_id)
+    }
+
+    /// Mark or unmark the selected spec
+    pub fn toggle_mark_selected(&mut self) {
+        let Some(spec_id) = self.selected_spec().map(|s| s.id.clone()) else {
+            return;
+        };
+        match self.marked_specs.iter().position(|id| *id == spec_id) {
+            Some(idx) => {
+                self.marked_specs.remove(idx);
+            }
+            None => self.marked_specs.push(spec_id),
+        }
+    }
+
+    /// Mark every spec in the selected spec's phase, or unmark them if they
+    /// are all marked already
+    pub fn toggle_mark_phase(&mut self) {
+        let Some(phase) = self.selected_spec().map(|s| s.phase) else {
+            return;
+        };
+        let in_phase: Vec<SpecId> = self
+            .specs
+            .iter()
+            .filter(|s| s.phase == phase)
+            .map(|s| s.id.clone())
+            .collect();
+
+        if in_phase.iter().all(|id| self.is_marked(id)) {
+            self.marked_specs.retain(|id| !in_phase.contains(id));
+        } else {
+            for id in in_phase {
+                if !self.is_marked(&id) {
+                    self.marked_specs.push(id);
+                }
+            }
+        }
+    }
+
     /// Open the workflow command menu
     pub fn open_workflow_menu(&mut self) {
         self.available_workflows = self.get_available_workflows();
         if self.available_workflows.is_empty() {
-            self.error_message = Some("No workflow commands available for this phase".to_string());
+            self.error_message = Some(if self.marked_specs.is_empty() {
+                "No workflow commands available for this phase".to_string()
+            } else {
+                "No workflow command is available for all marked specs".to_string()
+            });
             return;
         }
+        self.workflow_sessions = self.last_sessions();
         self.selected_workflow_index = 0;
         self.view = AppView::WorkflowMenu;
     }
 
+    /// Look up the last session of each available command for the selected
+    /// spec. Batches always start fresh sessions.
+    fn last_sessions(&self) -> Vec<Option<String>> {
+        let resumable = self
+            .workflow_runner
+            .as_ref()
+            .is_some_and(|r| r.supports_resume());
+        let spec = match self.selected_spec() {
+            Some(spec) if resumable && self.marked_specs.is_empty() => spec,
+            _ => return vec![None; self.available_workflows.len()],
+        };
+
+        let spec_id = spec.id.to_string();
+        self.available_workflows
+            .iter()
+            .map(|command| {
+                self.run_history
+                    .last_session(&spec_id, command.config_key())
+                    .unwrap_or_else(|e| {
+                        tracing::warn!("Failed to read run history: {}", e);
+                        None
+                    })
+            })
+            .collect()
+    }
+
+    /// Continue the last agent session of the selected workflow command
+    pub fn continue_last_session(&mut self) -> std::result::Result<(), String> {
+        if !self.marked_specs.is_empty() {
+            return Err("Sessions cannot be continued for a batch".to_string());
+        }
+        if !self
+            .workflow_runner
+            .as_ref()
+            .is_some_and(|r| r.supports_resume())
+        {
+            return Err("The configured agent has no resume_args".to_string());
+        }
+
+        let spec = match self.selected_spec() {
+            Some(s) => s.clone(),
+            None => return Err("No spec selected".to_string()),
+        };
+        let command_type = match self.available_workflows.get(self.selected_workflow_index) {
+            Some(t) => *t,
+            None => return Err("No workflow command selected".to_string()),
+        };
+        let session_id = self
+            .workflow_sessions
+            .get(self.selected_workflow_index)
+            .cloned()
+            .flatten()
+            .ok_or_else(|| {
+                format!(
+                    "No previous {} session for {}",
+                    command_type.display_name(),
+                    spec.id
+                )
+            })?;
+
+        self.start_workflow(command_type, &spec, true, Some(session_id))?;
+        self.pipeline = None;
+        self.batch = None;
+        Ok(())
+    }
+
     /// Run the selected workflow command
     pub fn run_selected_workflow(&mut self) -> std::result::Result<(), String> {
+        if !self.marked_specs.is_empty() {
+            return match self.available_workflows.get(self.selected_workflow_index) {
+                Some(&command_type) => self.run_batch(command_type),
+                None => Err("No workflow command selected".to_string()),
+            };
+        }
+
         let spec = match self.selected_spec() {
             Some(s) => s.clone(),
             None => return Err("No spec selected".to_string()),
@@ -352,173 +801,1172 @@ impl App {
         command_type: WorkflowCommandType,
         spec: &Specification,
     ) -> std::result::Result<(), String> {
-        // Check if a command is already running
+        self.start_workflow(command_type, spec, true, None)?;
+        self.pipeline = None;
+        self.batch = None;
+        Ok(())
+    }
+
+    /// Run a workflow command for every marked spec, one after another
+    pub fn run_batch(
+        &mut self,
+        command_type: WorkflowCommandType,
+    ) -> std::result::Result<(), String> {
         if self.is_command_running() {
             return Err("A command is already running".to_string());
         }
 
-        let runner = match &self.workflow_runner {
-            Some(r) => r,
-            None => return Err("Workflow runner not available".to_string()),
+        // Keep the spec list order rather than the order specs were marked in
+        let spec_ids: Vec<SpecId> = self
+            .specs
+            .iter()
+            .filter(|s| self.is_marked(&s.id))
+            .map(|s| s.id.clone())
+            .collect();
+        let Some(first) = spec_ids.first().cloned() else {
+            return Err("No specs marked".to_string());
         };
 
-        // Start the command
-        let (command, handle) = runner
-            .start_command(command_type, spec.id.as_str(), &spec.directory)
-            .map_err(|e| format!("Failed to start command: {}", e))?;
-
-        self.active_command = Some(command);
-        self.process_handle = Some(handle);
         self.output_buffer.start();
+        self.output_buffer.push_stdout(format!(
+            "Batch: {} for {} specs",
+            command_type.display_name(),
+            spec_ids.len()
+        ));
+        // A batch spans several spec directories, so there is no single
+        // snapshot to review changes against
+        self.commit_pending();
+        self.artifact_snapshot = None;
+        self.artifact_review = DiffViewState::default();
+        self.pipeline = None;
+        self.batch = Some(Batch::new(command_type, spec_ids));
+        self.marked_specs.clear();
         self.view = AppView::CommandOutput;
 
+        self.run_batch_entry(first);
         Ok(())
     }
 
-    /// Check if a command is currently running
-    pub fn is_command_running(&self) -> bool {
-        self.process_handle.as_ref().is_some_and(|h| h.is_running())
-    }
+    /// Start the batch's command for a spec, moving past specs that cannot
+    /// be started
+    fn run_batch_entry(&mut self, mut spec_id: SpecId) {
+        loop {
+            let Some(command_type) = self.batch.as_ref().map(|b| b.command) else {
+                return;
+            };
+            let result = match self.specs.iter().find(|s| s.id == spec_id).cloned() {
+                Some(spec) => self.start_workflow(command_type, &spec, false, None),
+                None => Err(format!("Spec {} no longer exists", spec_id)),
+            };
 
-    /// Cancel the running command
-    pub fn cancel_command(&mut self) {
-        if let Some(ref mut handle) = self.process_handle {
-            let _ = handle.kill();
+            let Some(ref mut batch) = self.batch else {
+                return;
+            };
+            let next = match result {
+                Ok(()) => {
+                    batch.start_entry();
+                    return;
+                }
+                Err(e) => {
+                    self.output_buffer
+                        .push_stderr(format!("{}: {}", spec_id, e));
+                    batch.entry_finished(Err(e))
+                }
+            };
+            match next {
+                Some(next) => spec_id = next,
+                None => {
+                    self.finish_batch();
+                    return;
+                }
+            }
         }
+    }
 
-        if let Some(ref mut cmd) = self.active_command {
-            cmd.state = ExecutionState::Cancelled;
+    /// Move a running batch on to its next spec, or wrap it up
+    fn advance_batch(&mut self, post_hook_failure: Option<String>) {
+        if !matches!(self.batch, Some(ref b) if !b.is_finished()) {
+            return;
         }
+        let Some(outcome) = self.run_outcome(post_hook_failure) else {
+            return;
+        };
+        let cancelled = matches!(
+            self.active_command.as_ref().map(|c| &c.state),
+            Some(ExecutionState::Cancelled { .. })
+        );
 
-        self.output_buffer
-            .push_stderr("Command cancelled by user".to_string());
+        let Some(ref mut batch) = self.batch else {
+            return;
+        };
+        let next = batch.entry_finished(outcome);
+        if cancelled {
+            batch.stop();
+        }
+        match next {
+            Some(spec_id) if !cancelled => self.run_batch_entry(spec_id),
+            _ => self.finish_batch(),
+        }
     }
 
-    /// Poll process output (call this in the event loop)
-    pub fn poll_process_output(&mut self) {
-        if let Some(ref mut handle) = self.process_handle {
-            // Drain all available output
-            while let Some(output) = handle.try_recv() {
-                match output {
-                    ProcessOutput::Stdout(line) => {
-                        self.output_buffer.push_stdout(line);
-                    }
-                    ProcessOutput::Stderr(line) => {
-                        self.output_buffer.push_stderr(line);
-                    }
-                    ProcessOutput::Exit(code) => {
-                        if let Some(ref mut cmd) = self.active_command {
-                            cmd.complete(code);
-                        }
-                        self.output_buffer
-                            .push_stdout(format!("Process exited with code {}", code));
-                    }
-                    ProcessOutput::Terminated => {
-                        if let Some(ref mut cmd) = self.active_command {
-                            cmd.cancel();
-                        }
-                    }
-                    ProcessOutput::Error(e) => {
-                        self.output_buffer.push_stderr(format!("Error: {}", e));
-                        if let Some(ref mut cmd) = self.active_command {
-                            cmd.fail(e);
-                        }
-                    }
-                }
+    /// Print the outcome of every spec in the finished batch
+    fn finish_batch(&mut self) {
+        let Some(ref batch) = self.batch else {
+            return;
+        };
+        let (succeeded, failed, skipped) = batch.counts();
+        self.output_buffer.push_stdout(format!(
+            "Batch {} finished: {} succeeded, {} failed, {} skipped",
+            batch.command.display_name(),
+            succeeded,
+            failed,
+            skipped
+        ));
+        for entry in &batch.entries {
+            let indicator = entry.state.indicator();
+            match entry.state {
+                StepState::Failed(ref reason) => self
+                    .output_buffer
+                    .push_stderr(format!("  {} {}: {}", indicator, entry.spec_id, reason)),
+                StepState::Skipped => self
+                    .output_buffer
+                    .push_stdout(format!("  {} {}: skipped", indicator, entry.spec_id)),
+                _ => self
+                    .output_buffer
+                    .push_stdout(format!("  {} {}", indicator, entry.spec_id)),
             }
         }
     }
 
-    /// Open a document for viewing
-    pub fn open_document_view(&mut self, doc_type: DocType) -> std::result::Result<(), String> {
-        let spec = match self.selected_spec() {
-            Some(s) => s.clone(),
-            None => return Err("No spec selected".to_string()),
-        };
+    /// Start a workflow command, optionally keeping the previous output and
+    /// continuing an earlier agent session
+    fn start_workflow(
+        &mut self,
+        command_type: WorkflowCommandType,
+        spec: &Specification,
+        clear_output: bool,
+        session_id: Option<String>,
+    ) -> std::result::Result<(), String> {
+        // Check if a command is already running
+        if self.is_command_running() {
+            return Err("A command is already running".to_string());
+        }
 
-        let artifact_type = match doc_type {
-            DocType::Spec => ArtifactType::Spec,
-            DocType::Plan => ArtifactType::Plan,
-            DocType::Tasks => ArtifactType::Tasks,
-            DocType::Research => ArtifactType::Research,
-        };
+        if self.workflow_runner.is_none() {
+            return Err("Workflow runner not available".to_string());
+        }
 
-        // Check if artifact exists
-        let has_artifact = match doc_type {
-            DocType::Spec => spec.artifacts.has_spec,
-            DocType::Plan => spec.artifacts.has_plan,
-            DocType::Tasks => spec.artifacts.has_tasks,
-            DocType::Research => spec.artifacts.has_research,
-        };
+        // Changes left unreviewed are kept
+        self.commit_pending();
+
+        // Run inside the spec's worktree so agents never touch the main checkout
+        let worktree = self.ensure_worktree_for_spec(spec)?;
+        let spec_path = self.project.relative_spec_path(&spec.directory);
+        let context = WorkflowContext::new(spec)
+            .in_worktree(worktree, spec_path)
+            .with_env(
+                self.project
+                    .config
+                    .workflow
+                    .env_for(command_type.config_key()),
+            )
+            .with_session(session_id);
+
+        // The spec may not be committed on its branch yet
+        std::fs::create_dir_all(&context.spec_dir)
+            .map_err(|e| format!("Failed to create spec directory in worktree: {}", e))?;
+
+        let hooks = self
+            .project
+            .config
+            .workflow
+            .commands
+            .get(command_type.config_key())
+            .cloned()
+            .unwrap_or_default();
 
-        if !has_artifact {
-            return Err(format!(
-                "{} not found for {}",
-                artifact_type.filename(),
+        // Later pipeline steps are reviewed together with the first
+        if clear_output {
+            self.artifact_snapshot = Some(ArtifactSnapshot::capture(&context.spec_dir));
+            self.artifact_review = DiffViewState::default();
+        }
+
+        self.active_context = Some(context);
+        self.git_run = None;
+        self.post_hooks = hooks.post;
+        if clear_output {
+            self.output_buffer.start();
+        } else {
+            self.output_buffer.push_stdout(format!(
+                "── {}: {} ──",
+                command_type.display_name(),
                 spec.id
             ));
         }
+        self.stdin_input.clear();
+        self.stdin_focused = false;
+        self.view = AppView::CommandOutput;
 
-        // Read the artifact
-        match self.spec_service.read_artifact(&spec.id, artifact_type) {
-            Ok(content) => {
-                self.document_content = Some(content.clone());
-                self.document_viewer_state = DocumentViewerState::new();
-                self.document_viewer_state
-                    .set_total_lines(content.lines().count());
-                self.current_doc_type = Some(doc_type);
-                self.view = AppView::DocumentView(doc_type);
+        // Pre-run hooks start the agent once they all pass
+        if !hooks.pre.is_empty() {
+            let mut command = WorkflowCommand::new(command_type, spec.id.clone());
+            if let (Some(runner), Some(context)) = (&self.workflow_runner, &mut self.active_context)
+            {
+                // Hooks write to the log the first attempt then continues
+                match runner.create_run_log(command_type, context) {
+                    Ok(log) => {
+                        context.log_file = Some(log.clone());
+                        command.log_path = Some(log);
+                    }
+                    Err(e) => tracing::warn!("Failed to create run log: {}", e),
+                }
+            }
+            self.active_command = Some(command);
+            self.hook_run = Some(HookRun::new(HookPhase::Pre, hooks.pre));
+            self.run_next_hook();
+            return Ok(());
+        }
+
+        self.spawn_agent(command_type, 1)
+    }
+
+    /// Start an attempt of the agent process for the active context. A start
+    /// failure covered by the command's retry policy queues another attempt.
+    fn spawn_agent(
+        &mut self,
+        command_type: WorkflowCommandType,
+        attempt: u32,
+    ) -> std::result::Result<(), String> {
+        let context = self
+            .active_context
+            .as_ref()
+            .ok_or_else(|| "No workflow context".to_string())?;
+        let runner = self
+            .workflow_runner
+            .as_ref()
+            .ok_or_else(|| "Workflow runner not available".to_string())?;
+
+        match runner.start_attempt(command_type, context, attempt) {
+            Ok((command, handle)) => {
+                self.active_command = Some(command);
+                self.process_handle = Some(handle);
+                Ok(())
+            }
+            Err(e) => {
+                let policy = runner.retry_policy(command_type);
+                if !policy.retries_error(&e) || !policy.allows_attempt_after(attempt) {
+                    return Err(format!("Failed to start command: {}", e));
+                }
+
+                let spec_id = SpecId::parse(&context.spec_id)
+                    .unwrap_or_else(|_| SpecId::new(0, &context.spec_id));
+                let mut command = WorkflowCommand::new(command_type, spec_id)
+                    .with_attempt(attempt, policy.max_attempts);
+                let delay = policy.delay_before(attempt + 1);
+                command.fail(e.to_string());
+                self.active_command = Some(command);
+                self.schedule_retry(delay, format!("could not start ({})", e));
                 Ok(())
             }
-            Err(e) => Err(format!("Failed to read document: {}", e)),
         }
     }
 
-    /// Open a document for editing
-    pub fn open_document_edit(&mut self, doc_type: DocType) -> std::result::Result<(), String> {
-        let spec = match self.selected_spec() {
-            Some(s) => s.clone(),
-            None => return Err("No spec selected".to_string()),
+    /// Queue the next attempt of the active command after a delay
+    fn schedule_retry(&mut self, delay: Duration, reason: String) {
+        let Some(cmd) = self.active_command.as_ref() else {
+            return;
         };
+        tracing::info!(
+            "{} for {} attempt {}/{} {}; retrying in {:?}",
+            cmd.command_type.config_key(),
+            cmd.spec_id,
+            cmd.attempt,
+            cmd.max_attempts,
+            reason,
+            delay
+        );
+        self.output_buffer.push_stderr(format!(
+            "Attempt {}/{} {}; retrying in {:.1}s (c to stop)",
+            cmd.attempt,
+            cmd.max_attempts,
+            reason,
+            delay.as_secs_f64()
+        ));
+        self.retry_at = Some(Instant::now() + delay);
+    }
 
-        let artifact_type = match doc_type {
-            DocType::Spec => ArtifactType::Spec,
-            DocType::Plan => ArtifactType::Plan,
-            DocType::Tasks => ArtifactType::Tasks,
-            DocType::Research => ArtifactType::Research,
+    /// Queue another attempt if the finished command failed in a way its
+    /// retry policy covers
+    fn retry_failed_attempt(&mut self) -> bool {
+        let Some(cmd) = self.active_command.as_ref() else {
+            return false;
+        };
+        let ExecutionState::Completed { exit_code, .. } = cmd.state else {
+            return false;
         };
+        let Some(policy) = self
+            .workflow_runner
+            .as_ref()
+            .map(|r| r.retry_policy(cmd.command_type))
+        else {
+            return false;
+        };
+        if !policy.retries_exit_code(exit_code) || !policy.allows_attempt_after(cmd.attempt) {
+            return false;
+        }
 
-        // Read the artifact (or create empty if it doesn't exist)
-        let content = self
-            .spec_service
-            .read_artifact(&spec.id, artifact_type)
-            .unwrap_or_default();
+        let delay = policy.delay_before(cmd.attempt + 1);
+        self.schedule_retry(delay, format!("exited with code {}", exit_code));
+        true
+    }
 
-        let file_path = spec.directory.join(artifact_type.filename());
-        let title = format!("{} - {}", artifact_type.filename(), spec.id);
+    /// Start the queued attempt once its backoff has passed
+    fn start_due_retry(&mut self) {
+        if self.retry_at.is_none_or(|at| Instant::now() < at) {
+            return;
+        }
+        self.retry_at = None;
+        let Some((command_type, attempt)) = self
+            .active_command
+            .as_ref()
+            .map(|c| (c.command_type, c.attempt + 1))
+        else {
+            return;
+        };
+
+        // Each attempt gets its own history entry
+        if let Some(record) = self.run_record() {
+            self.append_run(&record);
+        }
+        self.output_buffer.push_stdout(format!(
+            "── {} (attempt {}) ──",
+            command_type.display_name(),
+            attempt
+        ));
+        if let Err(e) = self.spawn_agent(command_type, attempt) {
+            self.output_buffer.push_stderr(e.clone());
+            if let Some(ref mut cmd) = self.active_command {
+                cmd.attempt = attempt;
+                cmd.fail(e);
+            }
+            self.run_post_hooks();
+        }
+    }
+
+    /// Start the next queued hook, or wrap up the hook run if none are left
+    fn run_next_hook(&mut self) {
+        let working_dir = self
+            .active_context
+            .as_ref()
+            .map(|c| c.working_dir().to_path_buf())
+            .unwrap_or_else(|| self.project.root_path.clone());
+        let log_file = self
+            .active_command
+            .as_ref()
+            .and_then(|c| c.log_path.clone());
+
+        loop {
+            let Some(hook_run) = self.hook_run.as_mut() else {
+                return;
+            };
+            let phase = hook_run.phase;
+            let Some(hook) = hook_run.start_next().cloned() else {
+                self.hooks_finished();
+                return;
+            };
+
+            self.output_buffer
+                .push_stdout(format!("$ {} ({})", hook.run, phase.display_name()));
+
+            let result = match &self.workflow_runner {
+                Some(runner) => runner
+                    .spawn_hook(&hook.run, &working_dir, log_file.as_deref())
+                    .map_err(|e| e.to_string()),
+                None => Err("Workflow runner not available".to_string()),
+            };
+            match result {
+                Ok(handle) => {
+                    self.process_handle = Some(handle);
+                    return;
+                }
+                Err(e) => {
+                    if let Some(ref mut hook_run) = self.hook_run {
+                        hook_run.fail_current(&format!("could not start: {}", e));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Record how the running hook ended and move on to the next one
+    fn on_hook_finished(&mut self, exit_code: std::result::Result<i32, String>) {
+        if let Some(ref mut hook_run) = self.hook_run {
+            match exit_code {
+                Ok(code) => {
+                    if !hook_run.finish_current(code) {
+                        self.output_buffer
+                            .push_stderr(format!("Hook exited with code {}", code));
+                    }
+                }
+                Err(reason) => {
+                    self.output_buffer.push_stderr(format!("Hook {}", reason));
+                    hook_run.fail_current(&reason);
+                }
+            }
+        }
+        self.run_next_hook();
+    }
+
+    /// Act on the result of a completed hook run
+    fn hooks_finished(&mut self) {
+        let Some(hook_run) = self.hook_run.take() else {
+            return;
+        };
+        let failure = hook_run
+            .failure()
+            .map(|f| format!("{} {} failed", hook_run.phase.display_name(), f));
+
+        match (hook_run.phase, failure) {
+            (HookPhase::Pre, Some(reason)) => {
+                let message = format!("{}; workflow blocked", reason);
+                self.output_buffer.push_stderr(message.clone());
+                if let Some(ref mut cmd) = self.active_command {
+                    if !cmd.state.is_finished() {
+                        cmd.fail(message);
+                    }
+                }
+                self.finish_run(None);
+            }
+            (HookPhase::Pre, None) => {
+                let Some(command_type) = self.active_command.as_ref().map(|c| c.command_type)
+                else {
+                    return;
+                };
+                if let Err(e) = self.spawn_agent(command_type, 1) {
+                    self.output_buffer.push_stderr(e.clone());
+                    if let Some(ref mut cmd) = self.active_command {
+                        cmd.fail(e);
+                    }
+                    self.finish_run(None);
+                }
+            }
+            (HookPhase::Post, Some(reason)) => {
+                self.output_buffer.push_stderr(reason.clone());
+                self.finish_run(Some(reason));
+            }
+            (HookPhase::Post, None) => self.finish_run(None),
+        }
+    }
+
+    /// Start a pipeline at the workflow selected in the menu
+    pub fn run_selected_pipeline(&mut self) -> std::result::Result<(), String> {
+        let spec = match self.selected_spec() {
+            Some(s) => s.clone(),
+            None => return Err("No spec selected".to_string()),
+        };
+
+        let first = match self.available_workflows.get(self.selected_workflow_index) {
+            Some(t) => *t,
+            None => return Err("No workflow command selected".to_string()),
+        };
+
+        self.run_pipeline(first, &spec)
+    }
+
+    /// Run the configured pipeline for a spec, starting at `first`
+    pub fn run_pipeline(
+        &mut self,
+        first: WorkflowCommandType,
+        spec: &Specification,
+    ) -> std::result::Result<(), String> {
+        let config = &self.project.config.workflow;
+        let steps = parse_commands(&config.pipeline);
+        let gates = parse_commands(&config.review_gates);
+
+        let start = steps
+            .iter()
+            .position(|&c| c == first)
+            .ok_or_else(|| format!("{} is not part of the configured pipeline", first))?;
+
+        self.batch = None;
+        self.pipeline = Some(Pipeline::new(spec.id.clone(), &steps[start..], &gates));
+        if let Err(e) = self.start_workflow(first, spec, true, None) {
+            self.pipeline = None;
+            return Err(e);
+        }
+        if let Some(ref mut pipeline) = self.pipeline {
+            pipeline.start_step();
+        }
+        Ok(())
+    }
+
+    /// Run the pipeline's current step after a previous step finished
+    fn run_pipeline_step(&mut self, command_type: WorkflowCommandType) {
+        let Some(spec_id) = self.pipeline.as_ref().map(|p| p.spec_id.clone()) else {
+            return;
+        };
+
+        let result = match self.specs.iter().find(|s| s.id == spec_id).cloned() {
+            Some(spec) => self.start_workflow(command_type, &spec, false, None),
+            None => Err(format!("Spec {} no longer exists", spec_id)),
+        };
+
+        if let Some(ref mut pipeline) = self.pipeline {
+            match result {
+                Ok(()) => pipeline.start_step(),
+                Err(e) => {
+                    self.output_buffer.push_stderr(e.clone());
+                    pipeline.step_failed(e);
+                }
+            }
+        }
+    }
+
+    /// Continue a pipeline paused at a review gate
+    pub fn approve_pipeline_step(&mut self) {
+        let next = self.pipeline.as_mut().and_then(|p| p.approve());
+        if let Some(command_type) = next {
+            self.run_pipeline_step(command_type);
+        }
+    }
+
+    /// Stop a pipeline paused at a review gate
+    pub fn stop_pipeline(&mut self) {
+        if let Some(ref mut pipeline) = self.pipeline {
+            pipeline.stop();
+            self.output_buffer
+                .push_stdout("Pipeline stopped at review gate".to_string());
+        }
+    }
+
+    /// Check if a pipeline is paused at a review gate
+    pub fn is_pipeline_awaiting_review(&self) -> bool {
+        self.pipeline
+            .as_ref()
+            .is_some_and(|p| p.is_awaiting_review())
+    }
+
+    /// Handle the end of the agent process, running post-run hooks first
+    fn on_command_finished(&mut self) {
+        if self.retry_failed_attempt() {
+            return;
+        }
+        self.run_post_hooks();
+    }
+
+    /// Run the post-run hooks that apply to the command's result, then wrap up
+    fn run_post_hooks(&mut self) {
+        let state = self.active_command.as_ref().map(|c| &c.state);
+        let succeeded = matches!(state, Some(ExecutionState::Completed { exit_code: 0, .. }));
+        let cancelled = matches!(state, Some(ExecutionState::Cancelled { .. }));
+
+        let post: Vec<HookConfig> = std::mem::take(&mut self.post_hooks)
+            .into_iter()
+            .filter(|h| h.applies(succeeded))
+            .collect();
+        if !post.is_empty() && !cancelled {
+            self.hook_run = Some(HookRun::new(HookPhase::Post, post));
+            self.run_next_hook();
+            return;
+        }
+
+        self.finish_run(None);
+    }
+
+    /// Wrap up a workflow run once the agent and its hooks are done
+    fn finish_run(&mut self, post_hook_failure: Option<String>) {
+        if let Err(e) = self.refresh_specs() {
+            tracing::warn!("Failed to refresh specs: {}", e);
+        }
+
+        // A failed post-hook means the run's output is not worth keeping
+        let record = self.run_record();
+        let commit = match post_hook_failure {
+            None => self.prepare_auto_commit(),
+            Some(_) => None,
+        };
+        match commit {
+            Some(commit) => {
+                self.pending_commit = Some(PendingCommit { record, ..commit });
+            }
+            None => {
+                if let Some(record) = record {
+                    self.append_run(&record);
+                }
+            }
+        }
+
+        self.advance_pipeline(post_hook_failure.clone());
+        self.advance_batch(post_hook_failure);
+
+        // Offer a review once nothing else is queued for this run
+        if !self.is_command_running() && self.hook_run.is_none() {
+            self.collect_artifact_changes();
+        }
+
+        // Hold the commit while the changes are reviewed, so reverted files
+        // stay out of it
+        if self.artifact_review.is_empty() {
+            self.commit_pending();
+        } else if self.pending_commit.is_some() {
+            self.output_buffer
+                .push_stdout("Auto-commit waits until the review is done".to_string());
+        }
+    }
+
+    /// Auto-commit for the run, if enabled and the agent exited with 0
+    fn prepare_auto_commit(&self) -> Option<PendingCommit> {
+        let settings = &self.project.config.git.auto_commit;
+        if !settings.enabled {
+            return None;
+        }
+        let cmd = self.active_command.as_ref()?;
+        if !matches!(cmd.state, ExecutionState::Completed { exit_code: 0, .. }) {
+            return None;
+        }
+        let context = self.active_context.as_ref()?;
+        let worktree = context.worktree.clone()?;
+
+        let pathspec = match settings.scope {
+            CommitScope::Spec => Some(context.spec_path.clone()),
+            CommitScope::All => None,
+        };
+        Some(PendingCommit {
+            worktree,
+            pathspec,
+            message: settings.message_for(cmd.command_type.config_key(), &context.spec_id),
+            record: None,
+        })
+    }
+
+    /// Make the held auto-commit, if any, and record its run in the history
+    fn commit_pending(&mut self) {
+        let Some(pending) = self.pending_commit.take() else {
+            return;
+        };
+        let commit = self.auto_commit(&pending);
+        if let Some(mut record) = pending.record {
+            record.commit = commit;
+            self.append_run(&record);
+        }
+    }
+
+    /// Commit the run's changes in the spec's worktree
+    fn auto_commit(&mut self, pending: &PendingCommit) -> Option<String> {
+        let git = self.git_service.as_ref()?;
+        let worktree = pending.worktree.as_path();
+        let pathspec = pending.pathspec.as_deref();
+        let message = &pending.message;
+        let result = git
+            .stage(worktree, pathspec)
+            .and_then(|()| git.has_staged_changes(worktree, pathspec))
+            .and_then(|staged| {
+                if staged {
+                    git.commit(worktree, message, pathspec).map(Some)
+                } else {
+                    Ok(None)
+                }
+            });
+
+        match result {
+            Ok(Some(hash)) => {
+                self.output_buffer.push_stdout(format!(
+                    "Committed {}: {}",
+                    &hash[..hash.len().min(7)],
+                    message
+                ));
+                Some(hash)
+            }
+            Ok(None) => {
+                self.output_buffer
+                    .push_stdout("Nothing to commit".to_string());
+                None
+            }
+            Err(e) => {
+                self.output_buffer
+                    .push_stderr(format!("Auto-commit failed: {}", e));
+                None
+            }
+        }
+    }
+
+    /// History entry for the finished command
+    fn run_record(&self) -> Option<RunRecord> {
+        let mut record = self
+            .active_command
+            .as_ref()
+            .and_then(RunRecord::from_command)?;
+        if let Some(usage) = &record.usage {
+            record.cost_usd = self
+                .project
+                .config
+                .pricing
+                .price_for(usage.model.as_deref())
+                .map(|pricing| pricing.cost(usage.input_tokens, usage.output_tokens));
+        }
+        Some(record)
+    }
+
+    /// Append a finished command to the run history
+    fn append_run(&self, record: &RunRecord) {
+        if let Err(e) = self.run_history.append(record) {
+            tracing::warn!("Failed to record run history: {}", e);
+        }
+    }
+
+    /// Move a running pipeline on to its next step, or stop it
+    fn advance_pipeline(&mut self, post_hook_failure: Option<String>) {
+        if !matches!(self.pipeline, Some(ref p) if !p.is_finished()) {
+            return;
+        }
+        let Some(command_type) = self.active_command.as_ref().map(|c| c.command_type) else {
+            return;
+        };
+        let Some(outcome) = self.run_outcome(post_hook_failure) else {
+            return;
+        };
+
+        let Some(ref mut pipeline) = self.pipeline else {
+            return;
+        };
+        match outcome {
+            Ok(()) => match pipeline.step_succeeded() {
+                Some(next) => self.run_pipeline_step(next),
+                None if pipeline.is_awaiting_review() => {
+                    self.output_buffer.push_stdout(format!(
+                        "Review {} output, then press n to continue or s to stop",
+                        command_type.display_name()
+                    ));
+                }
+                None => {
+                    self.output_buffer
+                        .push_stdout("Pipeline completed".to_string());
+                }
+            },
+            Err(reason) => {
+                self.output_buffer.push_stderr(format!(
+                    "Pipeline stopped: {} {}",
+                    command_type.display_name(),
+                    reason
+                ));
+                pipeline.step_failed(reason);
+            }
+        }
+    }
+
+    /// Judge the finished command: it must exit with 0, leave its artifact
+    /// in the worktree and pass its post-hooks
+    fn run_outcome(
+        &self,
+        post_hook_failure: Option<String>,
+    ) -> Option<std::result::Result<(), String>> {
+        let cmd = self.active_command.as_ref()?;
+        let outcome = match &cmd.state {
+            ExecutionState::Completed { exit_code: 0, .. } => {
+                // Confirm the step produced its artifact in the worktree
+                let artifacts = self
+                    .active_context
+                    .as_ref()
+                    .map(|c| SpecArtifacts::scan(&c.spec_dir))
+                    .unwrap_or_default();
+                match cmd.command_type.expected_artifact() {
+                    Some(artifact) if !artifacts.has(artifact) => {
+                        Err(format!("{} was not created", artifact.filename()))
+                    }
+                    _ => Ok(()),
+                }
+            }
+            ExecutionState::Completed { exit_code, .. } => {
+                Err(format!("exited with code {}", exit_code))
+            }
+            ExecutionState::Cancelled { outcome } => {
+                Err(format!("cancelled ({})", outcome.display_name()))
+            }
+            ExecutionState::Failed { error, .. } => Err(error.clone()),
+            _ => return None,
+        };
+        Some(match (outcome, post_hook_failure) {
+            (Ok(()), Some(reason)) => Err(reason),
+            (outcome, _) => outcome,
+        })
+    }
+
+    /// Diff the spec directory against the snapshot taken before the run
+    fn collect_artifact_changes(&mut self) {
+        let Some(ref snapshot) = self.artifact_snapshot else {
+            return;
+        };
+
+        let changes = snapshot.changes();
+        if changes.is_empty() {
+            return;
+        }
+
+        self.output_buffer.push_stdout(format!(
+            "{} artifact file(s) changed, press d to review",
+            changes.len()
+        ));
+        self.artifact_review = DiffViewState::new(changes);
+    }
+
+    /// Open the review of artifact changes made by the last run
+    pub fn open_artifact_review(&mut self) {
+        if !self.artifact_review.is_empty() {
+            self.view = AppView::ArtifactReview;
+        }
+    }
+
+    /// Keep the selected file's changes and drop it from the review
+    pub fn accept_artifact_change(&mut self) {
+        if let Some(file) = self.artifact_review.remove_selected() {
+            self.output_buffer
+                .push_stdout(format!("Accepted {}", file.path.display()));
+        }
+        self.close_artifact_review_if_done();
+    }
+
+    /// Keep all remaining changes and close the review
+    pub fn accept_all_artifact_changes(&mut self) {
+        let count = self.artifact_review.files.len();
+        self.artifact_review = DiffViewState::default();
+        self.output_buffer
+            .push_stdout(format!("Accepted {} file(s)", count));
+        self.close_artifact_review_if_done();
+    }
+
+    /// Restore the selected file to its content from before the run
+    pub fn revert_artifact_change(&mut self) -> std::result::Result<(), String> {
+        let snapshot = self
+            .artifact_snapshot
+            .as_ref()
+            .ok_or_else(|| "No snapshot to revert to".to_string())?;
+        let path = match self.artifact_review.selected_file() {
+            Some(file) => file.path.clone(),
+            None => return Ok(()),
+        };
+        // Restoring the file would leave the commit in the history
+        if self.is_change_committed(&path) {
+            return Err(format!(
+                "{} is already committed, revert it with git instead",
+                path.display()
+            ));
+        }
+
+        snapshot
+            .restore(&path)
+            .map_err(|e| format!("Failed to revert {}: {}", path.display(), e))?;
+
+        self.artifact_review.remove_selected();
+        self.output_buffer
+            .push_stdout(format!("Reverted {}", path.display()));
+        if let Err(e) = self.refresh_specs() {
+            tracing::warn!("Failed to refresh specs: {}", e);
+        }
+        self.close_artifact_review_if_done();
+        Ok(())
+    }
+
+    /// Check if a reviewed file has no uncommitted changes left, i.e. what
+    /// the run changed in it was committed, by an earlier pipeline step or
+    /// the agent itself
+    fn is_change_committed(&self, path: &std::path::Path) -> bool {
+        let (Some(context), Some(git)) = (self.active_context.as_ref(), self.git_service.as_ref())
+        else {
+            return false;
+        };
+        let Some(worktree) = context.worktree.as_ref() else {
+            return false;
+        };
+        let path = context.spec_path.join(path);
+        git.changed_files(worktree)
+            .is_ok_and(|files| !files.iter().any(|f| f.path == path))
+    }
+
+    /// Open the selected changed file in the editor
+    pub fn edit_artifact_change(&mut self) -> std::result::Result<(), String> {
+        let snapshot = self
+            .artifact_snapshot
+            .as_ref()
+            .ok_or_else(|| "No snapshot for this run".to_string())?;
+        let file = self
+            .artifact_review
+            .selected_file()
+            .ok_or_else(|| "No file selected".to_string())?;
+
+        let file_path = snapshot.directory().join(&file.path);
+        let content = std::fs::read_to_string(&file_path)
+            .map_err(|e| format!("Failed to open {}: {}", file.path.display(), e))?;
+        let title = file.path.display().to_string();
+
+        self.editor_state.open(content, title, file_path);
+        self.editor_return_view = Some(AppView::ArtifactReview);
+        self.input_mode = InputMode::Insert;
+        self.view = AppView::FileEdit;
+        Ok(())
+    }
+
+    /// Refresh the selected file's diff after it was edited
+    fn refresh_artifact_review(&mut self) {
+        let (Some(snapshot), Some(file)) = (
+            self.artifact_snapshot.as_ref(),
+            self.artifact_review.selected_file(),
+        ) else {
+            return;
+        };
+
+        match snapshot.change(&file.path) {
+            Some(diff) => self.artifact_review.files[self.artifact_review.selected] = diff,
+            None => {
+                self.artifact_review.remove_selected();
+            }
+        }
+        self.close_artifact_review_if_done();
+    }
+
+    fn close_artifact_review_if_done(&mut self) {
+        if !self.artifact_review.is_empty() {
+            return;
+        }
+        // Commit only the changes that were kept
+        self.commit_pending();
+        if self.view == AppView::ArtifactReview {
+            self.view = AppView::CommandOutput;
+        }
+    }
+
+    /// Check if a command is currently running
+    pub fn is_command_running(&self) -> bool {
+        self.retry_at.is_some() || self.process_handle.as_ref().is_some_and(|h| h.is_running())
+    }
+
+    /// Check if the running command accepts input on stdin
+    pub fn accepts_stdin(&self) -> bool {
+        self.process_handle.as_ref().is_some_and(|h| h.has_stdin())
+    }
+
+    /// Send the stdin input line to the running command
+    pub fn send_stdin(&mut self) -> std::result::Result<(), String> {
+        let handle = match self.process_handle.as_mut() {
+            Some(h) => h,
+            None => return Err("No command is running".to_string()),
+        };
+
+        let line = self.stdin_input.value().to_string();
+        handle.write_stdin(&line).map_err(|e| e.to_string())?;
+        self.output_buffer.push_stdin(line);
+        self.stdin_input.clear();
+        Ok(())
+    }
+
+    /// Close the running command's stdin
+    pub fn close_stdin(&mut self) {
+        if let Some(ref mut handle) = self.process_handle {
+            handle.close_stdin();
+        }
+        self.stdin_focused = false;
+        self.input_mode = InputMode::Normal;
+        self.output_buffer.push_stdin("<EOF>".to_string());
+    }
+
+    /// Cancel the running command.
+    ///
+    /// Cancellation escalates in the background; the final outcome is picked
+    /// up by [`App::poll_process_output`].
+    pub fn cancel_command(&mut self) {
+        // Between attempts there is no process; stop retrying instead
+        if self.retry_at.take().is_some() {
+            self.output_buffer
+                .push_stderr("Retry cancelled by user".to_string());
+            self.run_post_hooks();
+            return;
+        }
+
+        let Some(ref handle) = self.process_handle else {
+            return;
+        };
+        if handle.is_cancelling() {
+            return;
+        }
+
+        let policy = self
+            .workflow_runner
+            .as_ref()
+            .map(|r| r.cancel_policy())
+            .unwrap_or_default();
+        handle.cancel(policy);
+
+        if let Some(ref mut cmd) = self.active_command {
+            cmd.begin_cancel();
+        }
+
+        self.output_buffer
+            .push_stderr("Cancelling command (SIGINT)...".to_string());
+    }
+
+    /// Poll process output (call this in the event loop)
+    pub fn poll_process_output(&mut self) {
+        self.start_due_retry();
+
+        // Drain all available output
+        let events: Vec<ProcessOutput> = match self.process_handle.as_mut() {
+            Some(handle) => std::iter::from_fn(|| handle.try_recv()).collect(),
+            None => return,
+        };
+
+        let mut finished = false;
+        for output in events {
+            match output {
+                ProcessOutput::Stdout(line) => {
+                    self.output_buffer.push_stdout(line);
+                }
+                ProcessOutput::Stderr(line) => {
+                    self.output_buffer.push_stderr(line);
+                }
+                ProcessOutput::Event(event) => {
+                    if let Some(cmd) = self.active_command.as_mut() {
+                        match &event {
+                            AgentEvent::Usage(usage) => cmd.record_usage(usage),
+                            AgentEvent::Session(id) => cmd.session_id = Some(id.clone()),
+                            _ => {}
+                        }
+                    }
+                    self.output_buffer.push_event(event);
+                }
+                // Git processes started from the worktree manager
+                ProcessOutput::Exit(code) if self.is_git_op_running() => {
+                    self.on_git_op_finished(Ok(code));
+                }
+                ProcessOutput::Cancelled(outcome) if self.is_git_op_running() => {
+                    self.on_git_op_finished(Err(format!(
+                        "was cancelled ({})",
+                        outcome.display_name()
+                    )));
+                }
+                ProcessOutput::Terminated if self.is_git_op_running() => {
+                    self.on_git_op_finished(Err("was terminated".to_string()));
+                }
+                ProcessOutput::Error(e) if self.is_git_op_running() => {
+                    self.on_git_op_finished(Err(format!("failed: {}", e)));
+                }
+                // Hook processes report to the hook run rather than the command
+                ProcessOutput::Exit(code) if self.hook_run.is_some() => {
+                    self.on_hook_finished(Ok(code));
+                }
+                ProcessOutput::Cancelled(outcome) if self.hook_run.is_some() => {
+                    let pre = self
+                        .hook_run
+                        .as_ref()
+                        .is_some_and(|h| h.phase == HookPhase::Pre);
+                    if let Some(ref mut cmd) = self.active_command {
+                        if pre {
+                            cmd.cancel(outcome);
+                        }
+                    }
+                    self.on_hook_finished(Err(format!(
+                        "was cancelled ({})",
+                        outcome.display_name()
+                    )));
+                }
+                ProcessOutput::Terminated if self.hook_run.is_some() => {
+                    self.on_hook_finished(Err("was terminated".to_string()));
+                }
+                ProcessOutput::Error(e) if self.hook_run.is_some() => {
+                    self.on_hook_finished(Err(format!("failed: {}", e)));
+                }
+                ProcessOutput::Exit(code) => {
+                    if let Some(ref mut cmd) = self.active_command {
+                        cmd.complete(code);
+                    }
+                    self.output_buffer
+                        .push_stdout(format!("Process exited with code {}", code));
+                    finished = true;
+                }
+                ProcessOutput::Cancelled(outcome) => {
+                    if let Some(ref mut cmd) = self.active_command {
+                        cmd.cancel(outcome);
+                    }
+                    self.output_buffer.push_stderr(format!(
+                        "Command cancelled by user ({})",
+                        outcome.display_name()
+                    ));
+                    finished = true;
+                }
+                ProcessOutput::Terminated => {
+                    if let Some(ref mut cmd) = self.active_command {
+                        cmd.cancel(CancelOutcome::Killed);
+                    }
+                    finished = true;
+                }
+                ProcessOutput::Error(e) => {
+                    self.output_buffer.push_stderr(format!("Error: {}", e));
+                    if let Some(ref mut cmd) = self.active_command {
+                        cmd.fail(e);
+                    }
+                    finished = true;
+                }
+            }
+        }
 
-        self.editor_state.open(content, title, file_path);
-        self.current_doc_type = Some(doc_type);
-        self.input_mode = InputMode::Insert;
-        self.view = AppView::DocumentEdit(doc_type);
-        Ok(())
+        if finished {
+            if self.stdin_focused {
+                self.stdin_focused = false;
+                self.input_mode = InputMode::Normal;
+            }
+            self.prune_logs();
+            self.on_command_finished();
+        }
     }
 
-    /// Save the current document being edited
-    pub fn save_document(&mut self) -> std::result::Result<(), String> {
+    /// Open a document for viewing
+    pub fn open_document_view(&mut self, doc_type: DocType) -> std::result::Result<(), String> {
         let spec = match self.selected_spec() {
             Some(s) => s.clone(),
             None => return Err("No spec selected".to_string()),
         };
 
-        let doc_type = match self.current_doc_type {
-            Some(dt) => dt,
-            None => return Err("No document type set".to_string()),
+        let artifact_type = match doc_type {
+            DocType::Spec => ArtifactType::Spec,
+            DocType::Plan => ArtifactType::Plan,
+            DocType::Tasks => ArtifactType::Tasks,
+            DocType::Research => ArtifactType::Research,
         };
 
-        let content = match self.editor_state.content() {
-            Some(c) => c,
-            None => return Err("No content to save".to_string()),
+        // Check if artifact exists
+        let has_artifact = match doc_type {
+            DocType::Spec => spec.artifacts.has_spec,
+            DocType::Plan => spec.artifacts.has_plan,
+            DocType::Tasks => spec.artifacts.has_tasks,
+            DocType::Research => spec.artifacts.has_research,
+        };
+
+        if !has_artifact {
+            return Err(format!(
+                "{} not found for {}",
+                artifact_type.filename(),
+                spec.id
+            ));
+        }
+
+        // Read the artifact
+        match self.read_spec_artifact(&spec, artifact_type) {
+            Ok(content) => {
+                self.document_content = Some(content.clone());
+                self.document_viewer_state = DocumentViewerState::new();
+                self.document_viewer_state
+                    .set_total_lines(content.lines().count());
+                self.current_doc_type = Some(doc_type);
+                self.view = AppView::DocumentView(doc_type);
+                Ok(())
+            }
+            Err(e) => Err(format!("Failed to read document: {}", e)),
+        }
+    }
+
+    /// Open a document for editing
+    pub fn open_document_edit(&mut self, doc_type: DocType) -> std::result::Result<(), String> {
+        let spec = match self.selected_spec() {
+            Some(s) => s.clone(),
+            None => return Err("No spec selected".to_string()),
         };
 
         let artifact_type = match doc_type {
@@ -528,15 +1976,43 @@ impl App {
             DocType::Research => ArtifactType::Research,
         };
 
-        match self
-            .spec_service
-            .write_artifact(&spec.id, artifact_type, &content)
-        {
+        // Read the artifact (or create empty if it doesn't exist)
+        let content = self
+            .read_spec_artifact(&spec, artifact_type)
+            .unwrap_or_default();
+
+        let file_path = self.spec_artifact_dir(&spec).join(artifact_type.filename());
+        let title = format!("{} - {}", artifact_type.filename(), spec.id);
+
+        self.editor_state.open(content, title, file_path);
+        self.current_doc_type = Some(doc_type);
+        self.input_mode = InputMode::Insert;
+        self.view = AppView::DocumentEdit(doc_type);
+        Ok(())
+    }
+
+    /// Save the current document being edited
+    pub fn save_document(&mut self) -> std::result::Result<(), String> {
+        let file_path = match self.editor_state.file_path() {
+            Some(p) => p.clone(),
+            None => return Err("No document open".to_string()),
+        };
+
+        let content = match self.editor_state.content() {
+            Some(c) => c,
+            None => return Err("No content to save".to_string()),
+        };
+
+        // Write to the opened path, which may be inside a worktree
+        match std::fs::write(&file_path, &content) {
             Ok(()) => {
                 self.editor_state.mark_saved();
                 self.loading_message = Some("Document saved".to_string());
                 // Refresh specs to update artifact status
                 let _ = self.refresh_specs();
+                if self.editor_return_view == Some(AppView::ArtifactReview) {
+                    self.refresh_artifact_review();
+                }
                 Ok(())
             }
             Err(e) => Err(format!("Failed to save: {}", e)),
@@ -555,7 +2031,10 @@ impl App {
         self.editor_state.close();
         self.current_doc_type = None;
         self.input_mode = InputMode::Normal;
-        self.view = AppView::Overview;
+        self.view = self.editor_return_view.take().unwrap_or(AppView::Overview);
+        if self.view == AppView::ArtifactReview && self.artifact_review.is_empty() {
+            self.view = AppView::CommandOutput;
+        }
     }
 
     /// Open worktree management view
@@ -565,16 +2044,13 @@ impl App {
         self.view = AppView::WorktreeManagement;
     }
 
-    /// Refresh worktree sync statuses
+    /// Refresh worktree sync statuses in the background
     pub fn refresh_worktree_sync_statuses(&mut self) {
-        self.worktree_sync_statuses.clear();
-        if let Some(ref git) = self.git_service {
-            for wt in &self.worktrees {
-                if let Ok(sync_status) = git.sync_status(&wt.branch) {
-                    self.worktree_sync_statuses
-                        .insert(wt.branch.clone(), sync_status);
-                }
-            }
+        let worktrees = &self.worktrees;
+        self.worktree_sync_statuses
+            .retain(|branch, _| worktrees.iter().any(|w| &w.branch == branch));
+        if let Some(ref mut worker) = self.status_worker {
+            worker.request(&self.worktrees, true);
         }
     }
 
@@ -584,24 +2060,41 @@ impl App {
             .get(self.worktree_management_state.selected_index)
     }
 
-    /// Request deletion of the selected worktree
+    /// Request deletion of the selected worktree, offering to delete its
+    /// branch too
     pub fn request_worktree_delete(&mut self) {
-        if let Some(wt) = self.selected_worktree() {
-            if wt.is_main {
-                self.error_message = Some("Cannot delete the main worktree".to_string());
-                return;
+        let Some(wt) = self.selected_worktree().cloned() else {
+            return;
+        };
+        if wt.is_main {
+            self.error_message = Some("Cannot delete the main worktree".to_string());
+            return;
+        }
+        self.worktree_management_state.request_delete(wt.path);
+
+        let main_branch = &self.project.config.git.main_branch;
+        if let Some(ref git) = self.git_service {
+            if !wt.is_detached && wt.branch != *main_branch {
+                let safety = git
+                    .branch_safety(&wt.branch, main_branch)
+                    .inspect_err(|e| tracing::warn!("Failed to check {}: {}", wt.branch, e))
+                    .ok();
+                self.worktree_management_state
+                    .offer_branch_delete(wt.branch, safety);
             }
-            self.worktree_management_state
-                .request_delete(wt.path.clone());
         }
     }
 
-    /// Perform worktree deletion (called after confirmation)
-    pub fn delete_worktree(&mut self, force: bool) -> std::result::Result<(), String> {
-        let path = match self.worktree_management_state.pending_delete.take() {
-            Some(p) => p,
-            None => return Err("No worktree pending deletion".to_string()),
-        };
+    /// Perform worktree deletion (called after confirmation), deleting the
+    /// branch too if that was chosen in the dialog
+    pub fn delete_worktree(
+        &mut self,
+        path: PathBuf,
+        force: bool,
+    ) -> std::result::Result<(), String> {
+        // Taken up front so a failed deletion never leaves the branch choice
+        // behind for a later one
+        let branch = self.worktree_management_state.take_branch_delete();
 
         let git = match &self.git_service {
             Some(g) => g,
@@ -611,6 +2104,20 @@ impl App {
         match git.delete_worktree(&path, force) {
             Ok(()) => {
                 self.loading_message = Some("Worktree deleted".to_string());
+                if let Some(branch) = branch {
+                    let recovery = git
+                        .delete_branch(&branch)
+                        .map_err(|e| format!("Worktree deleted, but not branch {}: {}", branch, e));
+                    match recovery {
+                        Ok(recovery) => {
+                            self.loading_message = Some(format!(
+                                "Worktree and branch deleted; restore with: git branch {} {}",
+                                branch, recovery
+                            ))
+                        }
+                        Err(e) => self.error_message = Some(e),
+                    }
+                }
                 self.refresh_worktrees();
                 // Adjust selection if needed
                 if self.worktree_management_state.selected_index >= self.worktrees.len()
@@ -624,6 +2131,518 @@ impl App {
         }
     }
 
+    /// Prune worktrees whose directories are gone, then propose removing the
+    /// clean worktrees whose branches are merged into the main branch
+    pub fn clean_up_worktrees(&mut self) -> std::result::Result<(), String> {
+        let git = self
+            .git_service
+            .clone()
+            .ok_or_else(|| "Git service not available".to_string())?;
+        let pruned = git
+            .prune_worktrees()
+            .map_err(|e| format!("Failed to prune worktrees: {}", e))?;
+        self.refresh_worktrees();
+
+        let main_branch = &self.project.config.git.main_branch;
+        let merged: Vec<Worktree> = self
+            .worktrees
+            .iter()
+            .filter(|wt| !wt.is_main && wt.flags().is_empty())
+            .filter(|wt| git.is_merged(&wt.branch, main_branch).unwrap_or(false))
+            .filter(|wt| git.worktree_status(&wt.path).is_ok_and(|s| s.is_clean()))
+            .cloned()
+            .collect();
+
+        let pruned = match pruned.len() {
+            0 => "No missing worktrees to prune".to_string(),
+            n => format!("Pruned {} missing worktrees", n),
+        };
+        if merged.is_empty() {
+            self.loading_message = Some(format!("{}, none merged into {}", pruned, main_branch));
+        } else {
+            self.loading_message = Some(pruned);
+            self.worktree_management_state.request_cleanup(merged);
+        }
+        Ok(())
+    }
+
+    /// Remove worktrees confirmed in a cleanup, keeping their branches
+    pub fn remove_merged_worktrees(
+        &mut self,
+        worktrees: Vec<Worktree>,
+    ) -> std::result::Result<(), String> {
+        let git = self
+            .git_service
+            .clone()
+            .ok_or_else(|| "Git service not available".to_string())?;
+
+        let mut failed = Vec::new();
+        for wt in &worktrees {
+            if let Err(e) = git.delete_worktree(&wt.path, false) {
+                failed.push(format!("{}: {}", wt.branch, e));
+            }
+        }
+        self.refresh_worktrees();
+        let count = self.worktrees.len();
+        let state = &mut self.worktree_management_state;
+        state.selected_index = state.selected_index.min(count.saturating_sub(1));
+
+        if !failed.is_empty() {
+            return Err(format!("Failed to remove worktrees: {}", failed.join("; ")));
+        }
+        self.loading_message = Some(format!("Removed {} merged worktrees", worktrees.len()));
+        Ok(())
+    }
+
+    /// Run a git operation on the selected worktree's branch, streaming
+    /// git's output into the output panel
+    pub fn run_git_op(&mut self, op: GitOp) -> std::result::Result<(), String> {
+        let wt = self
+            .selected_worktree()
+            .cloned()
+            .ok_or_else(|| "No worktree selected".to_string())?;
+        self.start_git_op(op, wt.path, wt.branch)
+    }
+
+    /// Merge or rebase the selected worktree's branch onto the main branch,
+    /// as configured by `git.update_mode`
+    pub fn update_from_main(&mut self) -> std::result::Result<(), String> {
+        self.ensure_git_op_allowed()?;
+        let wt = self
+            .selected_worktree()
+            .cloned()
+            .ok_or_else(|| "No worktree selected".to_string())?;
+        let main = self.project.config.git.main_branch.clone();
+        if wt.branch == main {
+            return Err(format!("Worktree is already on {}", main));
+        }
+        let git = self
+            .git_service
+            .as_ref()
+            .ok_or_else(|| "Git service not available".to_string())?;
+
+        if let Some(mode) = git
+            .update_in_progress(&wt.path)
+            .map_err(|e| e.to_string())?
+        {
+            return Err(format!(
+                "A {} is already in progress; continue or abort it first",
+                mode.command()
+            ));
+        }
+        // Aborting only restores the branch cleanly if nothing was uncommitted
+        let status = git
+            .worktree_status(&wt.path)
+            .map_err(|e| format!("Failed to get worktree status: {}", e))?;
+        if status.is_dirty() {
+            return Err(format!(
+                "Worktree has uncommitted changes ({}); commit or stash them first",
+                status
+            ));
+        }
+
+        let op = GitOp::Update {
+            mode: self.project.config.git.update_mode,
+            onto: main,
+        };
+        self.start_git_op(op, wt.path, wt.branch)
+    }
+
+    /// Stage resolved conflicts and continue the merge or rebase
+    pub fn continue_update(&mut self) -> std::result::Result<(), String> {
+        self.ensure_git_op_allowed()?;
+        let (path, branch) = self.update_target()?;
+        let git = self
+            .git_service
+            .as_ref()
+            .ok_or_else(|| "Git service not available".to_string())?;
+
+        let mode = git
+            .update_in_progress(&path)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "No merge or rebase in progress".to_string())?;
+        let unresolved = git
+            .stage_resolved(&path)
+            .map_err(|e| format!("Failed to stage resolved files: {}", e))?;
+        if !unresolved.is_empty() {
+            let files: Vec<String> = unresolved.iter().map(|f| f.display().to_string()).collect();
+            return Err(format!("Unresolved conflicts in {}", files.join(", ")));
+        }
+
+        self.start_git_op(GitOp::Continue(mode), path, branch)
+    }
+
+    /// Abort the merge or rebase, restoring the branch
+    pub fn abort_update(&mut self) -> std::result::Result<(), String> {
+        self.ensure_git_op_allowed()?;
+        let (path, branch) = self.update_target()?;
+        let mode = self
+            .git_service
+            .as_ref()
+            .ok_or_else(|| "Git service not available".to_string())?
+            .update_in_progress(&path)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "No merge or rebase in progress".to_string())?;
+
+        self.start_git_op(GitOp::Abort(mode), path, branch)
+    }
+
+    /// Worktree to continue or abort an update in: the one of the last git
+    /// operation while its output is shown, otherwise the selected one
+    fn update_target(&self) -> std::result::Result<(PathBuf, String), String> {
+        match self.git_run {
+            Some(ref run) if self.view == AppView::CommandOutput => {
+                Ok((run.path.clone(), run.branch.clone()))
+            }
+            _ => self
+                .selected_worktree()
+                .map(|wt| (wt.path.clone(), wt.branch.clone()))
+                .ok_or_else(|| "No worktree selected".to_string()),
+        }
+    }
+
+    /// Check that nothing else is using the output panel
+    fn ensure_git_op_allowed(&self) -> std::result::Result<(), String> {
+        if self.is_command_running() || self.hook_run.is_some() {
+            return Err("A command is already running".to_string());
+        }
+        if self.is_pipeline_awaiting_review() {
+            return Err("A pipeline is waiting for review".to_string());
+        }
+        Ok(())
+    }
+
+    /// Start git in a worktree and show its output
+    fn start_git_op(
+        &mut self,
+        op: GitOp,
+        path: PathBuf,
+        branch: String,
+    ) -> std::result::Result<(), String> {
+        self.ensure_git_op_allowed()?;
+        if branch == "(detached)" {
+            return Err("Worktree has no branch checked out".to_string());
+        }
+        let runner = self
+            .workflow_runner
+            .as_ref()
+            .ok_or_else(|| "Workflow runner not available".to_string())?;
+
+        let args = op.git_args(&branch);
+        let handle = runner
+            .spawn_git(&args, &path)
+            .map_err(|e| format!("Failed to start git: {}", e))?;
+
+        self.output_buffer.start();
+        self.output_buffer
+            .push_stdout(format!("$ git {} ({})", args.join(" "), path.display()));
+        self.commit_pending();
+        self.active_command = None;
+        self.artifact_review = DiffViewState::default();
+        self.pipeline = None;
+        self.batch = None;
+        self.stdin_focused = false;
+        self.git_run = Some(GitRun::new(op, branch, path));
+        self.process_handle = Some(handle);
+        self.view = AppView::CommandOutput;
+        Ok(())
+    }
+
+    /// Check if a git operation is running
+    fn is_git_op_running(&self) -> bool {
+        self.git_run.as_ref().is_some_and(|run| run.is_running())
+    }
+
+    /// Check if the last git operation stopped on conflicts
+    pub fn has_update_conflicts(&self) -> bool {
+        !self.is_command_running() && self.git_run.as_ref().is_some_and(|run| run.has_conflicts())
+    }
+
+    /// Record how git ended, list any conflicts it stopped on and pick up
+    /// the new worktree and sync state
+    fn on_git_op_finished(&mut self, exit_code: std::result::Result<i32, String>) {
+        let conflicts = match (&self.git_run, &self.git_service) {
+            (Some(run), Some(git)) if run.op.can_conflict() && exit_code != Ok(0) => {
+                git.conflicted_files(&run.path).unwrap_or_default()
+            }
+            _ => Vec::new(),
+        };
+        let Some(ref mut run) = self.git_run else {
+            return;
+        };
+        match exit_code {
+            Ok(code) => run.finish(code),
+            Err(reason) => run.fail(reason),
+        }
+        run.conflicts = conflicts;
+
+        match run.result {
+            Some(Err(ref reason)) => self.output_buffer.push_stderr(format!(
+                "{} of {} failed: git {}",
+                run.op.display_name(),
+                run.branch,
+                reason
+            )),
+            _ => self.output_buffer.push_stdout(format!(
+                "{} of {} finished",
+                run.op.display_name(),
+                run.branch
+            )),
+        }
+        if run.has_conflicts() {
+            self.output_buffer
+                .push_stderr(format!("Conflicts in {} files:", run.conflicts.len()));
+            for file in &run.conflicts {
+                self.output_buffer
+                    .push_stderr(format!("  {}", file.display()));
+            }
+            self.output_buffer
+                .push_stdout("Resolve them, then press C to continue or A to abort".to_string());
+        }
+
+        self.refresh_worktree_statuses();
+        self.refresh_worktree_sync_statuses();
+    }
+
+    /// Open the diff view for the selected worktree, starting with its
+    /// uncommitted changes
+    pub fn open_worktree_diff(&mut self) -> std::result::Result<(), String> {
+        let wt = self
+            .selected_worktree()
+            .cloned()
+            .ok_or_else(|| "No worktree selected".to_string())?;
+        self.diff_worktree = Some(wt);
+        self.worktree_diff_scope = DiffScope::WorkingTree;
+        self.worktree_diff = DiffViewState::default();
+        self.load_worktree_diff()?;
+        self.view = AppView::WorktreeDiff;
+        Ok(())
+    }
+
+    /// Switch the diff view between uncommitted changes and the branch's
+    /// changes against the main branch
+    pub fn toggle_worktree_diff_scope(&mut self) -> std::result::Result<(), String> {
+        self.worktree_diff_scope = self.worktree_diff_scope.toggle();
+        self.worktree_diff = DiffViewState::default();
+        self.load_worktree_diff()
+    }
+
+    /// Load the diff view's changes, keeping the selected file if it is
+    /// still changed
+    pub fn load_worktree_diff(&mut self) -> std::result::Result<(), String> {
+        let wt = self
+            .diff_worktree
+            .as_ref()
+            .ok_or_else(|| "No worktree selected".to_string())?;
+        let git = self
+            .git_service
+            .as_ref()
+            .ok_or_else(|| "Git service not available".to_string())?;
+
+        let files = match self.worktree_diff_scope {
+            DiffScope::WorkingTree => git.working_tree_diff(&wt.path),
+            DiffScope::Branch => git.branch_diff(&wt.path, &self.project.config.git.main_branch),
+        }
+        .map_err(|e| format!("Failed to diff worktree: {}", e))?;
+
+        let selected = self.worktree_diff.selected_file().map(|f| f.path.clone());
+        self.worktree_diff = DiffViewState::new(files);
+        if let Some(path) = selected {
+            self.worktree_diff.select_path(&path);
+        }
+        Ok(())
+    }
+
+    /// Close the diff view, returning to the worktree manager
+    pub fn close_worktree_diff(&mut self) {
+        self.diff_worktree = None;
+        self.worktree_diff = DiffViewState::default();
+        self.view = AppView::WorktreeManagement;
+    }
+
+    /// Open the commit view for the worktree shown in the diff view, or
+    /// the selected worktree
+    pub fn open_commit_view(&mut self) -> std::result::Result<(), String> {
+        let wt = match self.view {
+            AppView::WorktreeDiff => self.diff_worktree.clone(),
+            _ => self.selected_worktree().cloned(),
+        }
+        .ok_or_else(|| "No worktree selected".to_string())?;
+
+        self.commit_view = CommitViewState::new(Vec::new(), &commit_prefill(&wt));
+        self.commit_worktree = Some(wt);
+        self.load_commit_files()?;
+        self.commit_return_view = Some(self.view.clone());
+        self.view = AppView::CommitView;
+        Ok(())
+    }
+
+    /// Load the commit view's changed files
+    pub fn load_commit_files(&mut self) -> std::result::Result<(), String> {
+        let (wt, git) = self.commit_target()?;
+        let files = git
+            .changed_files(&wt.path)
+            .map_err(|e| format!("Failed to read changed files: {}", e))?;
+        self.commit_view.set_files(files);
+        Ok(())
+    }
+
+    /// Stage the selected file, or unstage it if all of its changes are
+    /// already staged
+    pub fn toggle_commit_staging(&mut self) -> std::result::Result<(), String> {
+        let file = self
+            .commit_view
+            .selected_file()
+            .cloned()
+            .ok_or_else(|| "No file selected".to_string())?;
+        let (wt, git) = self.commit_target()?;
+        if file.is_fully_staged() {
+            git.unstage(&wt.path, &file.path)
+        } else {
+            git.stage(&wt.path, Some(&file.path))
+        }
+        .map_err(|e| format!("Failed to update {}: {}", file.path.display(), e))?;
+        self.load_commit_files()
+    }
+
+    /// Stage all changes in the commit view's worktree
+    pub fn stage_all_commit_files(&mut self) -> std::result::Result<(), String> {
+        let (wt, git) = self.commit_target()?;
+        git.stage(&wt.path, None)
+            .map_err(|e| format!("Failed to stage changes: {}", e))?;
+        self.load_commit_files()
+    }
+
+    /// Commit the staged changes with the entered message
+    pub fn commit_staged(&mut self) -> std::result::Result<(), String> {
+        let message = self.commit_view.message();
+        let (wt, git) = self.commit_target()?;
+        if self.commit_view.staged_count() == 0 {
+            return Err("Nothing staged to commit".to_string());
+        }
+        if message.is_empty() || message == commit_prefill(wt).trim() {
+            return Err("Commit message is empty".to_string());
+        }
+
+        let hash = git
+            .commit(&wt.path, &message, None)
+            .map_err(|e| format!("Commit failed: {}", e))?;
+        let committed = format!("Committed {} on {}", &hash[..hash.len().min(7)], wt.branch);
+        let prefill = commit_prefill(wt);
+
+        self.loading_message = Some(committed);
+        self.commit_view.reset_message(&prefill);
+        self.commit_view.set_focus(CommitFocus::Files);
+        self.input_mode = InputMode::Normal;
+        self.load_commit_files()?;
+        self.refresh_worktree_statuses();
+        self.refresh_worktree_sync_statuses();
+        Ok(())
+    }
+
+    /// Close the commit view, returning to the view it was opened from
+    pub fn close_commit_view(&mut self) {
+        self.commit_worktree = None;
+        self.commit_view = CommitViewState::default();
+        self.input_mode = InputMode::Normal;
+        self.view = self
+            .commit_return_view
+            .take()
+            .unwrap_or(AppView::WorktreeManagement);
+        if self.view == AppView::WorktreeDiff {
+            if let Err(e) = self.load_worktree_diff() {
+                self.error_message = Some(e);
+            }
+        }
+    }
+
+    fn commit_target(&self) -> std::result::Result<(&Worktree, &GitService), String> {
+        let wt = self
+            .commit_worktree
+            .as_ref()
+            .ok_or_else(|| "No worktree selected".to_string())?;
+        let git = self
+            .git_service
+            .as_ref()
+            .ok_or_else(|| "Git service not available".to_string())?;
+        Ok((wt, git))
+    }
+
+    /// Open the detail view of the selected spec with its branch's commits
+    pub fn open_spec_detail(&mut self) -> std::result::Result<(), String> {
+        let id = self
+            .selected_spec()
+            .map(|spec| spec.id.as_str().to_string())
+            .ok_or_else(|| "No spec selected".to_string())?;
+        self.commit_log = CommitLogState::default();
+        self.view = AppView::SpecDetail(id);
+        self.load_commit_log()
+    }
+
+    /// Load the commits of the detail view's spec branch that are not on
+    /// the main branch, keeping the selected commit if it is still listed
+    pub fn load_commit_log(&mut self) -> std::result::Result<(), String> {
+        let AppView::SpecDetail(ref id) = self.view else {
+            return Ok(());
+        };
+        let spec = self
+            .specs
+            .iter()
+            .find(|s| s.id.as_str() == id)
+            .ok_or_else(|| format!("Spec {} not found", id))?;
+        let git = self
+            .git_service
+            .as_ref()
+            .ok_or_else(|| "Git service not available".to_string())?;
+
+        // A spec without a branch yet has no commits to show
+        let commits = match git.branch_log(&spec.branch, &self.project.config.git.main_branch) {
+            Ok(commits) => commits,
+            Err(GitError::BranchNotFound(_)) => Vec::new(),
+            Err(e) => return Err(format!("Failed to read commit log: {}", e)),
+        };
+        let tasks = spec
+            .artifacts
+            .tasks_path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok());
+
+        let selected = self.commit_log.selected_commit().map(|c| c.id.clone());
+        self.commit_log = CommitLogState::new(commits, tasks);
+        if let Some(index) =
+            selected.and_then(|id| self.commit_log.commits.iter().position(|c| c.id == id))
+        {
+            self.commit_log.selected = index;
+        }
+        Ok(())
+    }
+
+    /// Open the diff of the commit selected in the commit log
+    pub fn open_commit_diff(&mut self) -> std::result::Result<(), String> {
+        let commit = self
+            .commit_log
+            .selected_commit()
+            .ok_or_else(|| "No commit selected".to_string())?;
+        let git = self
+            .git_service
+            .as_ref()
+            .ok_or_else(|| "Git service not available".to_string())?;
+        let files = git
+            .commit_diff(&commit.id)
+            .map_err(|e| format!("Failed to diff commit: {}", e))?;
+
+        self.commit_diff = DiffViewState::new(files);
+        self.commit_return_view = Some(self.view.clone());
+        self.view = AppView::CommitDiff;
+        Ok(())
+    }
+
+    /// Close the commit diff, returning to the spec detail view
+    pub fn close_commit_diff(&mut self) {
+        self.commit_diff = DiffViewState::default();
+        self.view = self.commit_return_view.take().unwrap_or_default();
+    }
+
     /// Close worktree management view
     pub fn close_worktree_management(&mut self) {
         self.worktree_management_state = WorktreeManagementState::new();
@@ -641,6 +2660,17 @@ impl App {
         self.view = AppView::Overview;
     }
 
+    /// Open the token usage summary, loaded from the run history
+    pub fn open_usage_summary(&mut self) -> std::result::Result<(), String> {
+        let records = self
+            .run_history
+            .load()
+            .map_err(|e| format!("Failed to load run history: {}", e))?;
+        self.usage_view_state = UsageViewState::new(UsageSummary::from_records(&records));
+        self.view = AppView::UsageSummary;
+        Ok(())
+    }
+
     /// Get available document types for the selected spec
     pub fn get_available_documents(&self) -> Vec<DocType> {
         match self.selected_spec() {
@@ -808,6 +2838,11 @@ impl App {
         // Handle view-specific keys first
         match &self.view {
             AppView::Overview => {
+                // Esc leaves multi-select before it quits
+                if key.code == KeyCode::Esc && !self.marked_specs.is_empty() {
+                    self.marked_specs.clear();
+                    return false;
+                }
                 // Handle quit keys in overview
                 if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
                     return true;
@@ -822,19 +2857,38 @@ impl App {
             AppView::DocumentView(_) => {
                 return self.handle_document_view_key(key);
             }
-            AppView::DocumentEdit(_) => {
+            AppView::DocumentEdit(_) | AppView::FileEdit => {
                 return self.handle_document_edit_key(key);
             }
+            AppView::ArtifactReview => {
+                return self.handle_artifact_review_key(key);
+            }
             AppView::WorktreeManagement => {
                 return self.handle_worktree_management_key(key);
             }
+            AppView::WorktreeDiff => {
+                return self.handle_worktree_diff_key(key);
+            }
+            AppView::CommitView => {
+                return self.handle_commit_view_key(key);
+            }
+            AppView::SpecDetail(_) => {
+                if self.handle_spec_detail_key(key) {
+                    return false;
+                }
+            }
+            AppView::CommitDiff => {
+                return self.handle_commit_diff_key(key);
+            }
             AppView::NewSpec => {
                 return self.handle_new_spec_key(key);
             }
+            AppView::UsageSummary => {
+                return self.handle_usage_summary_key(key);
+            }
             AppView::Help => {
                 return self.handle_help_key(key);
             }
-            _ => {}
         }
 
         // Process action from input handler
@@ -843,9 +2897,8 @@ impl App {
                 Action::MoveUp => self.select_previous(),
                 Action::MoveDown => self.select_next(),
                 Action::Select => {
-                    // Enter spec detail view
-                    if let Some(spec) = self.selected_spec() {
-                        self.view = AppView::SpecDetail(spec.id.as_str().to_string());
+                    if let Err(e) = self.open_spec_detail() {
+                        self.error_message = Some(e);
                     }
                 }
                 Action::Back => {
@@ -893,15 +2946,20 @@ impl App {
                 Action::NewSpec => {
                     self.open_new_spec_dialog();
                 }
-                Action::CancelCommand => {
-                    if self.is_command_running() {
-                        self.cancel_command();
-                    }
+                Action::CancelCommand if self.is_command_running() => {
+                    self.cancel_command();
                 }
                 Action::Refresh => {
                     let _ = self.refresh_specs();
                     self.refresh_worktrees();
                 }
+                Action::ToggleMark => self.toggle_mark_selected(),
+                Action::MarkPhase => self.toggle_mark_phase(),
+                Action::UsageSummary => {
+                    if let Err(e) = self.open_usage_summary() {
+                        self.error_message = Some(e);
+                    }
+                }
                 Action::Help => {
                     self.open_help();
                 }
@@ -936,6 +2994,29 @@ impl App {
         false
     }
 
+    /// Handle keys in the usage summary view
+    fn handle_usage_summary_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('u') => {
+                self.view = AppView::Overview;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.usage_view_state.scroll_up(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.usage_view_state.scroll_down(1);
+            }
+            KeyCode::PageUp => {
+                self.usage_view_state.scroll_up(10);
+            }
+            KeyCode::PageDown => {
+                self.usage_view_state.scroll_down(10);
+            }
+            _ => {}
+        }
+        false
+    }
+
     /// Handle keys in new spec dialog
     fn handle_new_spec_key(&mut self, key: KeyEvent) -> bool {
         match self.new_spec_input.handle_key(key) {
@@ -1023,6 +3104,22 @@ impl App {
         false
     }
 
+    /// Apply the choice made in the worktree manager's confirmation dialog
+    fn confirm_worktree_dialog(&mut self) {
+        let result = if self.worktree_management_state.pending_cleanup.is_empty() {
+            match self.worktree_management_state.confirm_delete() {
+                Some(path) => self.delete_worktree(path, false),
+                None => Ok(()),
+            }
+        } else {
+            let merged = self.worktree_management_state.confirm_cleanup();
+            self.remove_merged_worktrees(merged)
+        };
+        if let Err(e) = result {
+            self.error_message = Some(e);
+        }
+    }
+
     /// Handle keys in worktree management view
     fn handle_worktree_management_key(&mut self, key: KeyEvent) -> bool {
         // Handle confirmation dialog if showing
@@ -1035,28 +3132,17 @@ impl App {
                     self.worktree_management_state.confirm_yes_selected = false;
                 }
                 KeyCode::Enter | KeyCode::Char(' ') => {
-                    if let Some(_path) = self.worktree_management_state.confirm_delete() {
-                        match self.delete_worktree(false) {
-                            Ok(()) => {}
-                            Err(e) => {
-                                self.error_message = Some(e);
-                            }
-                        }
-                    }
+                    self.confirm_worktree_dialog();
                 }
                 KeyCode::Esc | KeyCode::Char('n') => {
                     self.worktree_management_state.cancel_confirm();
                 }
+                KeyCode::Char('b') => {
+                    self.worktree_management_state.toggle_delete_branch();
+                }
                 KeyCode::Char('y') => {
                     self.worktree_management_state.confirm_yes_selected = true;
-                    if let Some(_path) = self.worktree_management_state.confirm_delete() {
-                        match self.delete_worktree(false) {
-                            Ok(()) => {}
-                            Err(e) => {
-                                self.error_message = Some(e);
-                            }
-                        }
-                    }
+                    self.confirm_worktree_dialog();
                 }
                 _ => {}
             }
@@ -1085,8 +3171,10 @@ impl App {
                     if wt.is_main {
                         self.error_message = Some("Cannot delete the main worktree".to_string());
                     } else {
-                        self.worktree_management_state.pending_delete = Some(wt.path.clone());
-                        match self.delete_worktree(true) {
+                        let path = wt.path.clone();
+                        // Force deletion never deletes the branch
+                        self.worktree_management_state.cancel_confirm();
+                        match self.delete_worktree(path, true) {
                             Ok(()) => {}
                             Err(e) => {
                                 self.error_message = Some(e);
@@ -1108,6 +3196,58 @@ impl App {
                 self.refresh_worktrees();
                 self.refresh_worktree_sync_statuses();
             }
+            KeyCode::Char('f') => {
+                if let Err(e) = self.run_git_op(GitOp::Fetch) {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('p') => {
+                let op = GitOp::Pull(self.project.config.git.pull_mode);
+                if let Err(e) = self.run_git_op(op) {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('P') => {
+                // Without a remote branch, the push creates it and tracks it
+                let set_upstream = self.selected_worktree().is_some_and(|wt| {
+                    self.worktree_sync_statuses
+                        .get(&wt.branch)
+                        .is_none_or(|sync| !sync.remote_exists)
+                });
+                if let Err(e) = self.run_git_op(GitOp::Push { set_upstream }) {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('v') => {
+                if let Err(e) = self.open_worktree_diff() {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('c') => {
+                if let Err(e) = self.open_commit_view() {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('x') => {
+                if let Err(e) = self.clean_up_worktrees() {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('u') => {
+                if let Err(e) = self.update_from_main() {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('C') => {
+                if let Err(e) = self.continue_update() {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('A') => {
+                if let Err(e) = self.abort_update() {
+                    self.error_message = Some(e);
+                }
+            }
             _ => {}
         }
         false
@@ -1177,6 +3317,18 @@ impl App {
                     self.view = AppView::Overview;
                 }
             },
+            KeyCode::Char('a') if self.marked_specs.is_empty() => {
+                if let Err(e) = self.run_selected_pipeline() {
+                    self.error_message = Some(e);
+                    self.view = AppView::Overview;
+                }
+            }
+            KeyCode::Char('c') => {
+                if let Err(e) = self.continue_last_session() {
+                    self.error_message = Some(e);
+                    self.view = AppView::Overview;
+                }
+            }
             _ => {}
         }
         false
@@ -1184,17 +3336,71 @@ impl App {
 
     /// Handle keys in command output view
     fn handle_command_output_key(&mut self, key: KeyEvent) -> bool {
+        // Ctrl+D closes stdin, whether or not the input line has focus
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('d') {
+            if self.accepts_stdin() {
+                self.close_stdin();
+            }
+            return false;
+        }
+
+        if self.stdin_focused {
+            return self.handle_stdin_input_key(key);
+        }
+
         match key.code {
-            KeyCode::Esc | KeyCode::Char('q') => {
-                // Only allow leaving if command is not running
-                if !self.is_command_running() {
-                    self.view = AppView::Overview;
+            // Review gate: continue or stop the pipeline
+            KeyCode::Char('n') if self.is_pipeline_awaiting_review() => {
+                self.approve_pipeline_step();
+            }
+            KeyCode::Char('s') if self.is_pipeline_awaiting_review() => {
+                self.stop_pipeline();
+            }
+            // Review artifact changes once the run is over
+            KeyCode::Char('d') if !self.is_command_running() => {
+                self.open_artifact_review();
+            }
+            // Select, expand or collapse tool call blocks
+            KeyCode::Char(']') => {
+                self.output_buffer.select_next_block();
+            }
+            KeyCode::Char('[') => {
+                self.output_buffer.select_previous_block();
+            }
+            KeyCode::Char('o') => {
+                self.output_buffer.toggle_block();
+            }
+            KeyCode::Char('O') => {
+                self.output_buffer.toggle_all_blocks();
+            }
+            // Focus the stdin input line
+            KeyCode::Char('i') if self.accepts_stdin() => {
+                self.stdin_focused = true;
+                self.input_mode = InputMode::Insert;
+            }
+            // Only allow leaving if command is not running
+            KeyCode::Esc | KeyCode::Char('q') if !self.is_command_running() => {
+                if self.is_pipeline_awaiting_review() {
+                    self.stop_pipeline();
+                }
+                self.view = match self.git_run {
+                    Some(_) => AppView::WorktreeManagement,
+                    None => AppView::Overview,
+                };
+            }
+            // Cancel running command
+            KeyCode::Char('c') if self.is_command_running() => {
+                self.cancel_command();
+            }
+            // Continue or abort an update that stopped on conflicts
+            KeyCode::Char('C') if self.has_update_conflicts() => {
+                if let Err(e) = self.continue_update() {
+                    self.error_message = Some(e);
                 }
             }
-            KeyCode::Char('c') => {
-                // Cancel running command
-                if self.is_command_running() {
-                    self.cancel_command();
+            KeyCode::Char('A') if self.has_update_conflicts() => {
+                if let Err(e) = self.abort_update() {
+                    self.error_message = Some(e);
                 }
             }
             KeyCode::Up | KeyCode::Char('k') => {
@@ -1217,6 +3423,214 @@ impl App {
         false
     }
 
+    /// Handle keys in the artifact review view
+    fn handle_artifact_review_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.view = AppView::CommandOutput;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.artifact_review.select_previous();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.artifact_review.select_next();
+            }
+            KeyCode::PageUp => {
+                self.artifact_review.scroll_up(10);
+            }
+            KeyCode::PageDown | KeyCode::Char(' ') => {
+                self.artifact_review.scroll_down(10);
+            }
+            KeyCode::Char('a') => {
+                self.accept_artifact_change();
+            }
+            KeyCode::Char('A') => {
+                self.accept_all_artifact_changes();
+            }
+            KeyCode::Char('r') => {
+                if let Err(e) = self.revert_artifact_change() {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('e') => {
+                if let Err(e) = self.edit_artifact_change() {
+                    self.error_message = Some(e);
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Handle keys in the worktree diff view
+    fn handle_worktree_diff_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.close_worktree_diff();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.worktree_diff.select_previous();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.worktree_diff.select_next();
+            }
+            KeyCode::Char('n') => {
+                self.worktree_diff.next_hunk();
+            }
+            KeyCode::Char('N') => {
+                self.worktree_diff.previous_hunk();
+            }
+            KeyCode::PageUp => {
+                self.worktree_diff.scroll_up(10);
+            }
+            KeyCode::PageDown | KeyCode::Char(' ') => {
+                self.worktree_diff.scroll_down(10);
+            }
+            KeyCode::Tab => {
+                if let Err(e) = self.toggle_worktree_diff_scope() {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('r') | KeyCode::F(5) => {
+                if let Err(e) = self.load_worktree_diff() {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('c') => {
+                if let Err(e) = self.open_commit_view() {
+                    self.error_message = Some(e);
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Handle commit log keys in the spec detail view, returning true if the
+    /// key was handled
+    fn handle_spec_detail_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.commit_log.select_previous();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.commit_log.select_next();
+            }
+            KeyCode::Enter | KeyCode::Char('d') => {
+                if let Err(e) = self.open_commit_diff() {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('r') | KeyCode::F(5) => {
+                if let Err(e) = self.load_commit_log() {
+                    self.error_message = Some(e);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Handle keys in the commit diff view
+    fn handle_commit_diff_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.close_commit_diff();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.commit_diff.select_previous();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.commit_diff.select_next();
+            }
+            KeyCode::Char('n') => {
+                self.commit_diff.next_hunk();
+            }
+            KeyCode::Char('N') => {
+                self.commit_diff.previous_hunk();
+            }
+            KeyCode::PageUp => {
+                self.commit_diff.scroll_up(10);
+            }
+            KeyCode::PageDown | KeyCode::Char(' ') => {
+                self.commit_diff.scroll_down(10);
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Handle keys in the commit view
+    fn handle_commit_view_key(&mut self, key: KeyEvent) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('s') {
+            if let Err(e) = self.commit_staged() {
+                self.error_message = Some(e);
+            }
+            return false;
+        }
+
+        if self.commit_view.focus == CommitFocus::Message {
+            match key.code {
+                KeyCode::Esc | KeyCode::Tab => {
+                    self.commit_view.set_focus(CommitFocus::Files);
+                    self.input_mode = InputMode::Normal;
+                }
+                _ => self.commit_view.handle_message_key(key),
+            }
+            return false;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.close_commit_view();
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.commit_view.select_previous();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.commit_view.select_next();
+            }
+            KeyCode::Char(' ') => {
+                if let Err(e) = self.toggle_commit_staging() {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Char('a') => {
+                if let Err(e) = self.stage_all_commit_files() {
+                    self.error_message = Some(e);
+                }
+            }
+            KeyCode::Tab | KeyCode::Char('m') => {
+                self.commit_view.set_focus(CommitFocus::Message);
+                self.input_mode = InputMode::Insert;
+            }
+            KeyCode::Char('r') | KeyCode::F(5) => {
+                if let Err(e) = self.load_commit_files() {
+                    self.error_message = Some(e);
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    /// Handle keys while the stdin input line has focus
+    fn handle_stdin_input_key(&mut self, key: KeyEvent) -> bool {
+        match self.stdin_input.handle_key(key) {
+            TextInputAction::Submit => {
+                if let Err(e) = self.send_stdin() {
+                    self.error_message = Some(e);
+                }
+            }
+            TextInputAction::Cancel => {
+                self.stdin_focused = false;
+                self.input_mode = InputMode::Normal;
+            }
+            TextInputAction::Changed | TextInputAction::None => {}
+        }
+        false
+    }
+
     /// Main event loop
     pub async fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> Result<()> {
         let tick_rate = Duration::from_millis(self.project.config.ui.refresh_rate_ms);
@@ -1228,12 +3642,19 @@ impl App {
         loop {
             // Poll process output if a command is running
             self.poll_process_output();
+            self.poll_fs_changes();
+            self.poll_status_updates();
 
             // Draw UI
             terminal.draw(|f| crate::ui::layout::draw(f, self))?;
 
-            // Calculate timeout - use shorter timeout when command is running for responsiveness
-            let timeout = if self.is_command_running() {
+            // Calculate timeout - use shorter timeout when command is running or
+            // statuses are refreshing for responsiveness
+            let refreshing = self
+                .status_worker
+                .as_ref()
+                .is_some_and(|worker| worker.is_pending());
+            let timeout = if self.is_command_running() || refreshing {
                 Duration::from_millis(50)
             } else {
                 tick_rate.saturating_sub(last_tick.elapsed())
@@ -1269,11 +3690,286 @@ impl App {
             }
         }
 
-        // Cleanup: cancel any running command
-        if self.is_command_running() {
-            self.cancel_command();
+        // Changes left unreviewed are kept
+        self.commit_pending();
+
+        // Cleanup: kill any running command; there is no time for a graceful cancel
+        if let Some(ref mut handle) = self.process_handle {
+            if handle.is_running() {
+                let _ = handle.kill();
+            }
         }
 
         Ok(())
     }
 }
+
+/// Parse configured command names, skipping unknown ones
+fn parse_commands(names: &[String]) -> Vec<WorkflowCommandType> {
+    names
+        .iter()
+        .filter_map(|name| {
+            let command = WorkflowCommandType::from_config_key(name);
+            if command.is_none() {
+                tracing::warn!("Ignoring unknown workflow command in config: {}", name);
+            }
+            command
+        })
+        .collect()
+}
+
+/// Build the commit message prefill for a worktree, starting with its spec id
+fn commit_prefill(wt: &Worktree) -> String {
+    wt.spec_id
+        .as_ref()
+        .map(|id| format!("{}: ", id))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::WorkflowPhase;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn git(path: &Path, args: &[&str]) -> bool {
+        std::process::Command::new("git")
+            .args(args)
+            .current_dir(path)
+            .output()
+            .unwrap()
+            .status
+            .success()
+    }
+
+    fn press(app: &mut App, c: char) {
+        app.handle_worktree_management_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+
+    fn select(app: &mut App, branch: &str) {
+        app.worktree_management_state.selected_index = app
+            .worktrees
+            .iter()
+            .position(|w| w.branch == branch)
+            .unwrap();
+    }
+
+    fn init_repo(root: &Path) {
+        assert!(git(root, &["init", "-q", "-b", "main"]));
+        assert!(git(root, &["config", "user.email", "test@test.com"]));
+        assert!(git(root, &["config", "user.name", "Test"]));
+        assert!(git(
+            root,
+            &["commit", "-q", "--allow-empty", "-m", "Initial commit"]
+        ));
+    }
+
+    #[test]
+    fn test_delete_worktree_and_branch_from_dialog() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        init_repo(root);
+        for branch in ["003-foo", "004-bar"] {
+            let path = format!(".worktrees/{}", branch);
+            assert!(git(root, &["worktree", "add", "-q", "-b", branch, &path]));
+        }
+
+        let mut app = App::new(root.to_path_buf()).unwrap();
+        app.refresh_worktrees();
+
+        // d, then b to also delete the branch, then y to confirm
+        select(&mut app, "003-foo");
+        press(&mut app, 'd');
+        press(&mut app, 'b');
+        press(&mut app, 'y');
+        assert_eq!(app.error_message, None);
+        assert!(!root.join(".worktrees/003-foo").exists());
+        assert!(!git(root, &["rev-parse", "--verify", "refs/heads/003-foo"]));
+
+        // A force delete after a dialog was dismissed keeps the branch
+        select(&mut app, "004-bar");
+        press(&mut app, 'd');
+        press(&mut app, 'b');
+        press(&mut app, 'n');
+        press(&mut app, 'D');
+        assert_eq!(app.error_message, None);
+        assert!(!root.join(".worktrees/004-bar").exists());
+        assert!(git(root, &["rev-parse", "--verify", "refs/heads/004-bar"]));
+    }
+
+    #[test]
+    fn test_phase_follows_worktree_spec_dir() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        init_repo(root);
+        std::fs::create_dir_all(root.join("specs/003-foo")).unwrap();
+        std::fs::write(root.join("specs/003-foo/spec.md"), "# Spec").unwrap();
+        assert!(git(root, &["add", "."]));
+        assert!(git(root, &["commit", "-q", "-m", "Add spec"]));
+        assert!(git(
+            root,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "003-foo",
+                ".worktrees/003-foo"
+            ]
+        ));
+
+        let mut app = App::new(root.to_path_buf()).unwrap();
+        app.refresh_specs().unwrap();
+        app.refresh_worktrees();
+        assert_eq!(app.specs[0].phase, WorkflowPhase::Clarify);
+
+        // An agent writes the plan in the worktree only
+        let wt = app.worktrees.iter().find(|w| !w.is_main).unwrap().clone();
+        std::fs::write(wt.path.join("specs/003-foo/plan.md"), "# Plan").unwrap();
+        app.apply_fs_changes(WatchChanges {
+            worktrees: [wt.path.clone()].into(),
+            ..WatchChanges::default()
+        });
+        assert_eq!(app.specs[0].phase, WorkflowPhase::Tasks);
+
+        // A full rescan keeps following the worktree
+        app.refresh_specs().unwrap();
+        assert!(app.specs[0].artifacts.has_plan);
+        assert_eq!(
+            app.read_spec_artifact(&app.specs[0], ArtifactType::Plan)
+                .unwrap(),
+            "# Plan"
+        );
+    }
+
+    #[test]
+    fn test_spec_worktree_matches_branch_exactly() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        init_repo(root);
+        for spec in ["003-main-menu", "012-foo", "012-foo-v2"] {
+            std::fs::create_dir_all(root.join("specs").join(spec)).unwrap();
+            std::fs::write(root.join("specs").join(spec).join("spec.md"), "# Spec").unwrap();
+        }
+        assert!(git(root, &["add", "."]));
+        assert!(git(root, &["commit", "-q", "-m", "Add specs"]));
+        assert!(git(
+            root,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "-b",
+                "012-foo-v2",
+                ".worktrees/012-foo-v2"
+            ]
+        ));
+
+        let mut app = App::new(root.to_path_buf()).unwrap();
+        app.refresh_specs().unwrap();
+        app.refresh_worktrees();
+        let spec = |app: &App, branch: &str| {
+            app.specs
+                .iter()
+                .find(|s| s.branch == branch)
+                .unwrap()
+                .clone()
+        };
+
+        // A branch containing the main branch's name gets its own worktree
+        let menu = spec(&app, "003-main-menu");
+        assert!(app.find_worktree_for_spec(&menu).is_none());
+        let path = app.ensure_worktree_for_spec(&menu).unwrap();
+        assert_ne!(path, app.project.root_path);
+        assert!(path.ends_with("003-main-menu"));
+
+        // A branch that is a prefix of another does not take its worktree
+        let foo = spec(&app, "012-foo");
+        assert!(app.find_worktree_for_spec(&foo).is_none());
+        let path = app.ensure_worktree_for_spec(&foo).unwrap();
+        assert!(path.ends_with("012-foo"));
+    }
+
+    #[test]
+    fn test_auto_commit_waits_for_review() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path();
+        init_repo(root);
+        std::fs::create_dir_all(root.join("specs/003-foo")).unwrap();
+        std::fs::write(root.join("specs/003-foo/spec.md"), "# Spec").unwrap();
+        assert!(git(root, &["add", "."]));
+        assert!(git(root, &["commit", "-q", "-m", "Add spec"]));
+
+        let mut app = App::new(root.to_path_buf()).unwrap();
+        app.refresh_specs().unwrap();
+        app.refresh_worktrees();
+        app.project.config.git.auto_commit.enabled = true;
+        let spec = app.specs[0].clone();
+        let worktree = app.ensure_worktree_for_spec(&spec).unwrap();
+        let head = |dir: &Path| {
+            let output = std::process::Command::new("git")
+                .args(["rev-parse", "HEAD"])
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            String::from_utf8(output.stdout).unwrap().trim().to_string()
+        };
+        let base = head(&worktree);
+
+        // The agent rewrites the spec and writes a plan
+        let context = WorkflowContext::new(&spec).in_worktree(
+            worktree.clone(),
+            app.project.relative_spec_path(&spec.directory),
+        );
+        app.artifact_snapshot = Some(ArtifactSnapshot::capture(&context.spec_dir));
+        let mut cmd = WorkflowCommand::new(WorkflowCommandType::Plan, spec.id.clone());
+        cmd.start();
+        std::fs::write(context.spec_dir.join("spec.md"), "# Rewritten").unwrap();
+        std::fs::write(context.spec_dir.join("plan.md"), "# Plan").unwrap();
+        cmd.complete(0);
+        app.active_command = Some(cmd);
+        app.active_context = Some(context);
+        app.finish_run(None);
+
+        // Nothing is committed while the changes are under review
+        assert_eq!(app.artifact_review.files.len(), 2);
+        assert_eq!(head(&worktree), base);
+        assert!(app.run_history.load().unwrap().is_empty());
+
+        // Revert the spec and keep the plan
+        app.artifact_review.select_path(Path::new("spec.md"));
+        app.revert_artifact_change().unwrap();
+        app.accept_artifact_change();
+        assert!(app.artifact_review.is_empty());
+
+        // The commit holds only what was kept, and the history records it
+        let commit = head(&worktree);
+        assert_ne!(commit, base);
+        let output = std::process::Command::new("git")
+            .args(["show", "--name-only", "--format=", &commit])
+            .current_dir(&worktree)
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap().trim(),
+            "specs/003-foo/plan.md"
+        );
+        let records = app.run_history.load().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].commit.as_deref(), Some(commit.as_str()));
+
+        // A file the run already committed cannot be reverted from the review
+        let spec_dir = app.active_context.as_ref().unwrap().spec_dir.clone();
+        app.artifact_snapshot = Some(ArtifactSnapshot::capture(&spec_dir));
+        std::fs::write(spec_dir.join("plan.md"), "# Plan v2").unwrap();
+        assert!(git(&worktree, &["commit", "-q", "-am", "Agent commit"]));
+        app.collect_artifact_changes();
+        assert_eq!(app.artifact_review.files.len(), 1);
+        assert!(app.revert_artifact_change().is_err());
+        assert_eq!(
+            std::fs::read_to_string(spec_dir.join("plan.md")).unwrap(),
+            "# Plan v2"
+        );
+    }
+}
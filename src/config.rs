@@ -2,8 +2,9 @@
 //!
 //! Supports layered configuration: defaults → project → user → env
 
-use crate::error::ConfigError;
+use crate::error::{ConfigError, McpErrorKind};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Main configuration structure
@@ -17,6 +18,16 @@ pub struct ProjectConfig {
     pub ui: UiConfig,
     #[serde(default)]
     pub git: GitConfig,
+    #[serde(default)]
+    pub logs: LogConfig,
+    #[serde(default)]
+    pub workflow: WorkflowConfig,
+    #[serde(default)]
+    pub agent: AgentConfig,
+    #[serde(default)]
+    pub pricing: PricingConfig,
+    #[serde(default)]
+    pub watch: WatchConfig,
 }
 
 impl ProjectConfig {
@@ -165,6 +176,21 @@ pub struct GitConfig {
     /// Main branch name
     #[serde(default = "default_main_branch")]
     pub main_branch: String,
+    /// Committing artifacts after successful workflow runs
+    #[serde(default)]
+    pub auto_commit: AutoCommitConfig,
+    /// Maximum number of worktree statuses computed at once
+    #[serde(default = "default_status_jobs")]
+    pub status_jobs: usize,
+    /// Delay in milliseconds for coalescing status refresh requests
+    #[serde(default = "default_status_debounce_ms")]
+    pub status_debounce_ms: u64,
+    /// How pulling integrates the remote branch
+    #[serde(default)]
+    pub pull_mode: PullMode,
+    /// How updating a worktree from the main branch integrates it
+    #[serde(default)]
+    pub update_mode: UpdateMode,
 }
 
 impl Default for GitConfig {
@@ -172,6 +198,11 @@ impl Default for GitConfig {
         Self {
             specs_directory: default_specs_directory(),
             main_branch: default_main_branch(),
+            auto_commit: AutoCommitConfig::default(),
+            status_jobs: default_status_jobs(),
+            status_debounce_ms: default_status_debounce_ms(),
+            pull_mode: PullMode::default(),
+            update_mode: UpdateMode::default(),
         }
     }
 }
@@ -184,6 +215,550 @@ fn default_main_branch() -> String {
     "main".to_string()
 }
 
+fn default_status_jobs() -> usize {
+    4
+}
+
+fn default_status_debounce_ms() -> u64 {
+    200
+}
+
+/// Filesystem watching configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchConfig {
+    /// Refresh specs and worktrees when files change
+    #[serde(default = "default_watch_enabled")]
+    pub enabled: bool,
+    /// Quiet period in milliseconds before a burst of changes is applied
+    #[serde(default = "default_watch_debounce_ms")]
+    pub debounce_ms: u64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_watch_enabled(),
+            debounce_ms: default_watch_debounce_ms(),
+        }
+    }
+}
+
+fn default_watch_enabled() -> bool {
+    true
+}
+
+fn default_watch_debounce_ms() -> u64 {
+    300
+}
+
+/// Auto-commit configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoCommitConfig {
+    /// Commit in the spec's worktree when a workflow exits with code 0
+    #[serde(default)]
+    pub enabled: bool,
+    /// Which changes to commit
+    #[serde(default)]
+    pub scope: CommitScope,
+    /// Commit message template; supports `{command}` and `{spec_id}`
+    #[serde(default = "default_commit_message")]
+    pub message: String,
+}
+
+impl AutoCommitConfig {
+    /// Render the commit message for a command run on a spec
+    pub fn message_for(&self, command: &str, spec_id: &str) -> String {
+        self.message
+            .replace("{command}", command)
+            .replace("{spec_id}", spec_id)
+    }
+}
+
+impl Default for AutoCommitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            scope: CommitScope::default(),
+            message: default_commit_message(),
+        }
+    }
+}
+
+fn default_commit_message() -> String {
+    "speck: {command} for {spec_id}".to_string()
+}
+
+/// Changes included in an automatic commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitScope {
+    /// Only the spec directory
+    #[default]
+    Spec,
+    /// Everything changed in the worktree
+    All,
+}
+
+/// How a pull integrates the remote branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PullMode {
+    /// Only fast-forward; fail if the branches diverged
+    #[default]
+    FfOnly,
+    /// Rebase local commits onto the remote branch
+    Rebase,
+}
+
+/// How a worktree branch is brought up to date with the main branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateMode {
+    /// Merge the main branch into the worktree branch
+    #[default]
+    Merge,
+    /// Rebase the worktree branch onto the main branch
+    Rebase,
+}
+
+impl UpdateMode {
+    /// Get the git subcommand performing the update
+    pub fn command(&self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Rebase => "rebase",
+        }
+    }
+}
+
+/// Workflow log retention configuration
+///
+/// A value of `0` disables the corresponding limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogConfig {
+    /// Remove logs older than this many days
+    #[serde(default = "default_max_age_days")]
+    pub max_age_days: u64,
+    /// Keep at most this many logs per spec
+    #[serde(default = "default_max_per_spec")]
+    pub max_per_spec: usize,
+    /// Keep the log directory below this size in megabytes
+    #[serde(default = "default_max_total_size_mb")]
+    pub max_total_size_mb: u64,
+    /// Gzip logs older than this many days
+    #[serde(default = "default_compress_after_days")]
+    pub compress_after_days: u64,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            max_age_days: default_max_age_days(),
+            max_per_spec: default_max_per_spec(),
+            max_total_size_mb: default_max_total_size_mb(),
+            compress_after_days: default_compress_after_days(),
+        }
+    }
+}
+
+fn default_max_age_days() -> u64 {
+    30
+}
+
+fn default_max_per_spec() -> usize {
+    20
+}
+
+fn default_max_total_size_mb() -> u64 {
+    100
+}
+
+fn default_compress_after_days() -> u64 {
+    1
+}
+
+/// Workflow execution configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowConfig {
+    /// Time to wait after SIGINT before sending SIGTERM, in milliseconds
+    #[serde(default = "default_interrupt_grace_ms")]
+    pub interrupt_grace_ms: u64,
+    /// Time to wait after SIGTERM before sending SIGKILL, in milliseconds
+    #[serde(default = "default_terminate_grace_ms")]
+    pub terminate_grace_ms: u64,
+    /// Extra environment for every workflow command
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Per-command settings, keyed by command name (e.g. `plan`)
+    #[serde(default)]
+    pub commands: HashMap<String, CommandConfig>,
+    /// Commands chained by a pipeline run, in order
+    #[serde(default = "default_pipeline")]
+    pub pipeline: Vec<String>,
+    /// Pipeline steps after which to pause for review
+    #[serde(default)]
+    pub review_gates: Vec<String>,
+    /// Retrying failed commands, unless a command overrides it
+    #[serde(default)]
+    pub retry: RetryConfig,
+}
+
+impl WorkflowConfig {
+    /// Environment for a workflow command: the shared variables, overridden
+    /// by the command's own, sorted by name
+    pub fn env_for(&self, command: &str) -> Vec<(String, String)> {
+        let mut env = self.env.clone();
+        if let Some(command) = self.commands.get(command) {
+            env.extend(command.env.clone());
+        }
+        let mut env: Vec<_> = env.into_iter().collect();
+        env.sort();
+        env
+    }
+
+    /// Retry settings for a workflow command: its own, or the shared ones
+    pub fn retry_for(&self, command: &str) -> &RetryConfig {
+        self.commands
+            .get(command)
+            .and_then(|c| c.retry.as_ref())
+            .unwrap_or(&self.retry)
+    }
+}
+
+impl Default for WorkflowConfig {
+    fn default() -> Self {
+        Self {
+            interrupt_grace_ms: default_interrupt_grace_ms(),
+            terminate_grace_ms: default_terminate_grace_ms(),
+            env: HashMap::new(),
+            commands: HashMap::new(),
+            pipeline: default_pipeline(),
+            review_gates: Vec::new(),
+            retry: RetryConfig::default(),
+        }
+    }
+}
+
+fn default_pipeline() -> Vec<String> {
+    ["specify", "clarify", "plan", "tasks"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+/// Settings for a single workflow command
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandConfig {
+    /// Extra environment for this command, overriding the shared variables
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Shell commands run in the worktree before the workflow; a failure blocks it
+    #[serde(default)]
+    pub pre: Vec<HookConfig>,
+    /// Shell commands run in the worktree after the workflow
+    #[serde(default)]
+    pub post: Vec<HookConfig>,
+    /// Retry settings replacing the shared `[workflow.retry]` ones
+    #[serde(default)]
+    pub retry: Option<RetryConfig>,
+}
+
+/// Retrying a workflow command that failed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Total attempts, including the first (1 disables retries)
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Delay before the first retry, in milliseconds
+    #[serde(default = "default_backoff_ms")]
+    pub backoff_ms: u64,
+    /// Factor applied to the delay after each retry
+    #[serde(default = "default_backoff_multiplier")]
+    pub backoff_multiplier: f64,
+    /// Upper bound for the delay, in milliseconds
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// Exit codes that trigger a retry; empty retries any non-zero exit
+    #[serde(default)]
+    pub exit_codes: Vec<i32>,
+    /// Error kinds that trigger a retry when the agent cannot be run
+    #[serde(default = "default_retry_errors")]
+    pub errors: Vec<McpErrorKind>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_max_attempts(),
+            backoff_ms: default_backoff_ms(),
+            backoff_multiplier: default_backoff_multiplier(),
+            max_backoff_ms: default_max_backoff_ms(),
+            exit_codes: Vec::new(),
+            errors: default_retry_errors(),
+        }
+    }
+}
+
+fn default_max_attempts() -> u32 {
+    1
+}
+
+fn default_backoff_ms() -> u64 {
+    2000
+}
+
+fn default_backoff_multiplier() -> f64 {
+    2.0
+}
+
+fn default_max_backoff_ms() -> u64 {
+    60_000
+}
+
+/// The failures the process runner reports when an agent cannot be started
+fn default_retry_errors() -> Vec<McpErrorKind> {
+    vec![McpErrorKind::SpawnFailed, McpErrorKind::Io]
+}
+
+/// A shell command run before or after a workflow command
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookConfig {
+    /// Shell command line, run with `sh -c`
+    pub run: String,
+    /// When a post-run hook runs, based on the workflow's result
+    #[serde(default)]
+    pub when: HookCondition,
+    /// Exit codes counted as success
+    #[serde(default = "default_success_codes")]
+    pub success_codes: Vec<i32>,
+}
+
+impl HookConfig {
+    /// Check whether the hook runs after a workflow with the given result
+    pub fn applies(&self, workflow_succeeded: bool) -> bool {
+        match self.when {
+            HookCondition::Success => workflow_succeeded,
+            HookCondition::Failure => !workflow_succeeded,
+            HookCondition::Always => true,
+        }
+    }
+
+    /// Check whether an exit code counts as success
+    pub fn is_success(&self, exit_code: i32) -> bool {
+        self.success_codes.contains(&exit_code)
+    }
+}
+
+/// Workflow result a post-run hook applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookCondition {
+    /// Run only after a successful workflow
+    #[default]
+    Success,
+    /// Run only after a failed workflow
+    Failure,
+    /// Run regardless of the workflow's result
+    Always,
+}
+
+fn default_success_codes() -> Vec<i32> {
+    vec![0]
+}
+
+fn default_interrupt_grace_ms() -> u64 {
+    3000
+}
+
+fn default_terminate_grace_ms() -> u64 {
+    5000
+}
+
+/// Agent CLI used to run workflow commands
+///
+/// `command`, `args` and `mcp_args` override the chosen preset. Workflow
+/// arguments support the placeholders `{tool}`, `{spec_id}`, `{spec_dir}`
+/// (relative to the worktree root), `{worktree}` and `{phase}`, and
+/// `resume_args` also `{session_id}`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AgentConfig {
+    /// Preset providing the default command and arguments
+    #[serde(default)]
+    pub preset: AgentPreset,
+    /// Agent executable
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Argument template for workflow commands
+    #[serde(default)]
+    pub args: Option<Vec<String>>,
+    /// Arguments that start the agent as an MCP server
+    #[serde(default)]
+    pub mcp_args: Option<Vec<String>>,
+    /// Format of the agent's stdout during workflow runs
+    #[serde(default)]
+    pub output_format: OutputFormat,
+    /// Argument template for continuing a previous session
+    #[serde(default)]
+    pub resume_args: Option<Vec<String>>,
+    /// Regex whose first capture group is a session id in the agent's output
+    #[serde(default)]
+    pub session_pattern: Option<String>,
+}
+
+impl AgentConfig {
+    /// Create a configuration for a preset without overrides
+    pub fn preset(preset: AgentPreset) -> Self {
+        Self {
+            preset,
+            ..Self::default()
+        }
+    }
+
+    /// Agent executable
+    pub fn command(&self) -> String {
+        self.command
+            .clone()
+            .unwrap_or_else(|| self.preset.command().to_string())
+    }
+
+    /// Argument template for workflow commands
+    pub fn args(&self) -> Vec<String> {
+        self.args
+            .clone()
+            .unwrap_or_else(|| to_strings(self.preset.args()))
+    }
+
+    /// Arguments that start the agent as an MCP server, if it has such a mode
+    pub fn mcp_args(&self) -> Option<Vec<String>> {
+        self.mcp_args
+            .clone()
+            .or_else(|| self.preset.mcp_args().map(to_strings))
+    }
+
+    /// Argument template for continuing a session, if the agent supports it
+    pub fn resume_args(&self) -> Option<Vec<String>> {
+        self.resume_args
+            .clone()
+            .or_else(|| self.preset.resume_args().map(to_strings))
+    }
+}
+
+/// Format of an agent's output stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Plain text, shown line by line
+    #[default]
+    Text,
+    /// Newline-delimited JSON events, as written by `--output-format stream-json`
+    StreamJson,
+}
+
+/// Built-in agent CLI presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentPreset {
+    /// Claude Code (`claude`)
+    #[default]
+    Claude,
+    /// OpenAI Codex CLI (`codex`)
+    Codex,
+    /// Gemini CLI (`gemini`)
+    Gemini,
+    /// Cursor CLI (`cursor-agent`)
+    Cursor,
+}
+
+impl AgentPreset {
+    /// Agent executable
+    pub fn command(&self) -> &'static str {
+        match self {
+            Self::Claude => "claude",
+            Self::Codex => "codex",
+            Self::Gemini => "gemini",
+            Self::Cursor => "cursor-agent",
+        }
+    }
+
+    /// Argument template for workflow commands
+    pub fn args(&self) -> &'static [&'static str] {
+        match self {
+            Self::Claude => &["--mcp", "{tool}", "{spec_dir}"],
+            Self::Codex => &["exec", "/{tool} {spec_dir}"],
+            Self::Gemini => &["--prompt", "/{tool} {spec_dir}"],
+            Self::Cursor => &["--print", "/{tool} {spec_dir}"],
+        }
+    }
+
+    /// Argument template for continuing the session `{session_id}`, if the
+    /// CLI supports it
+    pub fn resume_args(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Self::Claude => Some(&["--resume", "{session_id}", "--mcp", "{tool}", "{spec_dir}"]),
+            Self::Codex => Some(&["exec", "resume", "{session_id}", "/{tool} {spec_dir}"]),
+            Self::Gemini => None,
+            Self::Cursor => Some(&["--resume", "{session_id}", "--print", "/{tool} {spec_dir}"]),
+        }
+    }
+
+    /// Arguments that start the agent as an MCP server, if the CLI has
+    /// such a mode
+    pub fn mcp_args(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Self::Claude => Some(&["--mcp"]),
+            Self::Codex => Some(&["mcp-server"]),
+            Self::Gemini | Self::Cursor => None,
+        }
+    }
+}
+
+/// Per-model token pricing, used to estimate the cost of workflow runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PricingConfig {
+    /// Prices per model; the first entry whose `model` prefixes the reported
+    /// model wins, `"*"` matches any model
+    #[serde(default)]
+    pub models: Vec<ModelPricing>,
+}
+
+impl PricingConfig {
+    /// Find the price for a model, falling back to a `"*"` entry
+    pub fn price_for(&self, model: Option<&str>) -> Option<&ModelPricing> {
+        model
+            .and_then(|model| {
+                self.models
+                    .iter()
+                    .find(|pricing| pricing.model != "*" && model.starts_with(&pricing.model))
+            })
+            .or_else(|| self.models.iter().find(|pricing| pricing.model == "*"))
+    }
+}
+
+/// Token prices for one model, in USD per million tokens
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    /// Model name or prefix, e.g. `claude-sonnet`
+    pub model: String,
+    /// Price per million input tokens
+    #[serde(default)]
+    pub input_per_mtok: f64,
+    /// Price per million output tokens
+    #[serde(default)]
+    pub output_per_mtok: f64,
+}
+
+impl ModelPricing {
+    /// Cost in USD of the given token counts
+    pub fn cost(&self, input_tokens: u64, output_tokens: u64) -> f64 {
+        (input_tokens as f64 * self.input_per_mtok + output_tokens as f64 * self.output_per_mtok)
+            / 1_000_000.0
+    }
+}
+
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,5 +772,201 @@ mod tests {
         assert!(config.ui.vim_navigation);
         assert_eq!(config.git.specs_directory, "specs");
         assert_eq!(config.git.main_branch, "main");
+        assert_eq!(config.git.status_jobs, 4);
+        assert_eq!(config.git.pull_mode, PullMode::FfOnly);
+        assert_eq!(config.git.update_mode, UpdateMode::Merge);
+        assert!(config.watch.enabled);
+        assert!(!config.git.auto_commit.enabled);
+        assert_eq!(config.git.auto_commit.scope, CommitScope::Spec);
+        assert_eq!(config.logs.max_age_days, 30);
+        assert_eq!(config.logs.max_per_spec, 20);
+        assert_eq!(config.workflow.interrupt_grace_ms, 3000);
+        assert_eq!(config.workflow.terminate_grace_ms, 5000);
+        assert!(config.workflow.env.is_empty());
+        assert!(config.workflow.commands.is_empty());
+        assert_eq!(
+            config.workflow.pipeline,
+            vec!["specify", "clarify", "plan", "tasks"]
+        );
+        assert!(config.workflow.review_gates.is_empty());
+        assert_eq!(config.agent.preset, AgentPreset::Claude);
+        assert_eq!(config.agent.command(), "claude");
+        assert_eq!(config.agent.mcp_args().unwrap(), vec!["--mcp"]);
+        assert_eq!(config.agent.output_format, OutputFormat::Text);
+    }
+
+    #[test]
+    fn test_auto_commit_config() {
+        let toml = r#"
+            [git.auto_commit]
+            enabled = true
+            scope = "all"
+        "#;
+        let config: ProjectConfig = config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+
+        assert!(config.git.auto_commit.enabled);
+        assert_eq!(config.git.auto_commit.scope, CommitScope::All);
+        assert_eq!(
+            config.git.auto_commit.message_for("plan", "003-foo"),
+            "speck: plan for 003-foo"
+        );
+    }
+
+    #[test]
+    fn test_retry_config() {
+        let toml = r#"
+            [workflow.retry]
+            max_attempts = 2
+
+            [workflow.commands.implement.retry]
+            max_attempts = 4
+            backoff_ms = 500
+            exit_codes = [75]
+            errors = ["timeout", "spawn-failed"]
+        "#;
+        let config: ProjectConfig = config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+
+        let shared = config.workflow.retry_for("plan");
+        assert_eq!(shared.max_attempts, 2);
+        assert_eq!(shared.backoff_ms, 2000);
+        assert!(shared.exit_codes.is_empty());
+
+        let implement = config.workflow.retry_for("implement");
+        assert_eq!(implement.max_attempts, 4);
+        assert_eq!(implement.backoff_ms, 500);
+        assert_eq!(implement.exit_codes, vec![75]);
+        assert_eq!(
+            implement.errors,
+            vec![McpErrorKind::Timeout, McpErrorKind::SpawnFailed]
+        );
+    }
+
+    #[test]
+    fn test_pricing_config() {
+        let toml = r#"
+            [[pricing.models]]
+            model = "claude-sonnet"
+            input_per_mtok = 3.0
+            output_per_mtok = 15.0
+
+            [[pricing.models]]
+            model = "*"
+            input_per_mtok = 1.0
+        "#;
+        let config: ProjectConfig = config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+
+        let sonnet = config.pricing.price_for(Some("claude-sonnet-4-5")).unwrap();
+        assert_eq!(sonnet.model, "claude-sonnet");
+        assert!((sonnet.cost(1_000_000, 100_000) - 4.5).abs() < 1e-9);
+        assert_eq!(config.pricing.price_for(Some("gpt-5")).unwrap().model, "*");
+        assert_eq!(config.pricing.price_for(None).unwrap().model, "*");
+        assert!(ProjectConfig::default()
+            .pricing
+            .price_for(Some("gpt-5"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_agent_overrides_preset() {
+        let toml = r#"
+            [agent]
+            preset = "gemini"
+            args = ["run", "{tool}", "--spec", "{spec_dir}"]
+            output_format = "stream-json"
+        "#;
+        let config: ProjectConfig = config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+
+        assert_eq!(config.agent.preset, AgentPreset::Gemini);
+        assert_eq!(config.agent.command(), "gemini");
+        assert_eq!(
+            config.agent.args(),
+            vec!["run", "{tool}", "--spec", "{spec_dir}"]
+        );
+        assert!(config.agent.mcp_args().is_none());
+        assert_eq!(config.agent.output_format, OutputFormat::StreamJson);
+        assert!(config.agent.resume_args().is_none());
+        assert_eq!(
+            AgentConfig::preset(AgentPreset::Claude)
+                .resume_args()
+                .unwrap()[..2],
+            ["--resume", "{session_id}"]
+        );
+    }
+
+    #[test]
+    fn test_workflow_env_for_command() {
+        let toml = r#"
+            [workflow]
+            env = { AGENT_PROFILE = "default", LOG_LEVEL = "info" }
+
+            [workflow.commands.plan]
+            env = { LOG_LEVEL = "debug", PLAN_DEPTH = "2" }
+        "#;
+        let config: ProjectConfig = config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+
+        let env = config.workflow.env_for("plan");
+        assert_eq!(
+            env,
+            vec![
+                ("AGENT_PROFILE".to_string(), "default".to_string()),
+                ("LOG_LEVEL".to_string(), "debug".to_string()),
+                ("PLAN_DEPTH".to_string(), "2".to_string()),
+            ]
+        );
+        assert_eq!(config.workflow.env_for("tasks").len(), 2);
+    }
+
+    #[test]
+    fn test_workflow_hooks() {
+        let toml = r#"
+            [[workflow.commands.implement.post]]
+            run = "cargo test"
+
+            [[workflow.commands.implement.post]]
+            run = "notify-send failed"
+            when = "failure"
+            success_codes = [0, 1]
+        "#;
+        let config: ProjectConfig = config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .unwrap()
+            .try_deserialize()
+            .unwrap();
+
+        let implement = &config.workflow.commands["implement"];
+        assert!(implement.pre.is_empty());
+        let [test, notify] = implement.post.as_slice() else {
+            panic!("expected two post hooks");
+        };
+        assert!(test.applies(true) && !test.applies(false));
+        assert!(test.is_success(0) && !test.is_success(1));
+        assert!(notify.applies(false) && !notify.applies(true));
+        assert!(notify.is_success(1));
     }
 }
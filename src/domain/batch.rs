@@ -0,0 +1,148 @@
+//! Batch entity running one workflow command across several specs.
+
+use super::{SpecId, StepState, WorkflowCommandType};
+
+/// A spec queued in a batch
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    /// Spec the command runs for
+    pub spec_id: SpecId,
+    /// Current state
+    pub state: StepState,
+}
+
+/// One workflow command queued for several specs, run one after another
+#[derive(Debug, Clone)]
+pub struct Batch {
+    /// Command run for every spec
+    pub command: WorkflowCommandType,
+    /// Specs in execution order
+    pub entries: Vec<BatchEntry>,
+    /// Index of the current entry
+    current: usize,
+}
+
+impl Batch {
+    /// Create a batch running `command` for each spec
+    pub fn new(command: WorkflowCommandType, spec_ids: Vec<SpecId>) -> Self {
+        let entries = spec_ids
+            .into_iter()
+            .map(|spec_id| BatchEntry {
+                spec_id,
+                state: StepState::Pending,
+            })
+            .collect();
+
+        Self {
+            command,
+            entries,
+            current: 0,
+        }
+    }
+
+    /// Get the spec of the current entry
+    pub fn current_spec(&self) -> Option<&SpecId> {
+        self.entries.get(self.current).map(|e| &e.spec_id)
+    }
+
+    /// Mark the current entry as running
+    pub fn start_entry(&mut self) {
+        if let Some(entry) = self.entries.get_mut(self.current) {
+            entry.state = StepState::Running;
+        }
+    }
+
+    /// Record how the current entry ended, returning the next spec to run.
+    ///
+    /// A failure does not stop the batch; the remaining specs still run.
+    pub fn entry_finished(&mut self, outcome: Result<(), String>) -> Option<SpecId> {
+        let entry = self.entries.get_mut(self.current)?;
+        entry.state = match outcome {
+            Ok(()) => StepState::Succeeded,
+            Err(reason) => StepState::Failed(reason),
+        };
+        if self.current + 1 >= self.entries.len() {
+            return None;
+        }
+        self.current += 1;
+        self.current_spec().cloned()
+    }
+
+    /// Stop without running the remaining entries
+    pub fn stop(&mut self) {
+        for entry in self.entries.iter_mut().skip(self.current) {
+            if entry.state == StepState::Pending {
+                entry.state = StepState::Skipped;
+            }
+        }
+    }
+
+    /// Check if every entry has run, or the batch stopped
+    pub fn is_finished(&self) -> bool {
+        self.entries.iter().all(|e| {
+            matches!(
+                e.state,
+                StepState::Succeeded | StepState::Failed(_) | StepState::Skipped
+            )
+        })
+    }
+
+    /// Count entries that succeeded, failed and were skipped
+    pub fn counts(&self) -> (usize, usize, usize) {
+        self.entries
+            .iter()
+            .fold((0, 0, 0), |(ok, failed, skipped), e| match e.state {
+                StepState::Succeeded => (ok + 1, failed, skipped),
+                StepState::Failed(_) => (ok, failed + 1, skipped),
+                StepState::Skipped => (ok, failed, skipped + 1),
+                _ => (ok, failed, skipped),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch() -> Batch {
+        Batch::new(
+            WorkflowCommandType::Tasks,
+            vec![
+                SpecId::new(1, "foo"),
+                SpecId::new(2, "bar"),
+                SpecId::new(3, "baz"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_batch_continues_after_failure() {
+        let mut b = batch();
+        assert_eq!(b.current_spec(), Some(&SpecId::new(1, "foo")));
+
+        b.start_entry();
+        let next = b.entry_finished(Err("exited with code 1".to_string()));
+        assert_eq!(next, Some(SpecId::new(2, "bar")));
+        b.start_entry();
+        assert_eq!(b.entry_finished(Ok(())), Some(SpecId::new(3, "baz")));
+        b.start_entry();
+        assert_eq!(b.entry_finished(Ok(())), None);
+
+        assert!(b.is_finished());
+        assert_eq!(b.counts(), (2, 1, 0));
+    }
+
+    #[test]
+    fn test_batch_stop_skips_remaining() {
+        let mut b = batch();
+        b.start_entry();
+        b.entry_finished(Ok(()));
+        b.start_entry();
+        b.entry_finished(Err("cancelled (interrupted)".to_string()));
+        b.stop();
+
+        assert!(b.is_finished());
+        assert_eq!(b.counts(), (1, 1, 1));
+        assert_eq!(b.entries[2].state, StepState::Skipped);
+    }
+}
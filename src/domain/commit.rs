@@ -0,0 +1,79 @@
+//! Commit domain entities for a spec branch's history.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// A commit on a spec branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitInfo {
+    /// Full commit hash
+    pub id: String,
+    /// Author name
+    pub author: String,
+    /// Author time in seconds since the Unix epoch
+    pub time: u64,
+    /// First line of the message
+    pub subject: String,
+    /// Full commit message
+    pub message: String,
+}
+
+impl CommitInfo {
+    /// Get the abbreviated commit hash
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(7)]
+    }
+
+    /// Get the task ids (e.g. `T012`) mentioned in the message
+    pub fn task_ids(&self) -> Vec<String> {
+        task_ids(&self.message)
+    }
+}
+
+/// Find the task ids (e.g. `T012`) in a text, in order of first mention
+pub fn task_ids(text: &str) -> Vec<String> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| Regex::new(r"\bT\d{3,}\b").unwrap());
+
+    let mut ids: Vec<String> = Vec::new();
+    for found in pattern.find_iter(text) {
+        if !ids.iter().any(|id| id == found.as_str()) {
+            ids.push(found.as_str().to_string());
+        }
+    }
+    ids
+}
+
+/// Find the line of a tasks.md document that defines a task id
+pub fn find_task<'a>(tasks: &'a str, id: &str) -> Option<&'a str> {
+    tasks
+        .lines()
+        .map(str::trim)
+        .find(|line| task_ids(line).first().is_some_and(|first| first == id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_ids() {
+        let commit = CommitInfo {
+            id: "0123456789abcdef".to_string(),
+            author: "Dev".to_string(),
+            time: 0,
+            subject: "T012: Add parser".to_string(),
+            message: "T012: Add parser\n\nAlso covers T013 and T012, not XT014 or T15".to_string(),
+        };
+        assert_eq!(commit.short_id(), "0123456");
+        assert_eq!(commit.task_ids(), vec!["T012", "T013"]);
+        assert!(task_ids("No tasks").is_empty());
+
+        let tasks = "## Phase 1\n- [x] T012 Add parser (depends on T011)\n- [ ] T013 [P] Add tests for T012\n";
+        assert_eq!(
+            find_task(tasks, "T012"),
+            Some("- [x] T012 Add parser (depends on T011)")
+        );
+        assert_eq!(find_task(tasks, "T011"), None);
+    }
+}
@@ -0,0 +1,247 @@
+//! Unified diffs between two versions of a text file.
+
+use similar::{ChangeTag, TextDiff};
+use std::path::PathBuf;
+
+/// Number of unchanged lines shown around each change
+const CONTEXT_LINES: usize = 3;
+
+/// How a file changed between two versions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileChange {
+    /// File did not exist before
+    Added,
+    /// File exists in both versions with different content
+    Modified,
+    /// File no longer exists
+    Deleted,
+}
+
+impl FileChange {
+    /// Get the single-letter status indicator
+    pub fn indicator(&self) -> &'static str {
+        match self {
+            Self::Added => "A",
+            Self::Modified => "M",
+            Self::Deleted => "D",
+        }
+    }
+}
+
+/// Which changes of a worktree are compared
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffScope {
+    /// Uncommitted changes, including untracked files, against `HEAD`
+    #[default]
+    WorkingTree,
+    /// Commits on the branch since it forked from the main branch
+    Branch,
+}
+
+impl DiffScope {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::WorkingTree => "Working tree",
+            Self::Branch => "Branch",
+        }
+    }
+
+    /// Get the other scope
+    pub fn toggle(&self) -> Self {
+        match self {
+            Self::WorkingTree => Self::Branch,
+            Self::Branch => Self::WorkingTree,
+        }
+    }
+}
+
+/// Kind of a line within a hunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLineKind {
+    /// Unchanged line shown for context
+    Context,
+    /// Line present only in the new version
+    Added,
+    /// Line present only in the old version
+    Removed,
+}
+
+impl DiffLineKind {
+    /// Get the unified diff prefix for this kind of line
+    pub fn prefix(&self) -> char {
+        match self {
+            Self::Context => ' ',
+            Self::Added => '+',
+            Self::Removed => '-',
+        }
+    }
+}
+
+/// A single line of a diff hunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    /// Kind of line
+    pub kind: DiffLineKind,
+    /// Line content without the trailing newline
+    pub content: String,
+}
+
+/// A contiguous group of changes with surrounding context
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    /// First line of the hunk in the old version (1-based)
+    pub old_start: usize,
+    /// Number of old lines covered by the hunk
+    pub old_len: usize,
+    /// First line of the hunk in the new version (1-based)
+    pub new_start: usize,
+    /// Number of new lines covered by the hunk
+    pub new_len: usize,
+    /// Lines of the hunk
+    pub lines: Vec<DiffLine>,
+}
+
+impl DiffHunk {
+    /// Get the unified diff hunk header, e.g. `@@ -1,3 +1,4 @@`
+    pub fn header(&self) -> String {
+        format!(
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_len, self.new_start, self.new_len
+        )
+    }
+}
+
+/// Changes to a single file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    /// Path of the file, relative to the compared directory
+    pub path: PathBuf,
+    /// How the file changed
+    pub change: FileChange,
+    /// Changed regions of the file
+    pub hunks: Vec<DiffHunk>,
+}
+
+impl FileDiff {
+    /// Diff two versions of a file, where `None` means the file does not exist.
+    ///
+    /// Returns `None` if both versions are identical.
+    pub fn between(path: PathBuf, old: Option<&str>, new: Option<&str>) -> Option<Self> {
+        let change = match (old, new) {
+            (None, None) => return None,
+            (Some(old), Some(new)) if old == new => return None,
+            (None, Some(_)) => FileChange::Added,
+            (Some(_), None) => FileChange::Deleted,
+            (Some(_), Some(_)) => FileChange::Modified,
+        };
+
+        let old = old.unwrap_or_default();
+        let new = new.unwrap_or_default();
+        let diff = TextDiff::from_lines(old, new);
+
+        let hunks = diff
+            .grouped_ops(CONTEXT_LINES)
+            .iter()
+            .filter_map(|group| {
+                let first = group.first()?;
+                let last = group.last()?;
+                let old_range = first.old_range().start..last.old_range().end;
+                let new_range = first.new_range().start..last.new_range().end;
+
+                let lines = group
+                    .iter()
+                    .flat_map(|op| diff.iter_changes(op))
+                    .map(|change| DiffLine {
+                        kind: match change.tag() {
+                            ChangeTag::Equal => DiffLineKind::Context,
+                            ChangeTag::Insert => DiffLineKind::Added,
+                            ChangeTag::Delete => DiffLineKind::Removed,
+                        },
+                        content: change.value().trim_end_matches(['\n', '\r']).to_string(),
+                    })
+                    .collect();
+
+                Some(DiffHunk {
+                    old_start: hunk_start(old_range.start, old_range.len()),
+                    old_len: old_range.len(),
+                    new_start: hunk_start(new_range.start, new_range.len()),
+                    new_len: new_range.len(),
+                    lines,
+                })
+            })
+            .collect();
+
+        Some(Self {
+            path,
+            change,
+            hunks,
+        })
+    }
+
+    /// Count added lines
+    pub fn additions(&self) -> usize {
+        self.count_lines(DiffLineKind::Added)
+    }
+
+    /// Count removed lines
+    pub fn deletions(&self) -> usize {
+        self.count_lines(DiffLineKind::Removed)
+    }
+
+    fn count_lines(&self, kind: DiffLineKind) -> usize {
+        self.hunks
+            .iter()
+            .flat_map(|h| &h.lines)
+            .filter(|l| l.kind == kind)
+            .count()
+    }
+}
+
+/// Unified diffs number empty ranges from the line before them
+fn hunk_start(start: usize, len: usize) -> usize {
+    if len == 0 {
+        start
+    } else {
+        start + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modified_file_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nb\nc\nd\nE\nf\ng\nh\ni\nj\nk\n";
+
+        let diff = FileDiff::between(PathBuf::from("plan.md"), Some(old), Some(new)).unwrap();
+        assert_eq!(diff.change, FileChange::Modified);
+        assert_eq!(diff.additions(), 2);
+        assert_eq!(diff.deletions(), 1);
+        assert_eq!(diff.hunks.len(), 1);
+
+        let hunk = &diff.hunks[0];
+        assert_eq!(hunk.header(), "@@ -2,9 +2,10 @@");
+        assert_eq!(hunk.lines[0].kind, DiffLineKind::Context);
+        assert_eq!(hunk.lines[0].content, "b");
+        assert_eq!(hunk.lines[3].kind, DiffLineKind::Removed);
+        assert_eq!(hunk.lines[3].content, "e");
+        assert_eq!(hunk.lines[4].kind, DiffLineKind::Added);
+        assert_eq!(hunk.lines[4].content, "E");
+    }
+
+    #[test]
+    fn test_added_and_deleted_files() {
+        let added = FileDiff::between(PathBuf::from("new.md"), None, Some("x\ny\n")).unwrap();
+        assert_eq!(added.change, FileChange::Added);
+        assert_eq!(added.hunks[0].header(), "@@ -0,0 +1,2 @@");
+
+        let deleted = FileDiff::between(PathBuf::from("old.md"), Some("x\n"), None).unwrap();
+        assert_eq!(deleted.change, FileChange::Deleted);
+        assert_eq!(deleted.deletions(), 1);
+
+        assert!(FileDiff::between(PathBuf::from("same.md"), Some("x\n"), Some("x\n")).is_none());
+    }
+}
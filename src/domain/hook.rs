@@ -0,0 +1,125 @@
+//! Hook run entity tracking pre- and post-workflow hook commands.
+
+use crate::config::HookConfig;
+use std::collections::VecDeque;
+
+/// When a hook runs relative to its workflow command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookPhase {
+    /// Before the workflow; a failure blocks it
+    Pre,
+    /// After the workflow finished
+    Post,
+}
+
+impl HookPhase {
+    /// Display name for UI
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Pre => "pre-hook",
+            Self::Post => "post-hook",
+        }
+    }
+}
+
+/// A queue of hooks run one after another, stopping at the first failure
+#[derive(Debug, Clone)]
+pub struct HookRun {
+    /// Phase the hooks belong to
+    pub phase: HookPhase,
+    /// Hooks not started yet
+    pending: VecDeque<HookConfig>,
+    /// Hook currently running
+    current: Option<HookConfig>,
+    /// Reason the run failed, if it did
+    failure: Option<String>,
+}
+
+impl HookRun {
+    /// Create a hook run
+    pub fn new(phase: HookPhase, hooks: Vec<HookConfig>) -> Self {
+        Self {
+            phase,
+            pending: hooks.into(),
+            current: None,
+            failure: None,
+        }
+    }
+
+    /// Start the next hook, if the run has not failed
+    pub fn start_next(&mut self) -> Option<&HookConfig> {
+        if self.failure.is_some() {
+            return None;
+        }
+        self.current = self.pending.pop_front();
+        self.current.as_ref()
+    }
+
+    /// Record the current hook's exit code, returning whether it succeeded
+    pub fn finish_current(&mut self, exit_code: i32) -> bool {
+        let Some(hook) = self.current.take() else {
+            return true;
+        };
+        if hook.is_success(exit_code) {
+            return true;
+        }
+        self.failure = Some(format!("`{}` exited with code {}", hook.run, exit_code));
+        false
+    }
+
+    /// Record that the current hook could not run to completion
+    pub fn fail_current(&mut self, reason: &str) {
+        let run = self.current.take().map(|h| h.run).unwrap_or_default();
+        self.failure = Some(format!("`{}` {}", run, reason));
+    }
+
+    /// Reason the run failed, if it did
+    pub fn failure(&self) -> Option<&str> {
+        self.failure.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HookCondition;
+
+    fn hook(run: &str) -> HookConfig {
+        HookConfig {
+            run: run.to_string(),
+            when: HookCondition::Success,
+            success_codes: vec![0],
+        }
+    }
+
+    #[test]
+    fn test_hook_run_in_order() {
+        let mut run = HookRun::new(
+            HookPhase::Post,
+            vec![hook("cargo test"), hook("cargo clippy")],
+        );
+
+        assert_eq!(run.start_next().unwrap().run, "cargo test");
+        assert!(run.finish_current(0));
+        assert_eq!(run.start_next().unwrap().run, "cargo clippy");
+        assert!(run.finish_current(0));
+        assert!(run.start_next().is_none());
+        assert!(run.failure().is_none());
+    }
+
+    #[test]
+    fn test_hook_run_stops_at_failure() {
+        let mut run = HookRun::new(
+            HookPhase::Pre,
+            vec![hook("cargo fmt --check"), hook("true")],
+        );
+
+        run.start_next();
+        assert!(!run.finish_current(1));
+        assert!(run.start_next().is_none());
+        assert_eq!(
+            run.failure(),
+            Some("`cargo fmt --check` exited with code 1")
+        );
+    }
+}
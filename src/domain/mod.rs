@@ -3,17 +3,40 @@
 //! This module contains the core business entities:
 //! - Specification: A feature being developed
 //! - Worktree: A git worktree instance
+//! - Commit: A commit on a spec branch
 //! - Workflow: Workflow command execution
+//! - Pipeline: A chain of workflow commands
+//! - Batch: One workflow command run across several specs
+//! - Hook: Shell commands run around a workflow command
+//! - Diff/Snapshot: Reviewing changes an agent made to spec artifacts
+//! - Run: Records of finished workflow runs
 //! - Project: The overall repository context
 
+mod batch;
+mod commit;
+mod diff;
+mod hook;
+mod pipeline;
 mod project;
+mod run;
+mod snapshot;
 mod spec;
 mod workflow;
 mod worktree;
 
+pub use batch::{Batch, BatchEntry};
+pub use commit::{find_task, task_ids, CommitInfo};
+pub use diff::{DiffHunk, DiffLine, DiffLineKind, DiffScope, FileChange, FileDiff};
+pub use hook::{HookPhase, HookRun};
+pub use pipeline::{Pipeline, PipelineStep, StepState};
 pub use project::Project;
+pub use run::{RunOutcome, RunRecord, UsageSummary, UsageTotals};
+pub use snapshot::ArtifactSnapshot;
 pub use spec::{ArtifactType, SpecArtifacts, SpecId, Specification, WorkflowPhase};
 pub use workflow::{
-    ExecutionState, OutputLine, OutputStream, WorkflowCommand, WorkflowCommandType,
+    AgentEvent, CancelOutcome, ExecutionState, OutputLine, OutputStream, TokenUsage,
+    WorkflowCommand, WorkflowCommandType,
+};
+pub use worktree::{
+    BranchSafety, ChangedFile, GitOp, GitRun, Worktree, WorktreeStatus, WorktreeSyncStatus,
 };
-pub use worktree::{Worktree, WorktreeStatus, WorktreeSyncStatus};
@@ -0,0 +1,235 @@
+//! Workflow pipeline entity chaining several workflow commands.
+
+use super::{SpecId, WorkflowCommandType};
+
+/// State of a single pipeline step
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepState {
+    /// Not started yet
+    Pending,
+    /// Command is running
+    Running,
+    /// Command finished and its artifact is present
+    Succeeded,
+    /// Command finished; waiting for the user to review before continuing
+    AwaitingReview,
+    /// Command failed, was cancelled, or its artifact is missing
+    Failed(String),
+    /// Pipeline stopped before this step ran
+    Skipped,
+}
+
+impl StepState {
+    /// Get indicator for UI
+    pub fn indicator(&self) -> &'static str {
+        match self {
+            Self::Pending => "○",
+            Self::Running => "▶",
+            Self::Succeeded => "✓",
+            Self::AwaitingReview => "⏸",
+            Self::Failed(_) => "✗",
+            Self::Skipped => "-",
+        }
+    }
+}
+
+/// A step in a pipeline
+#[derive(Debug, Clone)]
+pub struct PipelineStep {
+    /// Command run by this step
+    pub command: WorkflowCommandType,
+    /// Current state
+    pub state: StepState,
+    /// Pause for review after this step succeeds
+    pub review_gate: bool,
+}
+
+/// A chain of workflow commands run one after another for a spec
+#[derive(Debug, Clone)]
+pub struct Pipeline {
+    /// Spec the pipeline runs for
+    pub spec_id: SpecId,
+    /// Steps in execution order
+    pub steps: Vec<PipelineStep>,
+    /// Index of the current step
+    current: usize,
+}
+
+impl Pipeline {
+    /// Create a pipeline; steps listed in `review_gates` pause after success
+    pub fn new(
+        spec_id: SpecId,
+        commands: &[WorkflowCommandType],
+        review_gates: &[WorkflowCommandType],
+    ) -> Self {
+        let steps = commands
+            .iter()
+            .map(|&command| PipelineStep {
+                command,
+                state: StepState::Pending,
+                review_gate: review_gates.contains(&command),
+            })
+            .collect();
+
+        Self {
+            spec_id,
+            steps,
+            current: 0,
+        }
+    }
+
+    /// Get the current step
+    pub fn current_step(&self) -> Option<&PipelineStep> {
+        self.steps.get(self.current)
+    }
+
+    /// Get the command of the current step
+    pub fn current_command(&self) -> Option<WorkflowCommandType> {
+        self.current_step().map(|s| s.command)
+    }
+
+    /// Mark the current step as running
+    pub fn start_step(&mut self) {
+        if let Some(step) = self.steps.get_mut(self.current) {
+            step.state = StepState::Running;
+        }
+    }
+
+    /// Record a successful step, pausing at review gates.
+    ///
+    /// Returns the next command to run, if the pipeline should continue.
+    pub fn step_succeeded(&mut self) -> Option<WorkflowCommandType> {
+        let step = self.steps.get_mut(self.current)?;
+        if step.review_gate {
+            step.state = StepState::AwaitingReview;
+            return None;
+        }
+        step.state = StepState::Succeeded;
+        self.advance()
+    }
+
+    /// Record a failed step and skip the remaining ones
+    pub fn step_failed(&mut self, reason: String) {
+        if let Some(step) = self.steps.get_mut(self.current) {
+            step.state = StepState::Failed(reason);
+        }
+        for step in self.steps.iter_mut().skip(self.current + 1) {
+            step.state = StepState::Skipped;
+        }
+    }
+
+    /// Continue past a review gate, returning the next command to run
+    pub fn approve(&mut self) -> Option<WorkflowCommandType> {
+        let step = self.steps.get_mut(self.current)?;
+        if step.state != StepState::AwaitingReview {
+            return None;
+        }
+        step.state = StepState::Succeeded;
+        self.advance()
+    }
+
+    /// Stop without running the remaining steps
+    pub fn stop(&mut self) {
+        for step in self.steps.iter_mut().skip(self.current) {
+            match step.state {
+                StepState::AwaitingReview => step.state = StepState::Succeeded,
+                StepState::Pending => step.state = StepState::Skipped,
+                _ => {}
+            }
+        }
+    }
+
+    /// Check if the pipeline is paused at a review gate
+    pub fn is_awaiting_review(&self) -> bool {
+        self.current_step()
+            .is_some_and(|s| s.state == StepState::AwaitingReview)
+    }
+
+    /// Check if every step has run, or the pipeline stopped
+    pub fn is_finished(&self) -> bool {
+        self.steps.iter().all(|s| {
+            matches!(
+                s.state,
+                StepState::Succeeded | StepState::Failed(_) | StepState::Skipped
+            )
+        })
+    }
+
+    /// Move to the next step, returning its command
+    fn advance(&mut self) -> Option<WorkflowCommandType> {
+        if self.current + 1 >= self.steps.len() {
+            return None;
+        }
+        self.current += 1;
+        self.current_command()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipeline(gates: &[WorkflowCommandType]) -> Pipeline {
+        Pipeline::new(
+            SpecId::new(3, "foo"),
+            &[
+                WorkflowCommandType::Specify,
+                WorkflowCommandType::Plan,
+                WorkflowCommandType::Tasks,
+            ],
+            gates,
+        )
+    }
+
+    #[test]
+    fn test_pipeline_runs_all_steps() {
+        let mut p = pipeline(&[]);
+        assert_eq!(p.current_command(), Some(WorkflowCommandType::Specify));
+
+        p.start_step();
+        assert_eq!(p.step_succeeded(), Some(WorkflowCommandType::Plan));
+        p.start_step();
+        assert_eq!(p.step_succeeded(), Some(WorkflowCommandType::Tasks));
+        p.start_step();
+        assert_eq!(p.step_succeeded(), None);
+
+        assert!(p.is_finished());
+        assert!(p.steps.iter().all(|s| s.state == StepState::Succeeded));
+    }
+
+    #[test]
+    fn test_pipeline_review_gate() {
+        let mut p = pipeline(&[WorkflowCommandType::Specify]);
+
+        p.start_step();
+        assert_eq!(p.step_succeeded(), None);
+        assert!(p.is_awaiting_review());
+        assert!(!p.is_finished());
+
+        assert_eq!(p.approve(), Some(WorkflowCommandType::Plan));
+        assert_eq!(p.steps[0].state, StepState::Succeeded);
+
+        p.start_step();
+        p.step_succeeded();
+        p.stop();
+        assert!(p.is_finished());
+        assert_eq!(p.steps[2].state, StepState::Skipped);
+    }
+
+    #[test]
+    fn test_pipeline_failure_skips_remaining() {
+        let mut p = pipeline(&[]);
+
+        p.start_step();
+        p.step_succeeded();
+        p.start_step();
+        p.step_failed("plan.md was not created".to_string());
+
+        assert!(p.is_finished());
+        assert_eq!(
+            p.steps[1].state,
+            StepState::Failed("plan.md was not created".to_string())
+        );
+        assert_eq!(p.steps[2].state, StepState::Skipped);
+    }
+}
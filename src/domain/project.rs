@@ -1,7 +1,7 @@
 //! Project entity representing the overall repository context.
 
 use crate::config::ProjectConfig;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Represents the overall git repository context
 #[derive(Debug, Clone)]
@@ -76,6 +76,18 @@ impl Project {
     pub fn worktree_path_for_branch(&self, branch: &str) -> PathBuf {
         self.worktree_directory.join(branch)
     }
+
+    /// Get a spec directory's path relative to the repository root, which is
+    /// also its path inside any worktree
+    pub fn relative_spec_path(&self, spec_directory: &Path) -> PathBuf {
+        match spec_directory.strip_prefix(&self.root_path) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => {
+                let name = spec_directory.file_name().unwrap_or_default();
+                PathBuf::from(&self.config.git.specs_directory).join(name)
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -109,4 +121,19 @@ mod tests {
             PathBuf::from("/tmp/test-project/.worktrees/001-feature")
         );
     }
+
+    #[test]
+    fn test_relative_spec_path() {
+        let config = ProjectConfig::default();
+        let project = Project::new(PathBuf::from("/tmp/test-project"), config);
+
+        assert_eq!(
+            project.relative_spec_path(Path::new("/tmp/test-project/specs/001-feature")),
+            PathBuf::from("specs/001-feature")
+        );
+        assert_eq!(
+            project.relative_spec_path(Path::new("/elsewhere/specs/002-other")),
+            PathBuf::from("specs/002-other")
+        );
+    }
 }
@@ -0,0 +1,231 @@
+//! Records of finished workflow runs.
+
+use super::workflow::{ExecutionState, TokenUsage, WorkflowCommand};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How a workflow run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RunOutcome {
+    /// Exited with code 0
+    Succeeded,
+    /// Exited with a non-zero code or could not run
+    Failed,
+    /// Cancelled by the user
+    Cancelled,
+}
+
+/// A finished workflow run, as stored in the run history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Spec the command ran on
+    pub spec_id: String,
+    /// Command name, e.g. `plan`
+    pub command: String,
+    /// When the run finished, in seconds since the Unix epoch
+    pub finished_at: u64,
+    /// How the run ended
+    pub outcome: RunOutcome,
+    /// Exit code, if the process exited normally
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    /// Run duration in milliseconds, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Path to the run's log file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<PathBuf>,
+    /// Hash of the commit made after the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Tokens reported by the agent during the run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<TokenUsage>,
+    /// Estimated cost in USD, if pricing for the model is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    /// Attempt number, starting at 1
+    #[serde(default = "default_attempt")]
+    pub attempt: u32,
+    /// Agent session the run can be continued in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+fn default_attempt() -> u32 {
+    1
+}
+
+impl RunRecord {
+    /// Build a record for a finished command, or `None` if it is still running
+    pub fn from_command(command: &WorkflowCommand) -> Option<Self> {
+        let (outcome, exit_code, duration_ms) = match &command.state {
+            ExecutionState::Completed {
+                exit_code,
+                duration,
+            } => {
+                let outcome = if *exit_code == 0 {
+                    RunOutcome::Succeeded
+                } else {
+                    RunOutcome::Failed
+                };
+                (outcome, Some(*exit_code), Some(duration.as_millis() as u64))
+            }
+            ExecutionState::Cancelled { .. } => (RunOutcome::Cancelled, None, None),
+            ExecutionState::Failed { .. } => (RunOutcome::Failed, None, None),
+            _ => return None,
+        };
+
+        let finished_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Some(Self {
+            spec_id: command.spec_id.as_str().to_string(),
+            command: command.command_type.config_key().to_string(),
+            finished_at,
+            outcome,
+            exit_code,
+            duration_ms,
+            log_file: command.log_path.clone(),
+            commit: None,
+            usage: command.usage.clone(),
+            cost_usd: None,
+            attempt: command.attempt,
+            session_id: command.session_id.clone(),
+        })
+    }
+}
+
+/// Token and cost totals over a set of runs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageTotals {
+    /// Runs that reported usage
+    pub runs: usize,
+    /// Input tokens
+    pub input_tokens: u64,
+    /// Output tokens
+    pub output_tokens: u64,
+    /// Summed cost of the runs with known pricing
+    pub cost_usd: f64,
+    /// Runs whose cost is unknown because no pricing matched
+    pub unpriced_runs: usize,
+}
+
+impl UsageTotals {
+    fn add(&mut self, usage: &TokenUsage, cost_usd: Option<f64>) {
+        self.runs += 1;
+        self.input_tokens += usage.input_tokens;
+        self.output_tokens += usage.output_tokens;
+        match cost_usd {
+            Some(cost) => self.cost_usd += cost,
+            None => self.unpriced_runs += 1,
+        }
+    }
+}
+
+/// Token usage aggregated from the run history
+#[derive(Debug, Clone, Default)]
+pub struct UsageSummary {
+    /// Totals over all runs
+    pub total: UsageTotals,
+    /// Totals per spec ID
+    pub by_spec: BTreeMap<String, UsageTotals>,
+    /// Totals per command (workflow phase)
+    pub by_command: BTreeMap<String, UsageTotals>,
+    /// Runs that reported usage, newest first
+    pub runs: Vec<RunRecord>,
+}
+
+impl UsageSummary {
+    /// Aggregate the runs that reported usage
+    pub fn from_records(records: &[RunRecord]) -> Self {
+        let mut summary = Self::default();
+        for record in records {
+            let Some(usage) = &record.usage else {
+                continue;
+            };
+            summary.total.add(usage, record.cost_usd);
+            summary
+                .by_spec
+                .entry(record.spec_id.clone())
+                .or_default()
+                .add(usage, record.cost_usd);
+            summary
+                .by_command
+                .entry(record.command.clone())
+                .or_default()
+                .add(usage, record.cost_usd);
+            summary.runs.push(record.clone());
+        }
+        summary.runs.reverse();
+        summary
+    }
+
+    /// Whether no run reported usage
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{SpecId, WorkflowCommandType};
+
+    #[test]
+    fn test_record_from_command() {
+        let mut command = WorkflowCommand::new(WorkflowCommandType::Plan, SpecId::new(3, "foo"));
+        assert!(RunRecord::from_command(&command).is_none());
+
+        command.start();
+        command.complete(0);
+        let record = RunRecord::from_command(&command).unwrap();
+        assert_eq!(record.spec_id, "003-foo");
+        assert_eq!(record.command, "plan");
+        assert_eq!(record.outcome, RunOutcome::Succeeded);
+        assert_eq!(record.exit_code, Some(0));
+        assert!(record.duration_ms.is_some());
+    }
+
+    #[test]
+    fn test_usage_summary() {
+        let mut command = WorkflowCommand::new(WorkflowCommandType::Plan, SpecId::new(3, "foo"));
+        command.start();
+        command.complete(0);
+        let base = RunRecord::from_command(&command).unwrap();
+
+        let usage = |input, output| TokenUsage {
+            model: Some("claude-sonnet".to_string()),
+            input_tokens: input,
+            output_tokens: output,
+        };
+        let records = vec![
+            RunRecord {
+                usage: Some(usage(1000, 200)),
+                cost_usd: Some(0.5),
+                ..base.clone()
+            },
+            base.clone(),
+            RunRecord {
+                spec_id: "004-bar".to_string(),
+                command: "tasks".to_string(),
+                usage: Some(usage(500, 100)),
+                ..base
+            },
+        ];
+
+        let summary = UsageSummary::from_records(&records);
+        assert_eq!(summary.runs.len(), 2);
+        assert_eq!(summary.runs[0].spec_id, "004-bar");
+        assert_eq!(summary.total.input_tokens, 1500);
+        assert_eq!(summary.total.unpriced_runs, 1);
+        assert_eq!(summary.by_spec["003-foo"].output_tokens, 200);
+        assert!((summary.by_command["plan"].cost_usd - 0.5).abs() < 1e-9);
+        assert_eq!(summary.by_command["tasks"].runs, 1);
+    }
+}
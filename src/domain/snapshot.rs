@@ -0,0 +1,170 @@
+//! Snapshots of a spec directory's artifacts.
+//!
+//! A snapshot is taken before a workflow run so the changes an agent made
+//! can be reviewed, and individual files rolled back, once it finishes.
+
+use super::diff::FileDiff;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Files larger than this are left out of snapshots
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Contents of a spec directory at a point in time
+#[derive(Debug, Clone)]
+pub struct ArtifactSnapshot {
+    /// Directory the snapshot was taken of
+    directory: PathBuf,
+    /// File contents keyed by path relative to the directory
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl ArtifactSnapshot {
+    /// Capture the text files in a directory, recursively
+    pub fn capture(directory: &Path) -> Self {
+        Self {
+            directory: directory.to_path_buf(),
+            files: read_text_files(directory),
+        }
+    }
+
+    /// Get the directory the snapshot was taken of
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Diff the snapshot against the directory's current contents
+    pub fn changes(&self) -> Vec<FileDiff> {
+        let current = read_text_files(&self.directory);
+        let paths: BTreeSet<&PathBuf> = self.files.keys().chain(current.keys()).collect();
+
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                FileDiff::between(
+                    path.clone(),
+                    self.files.get(path).map(String::as_str),
+                    current.get(path).map(String::as_str),
+                )
+            })
+            .collect()
+    }
+
+    /// Diff a single file against its snapshotted content
+    pub fn change(&self, path: &Path) -> Option<FileDiff> {
+        let current = fs::read_to_string(self.directory.join(path)).ok();
+        FileDiff::between(
+            path.to_path_buf(),
+            self.files.get(path).map(String::as_str),
+            current.as_deref(),
+        )
+    }
+
+    /// Restore a file to its snapshotted content, removing it if it did not exist
+    pub fn restore(&self, path: &Path) -> io::Result<()> {
+        let target = self.directory.join(path);
+        match self.files.get(path) {
+            Some(content) => {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&target, content)
+            }
+            None if target.exists() => fs::remove_file(&target),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Read all UTF-8 files below a directory, skipping anything unreadable
+fn read_text_files(directory: &Path) -> BTreeMap<PathBuf, String> {
+    let mut files = BTreeMap::new();
+    collect_files(directory, directory, &mut files);
+    files
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut BTreeMap<PathBuf, String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+
+        if metadata.is_dir() {
+            collect_files(root, &path, files);
+        } else if metadata.is_file() && metadata.len() <= MAX_FILE_SIZE {
+            if let (Ok(content), Ok(relative)) =
+                (fs::read_to_string(&path), path.strip_prefix(root))
+            {
+                files.insert(relative.to_path_buf(), content);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::diff::FileChange;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshot_changes() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("spec.md"), "# Spec\n").unwrap();
+        fs::write(dir.path().join("plan.md"), "# Plan\n").unwrap();
+        fs::write(dir.path().join("research.md"), "notes\n").unwrap();
+
+        let snapshot = ArtifactSnapshot::capture(dir.path());
+
+        fs::write(dir.path().join("plan.md"), "# Plan\n\nMore\n").unwrap();
+        fs::remove_file(dir.path().join("research.md")).unwrap();
+        fs::create_dir(dir.path().join("contracts")).unwrap();
+        fs::write(dir.path().join("contracts/api.md"), "GET /\n").unwrap();
+
+        let changes: Vec<(PathBuf, FileChange)> = snapshot
+            .changes()
+            .into_iter()
+            .map(|d| (d.path, d.change))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (PathBuf::from("contracts/api.md"), FileChange::Added),
+                (PathBuf::from("plan.md"), FileChange::Modified),
+                (PathBuf::from("research.md"), FileChange::Deleted),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("plan.md"), "# Plan\n").unwrap();
+        fs::write(dir.path().join("spec.md"), "# Spec\n").unwrap();
+
+        let snapshot = ArtifactSnapshot::capture(dir.path());
+
+        fs::write(dir.path().join("plan.md"), "rewritten\n").unwrap();
+        fs::remove_file(dir.path().join("spec.md")).unwrap();
+        fs::write(dir.path().join("tasks.md"), "- [ ] T001\n").unwrap();
+
+        snapshot.restore(Path::new("plan.md")).unwrap();
+        snapshot.restore(Path::new("spec.md")).unwrap();
+        snapshot.restore(Path::new("tasks.md")).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.path().join("plan.md")).unwrap(),
+            "# Plan\n"
+        );
+        assert!(dir.path().join("spec.md").exists());
+        assert!(!dir.path().join("tasks.md").exists());
+        assert!(snapshot.changes().is_empty());
+    }
+}
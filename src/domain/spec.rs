@@ -78,6 +78,19 @@ pub struct Specification {
 }
 
 impl Specification {
+    /// Rescan the spec directory for artifacts and update the phase
+    pub fn refresh_artifacts(&mut self) {
+        self.artifacts = SpecArtifacts::scan(&self.directory);
+        self.phase = WorkflowPhase::from_artifacts(&self.artifacts);
+    }
+
+    /// Rescan another copy of the spec directory, such as the one in the
+    /// spec's worktree, and update the phase from it
+    pub fn refresh_artifacts_from(&mut self, directory: &Path) {
+        self.artifacts = SpecArtifacts::scan(directory);
+        self.phase = WorkflowPhase::from_artifacts(&self.artifacts);
+    }
+
     /// Create a new Specification from directory info
     pub fn from_directory(directory: PathBuf, artifacts: SpecArtifacts) -> Result<Self, SpecError> {
         let dir_name = directory
@@ -125,6 +138,17 @@ pub struct SpecArtifacts {
 }
 
 impl SpecArtifacts {
+    /// Check whether an artifact is present
+    pub fn has(&self, artifact: ArtifactType) -> bool {
+        match artifact {
+            ArtifactType::Spec => self.has_spec,
+            ArtifactType::Plan => self.has_plan,
+            ArtifactType::Tasks => self.has_tasks,
+            ArtifactType::Research => self.has_research,
+            ArtifactType::DataModel => false,
+        }
+    }
+
     /// Scan a directory for artifacts
     pub fn scan(directory: &Path) -> Self {
         let spec_path = directory.join("spec.md");
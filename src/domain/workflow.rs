@@ -1,6 +1,6 @@
 //! Workflow command entity and related types.
 
-use super::SpecId;
+use super::{ArtifactType, SpecId};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
@@ -16,6 +16,30 @@ pub enum WorkflowCommandType {
 }
 
 impl WorkflowCommandType {
+    /// All commands, in workflow order
+    pub const ALL: [Self; 5] = [
+        Self::Specify,
+        Self::Clarify,
+        Self::Plan,
+        Self::Tasks,
+        Self::Implement,
+    ];
+
+    /// Parse a command from its configuration key
+    pub fn from_config_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.config_key() == key)
+    }
+
+    /// Artifact this command is expected to leave behind, if any
+    pub fn expected_artifact(&self) -> Option<ArtifactType> {
+        match self {
+            Self::Specify | Self::Clarify => Some(ArtifactType::Spec),
+            Self::Plan => Some(ArtifactType::Plan),
+            Self::Tasks => Some(ArtifactType::Tasks),
+            Self::Implement => None,
+        }
+    }
+
     /// Get the MCP tool name for this command
     pub fn tool_name(&self) -> &'static str {
         match self {
@@ -27,6 +51,17 @@ impl WorkflowCommandType {
         }
     }
 
+    /// Get the configuration key for this command (e.g. `[workflow.commands.plan]`)
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            Self::Specify => "specify",
+            Self::Clarify => "clarify",
+            Self::Plan => "plan",
+            Self::Tasks => "tasks",
+            Self::Implement => "implement",
+        }
+    }
+
     /// Get the display name for this command
     pub fn display_name(&self) -> &'static str {
         match self {
@@ -69,12 +104,36 @@ pub enum ExecutionState {
     },
     /// Command completed successfully
     Completed { exit_code: i32, duration: Duration },
+    /// Cancellation requested, waiting for the process group to exit
+    Cancelling { started_at: Instant },
     /// Command was cancelled by user
-    Cancelled,
+    Cancelled { outcome: CancelOutcome },
     /// Command failed with error
     Failed { error: String },
 }
 
+/// How a cancelled command was brought down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CancelOutcome {
+    /// Exited after SIGINT
+    Interrupted,
+    /// Exited after SIGTERM
+    Terminated,
+    /// Had to be killed with SIGKILL
+    Killed,
+}
+
+impl CancelOutcome {
+    /// Get the display name for this outcome
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Interrupted => "interrupted",
+            Self::Terminated => "terminated",
+            Self::Killed => "killed",
+        }
+    }
+}
+
 impl ExecutionState {
     /// Check if the command is still running
     pub fn is_running(&self) -> bool {
@@ -86,11 +145,16 @@ impl ExecutionState {
         matches!(self, Self::Pending)
     }
 
+    /// Check if cancellation is in progress
+    pub fn is_cancelling(&self) -> bool {
+        matches!(self, Self::Cancelling { .. })
+    }
+
     /// Check if the command has finished (completed, cancelled, or failed)
     pub fn is_finished(&self) -> bool {
         matches!(
             self,
-            Self::Completed { .. } | Self::Cancelled | Self::Failed { .. }
+            Self::Completed { .. } | Self::Cancelled { .. } | Self::Failed { .. }
         )
     }
 
@@ -101,7 +165,8 @@ impl ExecutionState {
             Self::Running { .. } => "🔄",
             Self::Completed { exit_code, .. } if *exit_code == 0 => "✓",
             Self::Completed { .. } => "✗",
-            Self::Cancelled => "⊘",
+            Self::Cancelling { .. } => "⊘",
+            Self::Cancelled { .. } => "⊘",
             Self::Failed { .. } => "✗",
         }
     }
@@ -120,6 +185,14 @@ pub struct WorkflowCommand {
     pub output: Vec<OutputLine>,
     /// Path to log file (if persisted)
     pub log_path: Option<PathBuf>,
+    /// Tokens reported by the agent so far
+    pub usage: Option<TokenUsage>,
+    /// Attempt number, starting at 1
+    pub attempt: u32,
+    /// Attempts allowed by the command's retry policy
+    pub max_attempts: u32,
+    /// Agent session the command ran in, if known
+    pub session_id: Option<String>,
 }
 
 impl WorkflowCommand {
@@ -131,9 +204,20 @@ impl WorkflowCommand {
             state: ExecutionState::Pending,
             output: Vec::new(),
             log_path: None,
+            usage: None,
+            attempt: 1,
+            max_attempts: 1,
+            session_id: None,
         }
     }
 
+    /// Set the attempt number and the number of attempts allowed
+    pub fn with_attempt(mut self, attempt: u32, max_attempts: u32) -> Self {
+        self.attempt = attempt;
+        self.max_attempts = max_attempts.max(attempt);
+        self
+    }
+
     /// Mark the command as running
     pub fn start(&mut self) {
         self.state = ExecutionState::Running {
@@ -160,9 +244,18 @@ impl WorkflowCommand {
         }
     }
 
+    /// Mark the command as being cancelled
+    pub fn begin_cancel(&mut self) {
+        if self.state.is_running() {
+            self.state = ExecutionState::Cancelling {
+                started_at: Instant::now(),
+            };
+        }
+    }
+
     /// Mark the command as cancelled
-    pub fn cancel(&mut self) {
-        self.state = ExecutionState::Cancelled;
+    pub fn cancel(&mut self, outcome: CancelOutcome) {
+        self.state = ExecutionState::Cancelled { outcome };
     }
 
     /// Mark the command as failed
@@ -176,9 +269,17 @@ impl WorkflowCommand {
             timestamp: Instant::now(),
             content,
             stream,
+            event: None,
         });
     }
 
+    /// Add token usage reported by the agent to the run's total
+    pub fn record_usage(&mut self, usage: &TokenUsage) {
+        self.usage
+            .get_or_insert_with(TokenUsage::default)
+            .accumulate(usage);
+    }
+
     /// Get all output as a single string
     pub fn output_text(&self) -> String {
         self.output
@@ -198,6 +299,115 @@ pub struct OutputLine {
     pub content: String,
     /// Which stream (stdout/stderr) it came from
     pub stream: OutputStream,
+    /// Structured agent event the line summarizes, if any
+    pub event: Option<AgentEvent>,
+}
+
+/// Structured event parsed from an agent's output stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AgentEvent {
+    /// Text written by the agent
+    Text(String),
+    /// Tool invoked by the agent
+    ToolCall {
+        /// Tool name
+        name: String,
+        /// Tool input, as reported by the agent
+        input: String,
+    },
+    /// Output returned to the agent by a tool
+    ToolResult {
+        /// Tool output
+        output: String,
+        /// Whether the tool reported an error
+        is_error: bool,
+    },
+    /// Token usage reported by the agent
+    Usage(TokenUsage),
+    /// Session identifier the agent can resume later
+    Session(String),
+}
+
+impl AgentEvent {
+    /// Get a one-line summary for display
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Text(text) => text.lines().next().unwrap_or_default().to_string(),
+            Self::ToolCall { name, input } => {
+                format!("Tool: {} {}", name, first_line(input))
+            }
+            Self::ToolResult { output, is_error } => {
+                let label = if *is_error {
+                    "Tool error"
+                } else {
+                    "Tool result"
+                };
+                let count = output.lines().count();
+                format!(
+                    "{} ({} line{})",
+                    label,
+                    count,
+                    if count == 1 { "" } else { "s" }
+                )
+            }
+            Self::Usage(usage) => match usage.model {
+                Some(ref model) => format!(
+                    "Tokens: {} in / {} out ({})",
+                    usage.input_tokens, usage.output_tokens, model
+                ),
+                None => format!(
+                    "Tokens: {} in / {} out",
+                    usage.input_tokens, usage.output_tokens
+                ),
+            },
+            Self::Session(id) => format!("Session: {}", id),
+        }
+    }
+
+    /// Get the content shown when the event's block is expanded
+    pub fn body(&self) -> Option<&str> {
+        match self {
+            Self::ToolCall { input, .. } => Some(input),
+            Self::ToolResult { output, .. } => Some(output),
+            Self::Text(_) | Self::Usage(_) | Self::Session(_) => None,
+        }
+    }
+}
+
+/// Token counts reported for a run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    /// Model that consumed the tokens, if reported
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Input (prompt) tokens
+    #[serde(default)]
+    pub input_tokens: u64,
+    /// Output (completion) tokens
+    #[serde(default)]
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Add another report's counts, keeping the first model seen
+    pub fn accumulate(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        if self.model.is_none() {
+            self.model = other.model.clone();
+        }
+    }
+}
+
+/// First line of a string, truncated for one-line summaries
+fn first_line(s: &str) -> String {
+    const MAX_CHARS: usize = 80;
+    let line = s.lines().next().unwrap_or_default();
+    if line.chars().count() > MAX_CHARS {
+        format!("{}…", line.chars().take(MAX_CHARS).collect::<String>())
+    } else {
+        line.to_string()
+    }
 }
 
 /// Output stream type
@@ -205,6 +415,8 @@ pub struct OutputLine {
 pub enum OutputStream {
     Stdout,
     Stderr,
+    /// Input sent to the process
+    Stdin,
 }
 
 impl OutputStream {
@@ -213,6 +425,7 @@ impl OutputStream {
         match self {
             Self::Stdout => "",
             Self::Stderr => "!",
+            Self::Stdin => ">",
         }
     }
 }
@@ -240,6 +453,29 @@ mod tests {
         assert!(cmd.state.is_finished());
     }
 
+    #[test]
+    fn test_cancellation_transitions() {
+        let mut cmd = WorkflowCommand::new(WorkflowCommandType::Plan, SpecId::new(1, "test"));
+
+        // Nothing to cancel before the command starts
+        cmd.begin_cancel();
+        assert!(cmd.state.is_pending());
+
+        cmd.start();
+        cmd.begin_cancel();
+        assert!(cmd.state.is_cancelling());
+        assert!(!cmd.state.is_finished());
+
+        cmd.cancel(CancelOutcome::Terminated);
+        assert!(matches!(
+            cmd.state,
+            ExecutionState::Cancelled {
+                outcome: CancelOutcome::Terminated
+            }
+        ));
+        assert!(cmd.state.is_finished());
+    }
+
     #[test]
     fn test_output_collection() {
         let mut cmd = WorkflowCommand::new(WorkflowCommandType::Specify, SpecId::new(1, "test"));
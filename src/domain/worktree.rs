@@ -1,6 +1,7 @@
 //! Worktree entity and related types.
 
 use super::SpecId;
+use crate::config::{PullMode, UpdateMode};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -17,6 +18,13 @@ pub struct Worktree {
     pub spec_id: Option<SpecId>,
     /// Is this the main worktree?
     pub is_main: bool,
+    /// Is HEAD detached?
+    pub is_detached: bool,
+    /// Reason the worktree is locked against pruning and removal, empty if
+    /// none was given
+    pub locked: Option<String>,
+    /// Reason git would prune the worktree, e.g. its directory is missing
+    pub prunable: Option<String>,
 }
 
 impl Worktree {
@@ -31,6 +39,9 @@ impl Worktree {
             status: WorktreeStatus::Unknown,
             spec_id,
             is_main,
+            is_detached: false,
+            locked: None,
+            prunable: None,
         }
     }
 
@@ -43,6 +54,18 @@ impl Worktree {
     pub fn display_name(&self) -> &str {
         &self.branch
     }
+
+    /// Get the labels of the worktree's detached, locked and prunable states
+    pub fn flags(&self) -> Vec<&'static str> {
+        [
+            (self.is_detached, "detached"),
+            (self.locked.is_some(), "locked"),
+            (self.prunable.is_some(), "prunable"),
+        ]
+        .into_iter()
+        .filter_map(|(set, flag)| set.then_some(flag))
+        .collect()
+    }
 }
 
 /// Working tree status
@@ -111,14 +134,69 @@ impl WorktreeStatus {
     }
 }
 
+impl WorktreeStatus {
+    /// Summarize the changed files of a worktree
+    pub fn from_changes(files: &[ChangedFile]) -> Self {
+        if files.is_empty() {
+            return Self::Clean;
+        }
+        let count = |f: fn(&ChangedFile) -> bool| files.iter().filter(|c| f(c)).count() as u32;
+        Self::Dirty {
+            modified: count(|c| c.modified),
+            staged: count(|c| c.staged),
+            untracked: count(|c| c.untracked),
+        }
+    }
+}
+
 impl std::fmt::Display for WorktreeStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.description())
     }
 }
 
+/// A file with uncommitted changes in a worktree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFile {
+    /// Path relative to the worktree root
+    pub path: PathBuf,
+    /// Has changes staged in the index
+    pub staged: bool,
+    /// Has changes in the working tree that are not staged
+    pub modified: bool,
+    /// Is not tracked by git
+    pub untracked: bool,
+}
+
+impl ChangedFile {
+    /// Create an entry for a file with no changes recorded yet
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            staged: false,
+            modified: false,
+            untracked: false,
+        }
+    }
+
+    /// Check if all of the file's changes are staged
+    pub fn is_fully_staged(&self) -> bool {
+        self.staged && !self.modified && !self.untracked
+    }
+
+    /// Get the two-column status indicator, like `git status --short`
+    pub fn indicator(&self) -> &'static str {
+        match (self.staged, self.modified, self.untracked) {
+            (_, _, true) => "??",
+            (true, true, _) => "SM",
+            (true, false, _) => "S ",
+            (false, _, _) => " M",
+        }
+    }
+}
+
 /// Sync status with remote tracking branch
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WorktreeSyncStatus {
     /// Commits ahead of remote
     pub ahead: u32,
@@ -157,15 +235,179 @@ impl WorktreeSyncStatus {
     }
 }
 
+/// What deleting a branch would lose
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchSafety {
+    /// All of the branch's commits are on the main branch
+    pub merged: bool,
+    /// Remote branch exists
+    pub remote_exists: bool,
+    /// Commits only on the local branch: not on the remote branch, or not
+    /// on the main branch if there is no remote branch
+    pub unpushed: u32,
+}
+
+impl BranchSafety {
+    /// Check if the branch's commits are kept elsewhere after deleting it
+    pub fn is_safe(&self) -> bool {
+        self.merged || (self.remote_exists && self.unpushed == 0)
+    }
+
+    /// Describe the work that deleting the branch would drop, if any
+    pub fn warning(&self, main_branch: &str) -> Option<String> {
+        if self.is_safe() {
+            return None;
+        }
+        let location = if self.remote_exists {
+            "not pushed"
+        } else {
+            "not pushed anywhere"
+        };
+        Some(format!(
+            "{} commits are {} and not merged into {}",
+            self.unpushed, location, main_branch
+        ))
+    }
+}
+
+/// A git operation on a worktree's branch, run from the worktree manager
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitOp {
+    /// Fetch from the remote without touching the worktree
+    Fetch,
+    /// Integrate the remote branch into the worktree
+    Pull(PullMode),
+    /// Push the branch, setting its upstream if it has none on the remote
+    Push { set_upstream: bool },
+    /// Merge or rebase the branch onto another branch
+    Update { mode: UpdateMode, onto: String },
+    /// Continue a merge or rebase after its conflicts were resolved
+    Continue(UpdateMode),
+    /// Abort a merge or rebase, restoring the branch
+    Abort(UpdateMode),
+}
+
+impl GitOp {
+    /// Get display name
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Fetch => "Fetch",
+            Self::Pull(_) => "Pull",
+            Self::Push { .. } => "Push",
+            Self::Update { .. } => "Update",
+            Self::Continue(_) => "Continue update",
+            Self::Abort(_) => "Abort update",
+        }
+    }
+
+    /// Check if the operation can stop on merge conflicts
+    pub fn can_conflict(&self) -> bool {
+        matches!(
+            self,
+            Self::Pull(PullMode::Rebase) | Self::Update { .. } | Self::Continue(_)
+        )
+    }
+
+    /// Arguments to `git` performing the operation on `branch`
+    pub fn git_args(&self, branch: &str) -> Vec<String> {
+        let args: &[&str] = match self {
+            Self::Fetch => &["fetch", "origin"],
+            Self::Pull(PullMode::FfOnly) => &["pull", "--ff-only", "origin", branch],
+            Self::Pull(PullMode::Rebase) => &["pull", "--rebase", "origin", branch],
+            Self::Push { set_upstream: true } => &["push", "--set-upstream", "origin", branch],
+            Self::Push {
+                set_upstream: false,
+            } => &["push", "origin", branch],
+            Self::Update {
+                mode: UpdateMode::Merge,
+                onto,
+            } => &["merge", "--no-edit", onto],
+            Self::Update {
+                mode: UpdateMode::Rebase,
+                onto,
+            } => &["rebase", onto],
+            Self::Continue(mode) => &[mode.command(), "--continue"],
+            Self::Abort(mode) => &[mode.command(), "--abort"],
+        };
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+}
+
+/// A git operation started from the worktree manager
+#[derive(Debug, Clone)]
+pub struct GitRun {
+    /// Operation being run
+    pub op: GitOp,
+    /// Branch it runs on
+    pub branch: String,
+    /// Worktree it runs in
+    pub path: PathBuf,
+    /// How the operation ended, once it has
+    pub result: Option<Result<(), String>>,
+    /// Files left with conflicts when the operation stopped
+    pub conflicts: Vec<PathBuf>,
+}
+
+impl GitRun {
+    /// Create a running operation
+    pub fn new(op: GitOp, branch: String, path: PathBuf) -> Self {
+        Self {
+            op,
+            branch,
+            path,
+            result: None,
+            conflicts: Vec::new(),
+        }
+    }
+
+    /// Check if the operation stopped on conflicts that need resolving
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    /// Check if git is still running
+    pub fn is_running(&self) -> bool {
+        self.result.is_none()
+    }
+
+    /// Record the exit code of git
+    pub fn finish(&mut self, exit_code: i32) {
+        self.result = Some(match exit_code {
+            0 => Ok(()),
+            code => Err(format!("exited with code {}", code)),
+        });
+    }
+
+    /// Record that git did not exit on its own
+    pub fn fail(&mut self, reason: String) {
+        self.result = Some(Err(reason));
+    }
+
+    /// Get a one-line summary like `Push 003-foo - ✓ Completed`
+    pub fn summary(&self) -> String {
+        let state = match self.result {
+            None => "▶ Running",
+            Some(Ok(())) => "✓ Completed",
+            Some(Err(_)) => "✗ Failed",
+        };
+        format!("{} {} - {}", self.op.display_name(), self.branch, state)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_worktree_new() {
-        let wt = Worktree::new(PathBuf::from("/tmp/wt"), "001-feature".to_string(), false);
+        let mut wt = Worktree::new(PathBuf::from("/tmp/wt"), "001-feature".to_string(), false);
         assert!(!wt.is_main);
         assert!(wt.has_spec());
+        assert!(wt.flags().is_empty());
+
+        wt.locked = Some(String::new());
+        wt.prunable = Some("gitdir file points to non-existent location".to_string());
+        assert_eq!(wt.flags(), ["locked", "prunable"]);
         assert_eq!(wt.spec_id.unwrap().number(), 1);
     }
 
@@ -201,4 +443,89 @@ mod tests {
         let no_remote = WorktreeSyncStatus::new(0, 0, false);
         assert_eq!(no_remote.indicator(), "⊘");
     }
+
+    #[test]
+    fn test_branch_safety() {
+        let merged = BranchSafety {
+            merged: true,
+            remote_exists: false,
+            unpushed: 2,
+        };
+        assert_eq!(merged.warning("main"), None);
+
+        let pushed = BranchSafety {
+            merged: false,
+            remote_exists: true,
+            unpushed: 0,
+        };
+        assert!(pushed.is_safe());
+
+        let local = BranchSafety {
+            merged: false,
+            remote_exists: false,
+            unpushed: 3,
+        };
+        assert_eq!(
+            local.warning("main").as_deref(),
+            Some("3 commits are not pushed anywhere and not merged into main")
+        );
+    }
+
+    #[test]
+    fn test_status_from_changes() {
+        let mut staged = ChangedFile::new(PathBuf::from("spec.md"));
+        staged.staged = true;
+        let mut partly = ChangedFile::new(PathBuf::from("plan.md"));
+        partly.staged = true;
+        partly.modified = true;
+        let mut untracked = ChangedFile::new(PathBuf::from("notes.md"));
+        untracked.untracked = true;
+
+        assert!(staged.is_fully_staged());
+        assert!(!partly.is_fully_staged());
+        assert_eq!(partly.indicator(), "SM");
+        assert_eq!(
+            WorktreeStatus::from_changes(&[staged, partly, untracked]),
+            WorktreeStatus::Dirty {
+                modified: 1,
+                staged: 2,
+                untracked: 1
+            }
+        );
+        assert_eq!(WorktreeStatus::from_changes(&[]), WorktreeStatus::Clean);
+    }
+
+    #[test]
+    fn test_git_op_args() {
+        assert_eq!(
+            GitOp::Pull(PullMode::Rebase).git_args("003-foo"),
+            ["pull", "--rebase", "origin", "003-foo"]
+        );
+        assert_eq!(
+            GitOp::Push { set_upstream: true }.git_args("003-foo"),
+            ["push", "--set-upstream", "origin", "003-foo"]
+        );
+
+        assert_eq!(
+            GitOp::Update {
+                mode: UpdateMode::Merge,
+                onto: "main".to_string()
+            }
+            .git_args("003-foo"),
+            ["merge", "--no-edit", "main"]
+        );
+        assert_eq!(
+            GitOp::Continue(UpdateMode::Rebase).git_args("003-foo"),
+            ["rebase", "--continue"]
+        );
+
+        let mut run = GitRun::new(
+            GitOp::Fetch,
+            "003-foo".to_string(),
+            PathBuf::from("/tmp/wt"),
+        );
+        assert!(run.is_running());
+        run.finish(128);
+        assert_eq!(run.summary(), "Fetch 003-foo - ✗ Failed");
+    }
 }
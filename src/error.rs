@@ -1,5 +1,6 @@
 //! Unified error types for the speck application.
 
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -134,6 +135,9 @@ pub enum McpError {
     #[error("Failed to spawn process: {0}")]
     SpawnFailed(String),
 
+    #[error("Agent preset '{0}' has no MCP server mode, set agent.mcp_args")]
+    NoServerMode(String),
+
     #[error("Protocol error: {0}")]
     Protocol(String),
 
@@ -168,6 +172,48 @@ pub enum McpError {
     Json(#[from] serde_json::Error),
 }
 
+/// Category of an [`McpError`], used to configure which errors are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum McpErrorKind {
+    ConnectionFailed,
+    NotConnected,
+    SpawnFailed,
+    Protocol,
+    Rpc,
+    Timeout,
+    Cancelled,
+    ToolNotFound,
+    ToolFailed,
+    InvalidResponse,
+    Serialization,
+    Io,
+}
+
+impl McpError {
+    /// Category of this error
+    pub fn kind(&self) -> McpErrorKind {
+        match self {
+            Self::ConnectionFailed(_) | Self::NoServerMode(_) => McpErrorKind::ConnectionFailed,
+            Self::AlreadyConnected | Self::NotConnected | Self::NotInitialized => {
+                McpErrorKind::NotConnected
+            }
+            Self::SpawnFailed(_) => McpErrorKind::SpawnFailed,
+            Self::Protocol(_) => McpErrorKind::Protocol,
+            Self::RpcError { .. } => McpErrorKind::Rpc,
+            Self::Timeout => McpErrorKind::Timeout,
+            Self::Cancelled => McpErrorKind::Cancelled,
+            Self::ToolNotFound(_) => McpErrorKind::ToolNotFound,
+            Self::ToolFailed(_) => McpErrorKind::ToolFailed,
+            Self::InvalidResponse(_) => McpErrorKind::InvalidResponse,
+            Self::SerializationError(_) | Self::DeserializationError(_) | Self::Json(_) => {
+                McpErrorKind::Serialization
+            }
+            Self::IoError(_) => McpErrorKind::Io,
+        }
+    }
+}
+
 /// Result type alias for the application
 pub type Result<T> = std::result::Result<T, AppError>;
 
@@ -14,7 +14,8 @@ use std::panic;
 use std::path::PathBuf;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-use speck::App;
+use speck::services::ProcessService;
+use speck::{App, ProjectConfig};
 
 /// Setup the terminal for TUI mode
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
@@ -55,6 +56,18 @@ fn install_panic_hook() {
     }));
 }
 
+/// Run `speck logs prune`: enforce log retention and print a summary
+fn prune_logs(project_root: PathBuf) -> Result<()> {
+    let config = ProjectConfig::load(Some(&project_root)).unwrap_or_default();
+    let log_dir = project_root.join(".speck").join("logs");
+    let report = ProcessService::new(log_dir.clone())
+        .with_retention(config.logs)
+        .prune_logs()?;
+
+    println!("{}: {}", log_dir.display(), report);
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     // Initialize logging
@@ -67,6 +80,18 @@ async fn main() -> Result<()> {
     let project_root = speck::domain::Project::discover(None)
         .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
 
+    // Non-interactive subcommands
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match args.as_slice() {
+        [] => {}
+        ["logs", "prune"] => return prune_logs(project_root),
+        _ => anyhow::bail!(
+            "Unknown command: {}\nUsage: speck [logs prune]",
+            args.join(" ")
+        ),
+    }
+
     tracing::info!("Starting speck in {:?}", project_root);
 
     // Setup terminal
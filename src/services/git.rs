@@ -1,16 +1,32 @@
 //! Git service for repository and worktree operations.
 //!
-//! Uses gitoxide (gix) for native Rust git operations.
+//! Reads (worktree listing, status, diffs, logs, branch lookup, ahead/behind) use
+//! gitoxide (gix) natively and fall back to the git CLI when gix cannot handle
+//! a repository. Writes go through the git CLI.
 //! All operations are blocking and should be wrapped with spawn_blocking.
 
-use crate::domain::{Worktree, WorktreeStatus, WorktreeSyncStatus};
+use crate::config::UpdateMode;
+use crate::domain::{
+    BranchSafety, ChangedFile, CommitInfo, FileDiff, Worktree, WorktreeStatus, WorktreeSyncStatus,
+};
 use crate::error::{GitError, GitResult};
+use gix::bstr::BString;
+use gix::object::tree::diff::ChangeDetached;
+use gix::revision::walk::Sorting;
+use gix::state::InProgress;
+use gix::status::index_worktree::iter::Summary;
+use gix::status::UntrackedFiles;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Git service for worktree management
 pub struct GitService {
     repo_path: PathBuf,
     _worktree_base: PathBuf,
+    /// Repository opened with gix, or `None` to use the git CLI only
+    repo: Option<gix::ThreadSafeRepository>,
 }
 
 impl GitService {
@@ -22,9 +38,18 @@ impl GitService {
             return Err(GitError::NotARepository);
         }
 
+        let repo = match gix::ThreadSafeRepository::open(&repo_path) {
+            Ok(repo) => Some(repo),
+            Err(e) => {
+                tracing::warn!("Failed to open repository with gix, using git CLI: {}", e);
+                None
+            }
+        };
+
         Ok(Self {
             repo_path,
             _worktree_base: worktree_base,
+            repo,
         })
     }
 
@@ -33,8 +58,79 @@ impl GitService {
         &self.repo_path
     }
 
+    /// Get the directory where git registers linked worktrees
+    /// (`.git/worktrees` of the main repository)
+    pub fn worktrees_dir(&self) -> PathBuf {
+        match self.repo {
+            Some(ref repo) => repo.to_thread_local().common_dir().join("worktrees"),
+            None => self.repo_path.join(".git").join("worktrees"),
+        }
+    }
+
+    /// Get a thread-local handle to the repository opened with gix
+    fn gix_repo(&self) -> GitResult<gix::Repository> {
+        self.repo
+            .as_ref()
+            .map(|repo| repo.to_thread_local())
+            .ok_or_else(|| GitError::Operation("Repository not opened with gix".to_string()))
+    }
+
+    /// Run a native operation, falling back to the git CLI if it fails
+    fn with_fallback<T>(
+        operation: &str,
+        native: impl FnOnce() -> GitResult<T>,
+        cli: impl FnOnce() -> GitResult<T>,
+    ) -> GitResult<T> {
+        native().or_else(|e| {
+            tracing::debug!("gix {} failed, falling back to git CLI: {}", operation, e);
+            cli()
+        })
+    }
+
     /// List all worktrees in the repository
     pub fn list_worktrees(&self) -> GitResult<Vec<Worktree>> {
+        Self::with_fallback(
+            "worktree list",
+            || self.list_worktrees_native(),
+            || self.list_worktrees_cli(),
+        )
+    }
+
+    fn list_worktrees_native(&self) -> GitResult<Vec<Worktree>> {
+        let repo = self.gix_repo()?.main_repo().map_err(gix_error)?;
+        let mut worktrees = Vec::new();
+
+        // The main worktree comes first, unless the repository is bare
+        if let Some(workdir) = repo.workdir() {
+            let path = workdir
+                .canonicalize()
+                .unwrap_or_else(|_| workdir.to_path_buf());
+            let mut main = Worktree::new(path, head_branch(&repo)?, true);
+            main.is_detached = repo.head_name().map_err(gix_error)?.is_none();
+            worktrees.push(main);
+        }
+
+        for proxy in repo.worktrees()? {
+            let path = proxy.base()?;
+            let locked = proxy.lock_reason().map(|reason| reason.to_string());
+            let linked = proxy
+                .into_repo_with_possibly_inaccessible_worktree()
+                .map_err(gix_error)?;
+
+            let mut wt = Worktree::new(path, head_branch(&linked)?, false);
+            wt.is_detached = linked.head_name().map_err(gix_error)?.is_none();
+            // Like git, never report a locked worktree as prunable
+            if locked.is_none() && !wt.path.exists() {
+                wt.prunable = Some("gitdir file points to non-existent location".to_string());
+            }
+            wt.locked = locked;
+            worktrees.push(wt);
+        }
+
+        Ok(worktrees)
+    }
+
+    fn list_worktrees_cli(&self) -> GitResult<Vec<Worktree>> {
         // Use git command to list worktrees (more reliable than gix for this)
         let output = std::process::Command::new("git")
             .args(["worktree", "list", "--porcelain"])
@@ -49,38 +145,7 @@ impl GitService {
         }
 
         let output_str = String::from_utf8_lossy(&output.stdout);
-        let mut worktrees = Vec::new();
-        let mut current_path: Option<PathBuf> = None;
-        let mut current_branch: Option<String> = None;
-        let mut is_bare = false;
-
-        for line in output_str.lines() {
-            if let Some(path) = line.strip_prefix("worktree ") {
-                // Save previous worktree if any
-                if let (Some(path), Some(branch)) = (current_path.take(), current_branch.take()) {
-                    let is_main = worktrees.is_empty(); // First worktree is main
-                    worktrees.push(Worktree::new(path, branch, is_main));
-                }
-                current_path = Some(PathBuf::from(path));
-                is_bare = false;
-            } else if let Some(branch_ref) = line.strip_prefix("branch refs/heads/") {
-                current_branch = Some(branch_ref.to_string());
-            } else if line == "bare" {
-                is_bare = true;
-            } else if line == "detached" {
-                current_branch = Some("(detached)".to_string());
-            }
-        }
-
-        // Don't forget the last worktree
-        if let (Some(path), Some(branch)) = (current_path, current_branch) {
-            if !is_bare {
-                let is_main = worktrees.is_empty();
-                worktrees.push(Worktree::new(path, branch, is_main));
-            }
-        }
-
-        Ok(worktrees)
+        Ok(parse_worktree_list(&output_str))
     }
 
     /// Create a new worktree for a branch
@@ -162,12 +227,177 @@ impl GitService {
         Ok(())
     }
 
+    /// Prune the administrative files of worktrees whose directories are
+    /// gone, returning the paths of the pruned worktrees. Locked worktrees
+    /// are kept.
+    pub fn prune_worktrees(&self) -> GitResult<Vec<PathBuf>> {
+        let prunable: Vec<PathBuf> = self
+            .list_worktrees()?
+            .into_iter()
+            .filter(|wt| wt.prunable.is_some())
+            .map(|wt| wt.path)
+            .collect();
+        if !prunable.is_empty() {
+            git_stdout(&self.repo_path, &["worktree", "prune"])?;
+        }
+        Ok(prunable)
+    }
+
+    /// Check if all commits of `branch` are on `base`.
+    ///
+    /// A branch at the same commit as `base` has nothing of its own yet and
+    /// is not considered merged.
+    pub fn is_merged(&self, branch: &str, base: &str) -> GitResult<bool> {
+        let (at_base, contained) = self.ancestry(branch, base)?;
+        Ok(contained && !at_base)
+    }
+
+    /// Check whether `branch` is at the same commit as `base`, and whether
+    /// all of its commits are on `base`
+    fn ancestry(&self, branch: &str, base: &str) -> GitResult<(bool, bool)> {
+        Self::with_fallback(
+            "merge-base",
+            || self.ancestry_native(branch, base),
+            || self.ancestry_cli(branch, base),
+        )
+    }
+
+    fn ancestry_native(&self, branch: &str, base: &str) -> GitResult<(bool, bool)> {
+        let repo = self.gix_repo()?;
+        let tip = repo
+            .rev_parse_single(branch)
+            .map_err(|_| GitError::BranchNotFound(branch.to_string()))?;
+        let base = repo
+            .rev_parse_single(base)
+            .map_err(|_| GitError::BranchNotFound(base.to_string()))?;
+        if tip == base {
+            return Ok((true, true));
+        }
+        Ok((false, repo.merge_base(tip, base).map_err(gix_error)? == tip))
+    }
+
+    fn ancestry_cli(&self, branch: &str, base: &str) -> GitResult<(bool, bool)> {
+        let tip = git_stdout(&self.repo_path, &["rev-parse", branch])?;
+        if tip == git_stdout(&self.repo_path, &["rev-parse", base])? {
+            return Ok((true, true));
+        }
+
+        let output = std::process::Command::new("git")
+            .args(["merge-base", "--is-ancestor", branch, base])
+            .current_dir(&self.repo_path)
+            .output()
+            .map_err(|e| GitError::Operation(format!("Failed to check merge: {}", e)))?;
+        match output.status.code() {
+            Some(0) => Ok((false, true)),
+            Some(1) => Ok((false, false)),
+            _ => Err(GitError::Operation(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            )),
+        }
+    }
+
+    /// Check what deleting a branch would lose: whether it is merged into
+    /// `base` or pushed, as of the last fetch
+    pub fn branch_safety(&self, branch: &str, base: &str) -> GitResult<BranchSafety> {
+        let (_, merged) = self.ancestry(branch, base)?;
+        let sync = self.sync_status(branch)?;
+        let unpushed = if sync.remote_exists {
+            sync.ahead
+        } else if merged {
+            0
+        } else {
+            self.branch_log(branch, base)?.len() as u32
+        };
+        Ok(BranchSafety {
+            merged,
+            remote_exists: sync.remote_exists,
+            unpushed,
+        })
+    }
+
+    /// Delete a local branch, first keeping its commits under a recovery
+    /// ref in `refs/speck/trash/`. Returns the recovery ref, from which the
+    /// branch can be restored with `git branch <branch> <ref>`.
+    pub fn delete_branch(&self, branch: &str) -> GitResult<String> {
+        let tip = git_stdout(
+            &self.repo_path,
+            &["rev-parse", "--verify", &format!("refs/heads/{}", branch)],
+        )
+        .map_err(|_| GitError::BranchNotFound(branch.to_string()))?;
+        let tip = String::from_utf8_lossy(&tip).trim().to_string();
+
+        let deleted_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let recovery = format!("refs/speck/trash/{}/{}", deleted_at, branch);
+        let reason = format!("speck: delete branch {}", branch);
+        git_stdout(
+            &self.repo_path,
+            &["update-ref", "-m", &reason, &recovery, &tip],
+        )?;
+
+        if let Err(e) = git_stdout(&self.repo_path, &["branch", "-D", branch]) {
+            let _ = git_stdout(&self.repo_path, &["update-ref", "-d", &recovery]);
+            return Err(e);
+        }
+        Ok(recovery)
+    }
+
     /// Get the status of a worktree
     pub fn worktree_status(&self, path: &Path) -> GitResult<WorktreeStatus> {
         if !path.exists() {
             return Err(GitError::WorktreeNotFound(path.to_path_buf()));
         }
 
+        Self::with_fallback(
+            "status",
+            || Self::worktree_status_native(path),
+            || Self::worktree_status_cli(path),
+        )
+    }
+
+    fn worktree_status_native(path: &Path) -> GitResult<WorktreeStatus> {
+        let repo = gix::open(path).map_err(gix_error)?;
+        let items = repo
+            .status(gix::progress::Discard)
+            .map_err(gix_error)?
+            .into_iter(None)
+            .map_err(gix_error)?;
+
+        // Like `git status --porcelain`, a path changed both in the index and
+        // the working tree counts as staged and modified
+        let mut staged: HashSet<BString> = HashSet::new();
+        let mut modified: HashSet<BString> = HashSet::new();
+        let mut untracked = 0u32;
+
+        for item in items {
+            match item.map_err(gix_error)? {
+                gix::status::Item::TreeIndex(change) => {
+                    staged.insert(change.location().to_owned());
+                }
+                gix::status::Item::IndexWorktree(item) => match item.summary() {
+                    Some(Summary::Added) => untracked += 1,
+                    Some(_) => {
+                        modified.insert(item.rela_path().to_owned());
+                    }
+                    None => {}
+                },
+            }
+        }
+
+        if staged.is_empty() && modified.is_empty() && untracked == 0 {
+            return Ok(WorktreeStatus::Clean);
+        }
+
+        Ok(WorktreeStatus::Dirty {
+            modified: modified.len() as u32,
+            staged: staged.len() as u32,
+            untracked,
+        })
+    }
+
+    fn worktree_status_cli(path: &Path) -> GitResult<WorktreeStatus> {
         let output = std::process::Command::new("git")
             .args(["status", "--porcelain"])
             .current_dir(path)
@@ -214,6 +444,28 @@ impl GitService {
 
     /// Get sync status with remote
     pub fn sync_status(&self, branch: &str) -> GitResult<WorktreeSyncStatus> {
+        Self::with_fallback(
+            "ahead/behind",
+            || self.sync_status_native(branch),
+            || self.sync_status_cli(branch),
+        )
+    }
+
+    fn sync_status_native(&self, branch: &str) -> GitResult<WorktreeSyncStatus> {
+        let repo = self.gix_repo()?;
+        let Some(remote) = resolve_ref(&repo, &format!("refs/remotes/origin/{}", branch))? else {
+            return Ok(WorktreeSyncStatus::new(0, 0, false));
+        };
+        let Some(local) = resolve_ref(&repo, &format!("refs/heads/{}", branch))? else {
+            return Ok(WorktreeSyncStatus::new(0, 0, true));
+        };
+
+        let ahead = count_commits(&repo, local, remote)?;
+        let behind = count_commits(&repo, remote, local)?;
+        Ok(WorktreeSyncStatus::new(ahead, behind, true))
+    }
+
+    fn sync_status_cli(&self, branch: &str) -> GitResult<WorktreeSyncStatus> {
         // Check if remote tracking branch exists
         let output = std::process::Command::new("git")
             .args(["rev-parse", "--verify", &format!("origin/{}", branch)])
@@ -252,6 +504,27 @@ impl GitService {
 
     /// Check if a branch exists (local or remote)
     pub fn branch_exists(&self, branch: &str) -> GitResult<bool> {
+        Self::with_fallback(
+            "branch lookup",
+            || self.branch_exists_native(branch),
+            || self.branch_exists_cli(branch),
+        )
+    }
+
+    fn branch_exists_native(&self, branch: &str) -> GitResult<bool> {
+        let repo = self.gix_repo()?;
+        for name in [
+            format!("refs/heads/{}", branch),
+            format!("refs/remotes/origin/{}", branch),
+        ] {
+            if resolve_ref(&repo, &name)?.is_some() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn branch_exists_cli(&self, branch: &str) -> GitResult<bool> {
         // Check local branch
         let output = std::process::Command::new("git")
             .args(["rev-parse", "--verify", &format!("refs/heads/{}", branch)])
@@ -277,8 +550,23 @@ impl GitService {
         Ok(output.status.success())
     }
 
-    /// Get the current branch of a worktree
+    /// Get the current branch of a worktree, or `HEAD` if it is detached
     pub fn current_branch(&self, path: &Path) -> GitResult<String> {
+        Self::with_fallback(
+            "rev-parse",
+            || {
+                let repo = gix::open(path).map_err(gix_error)?;
+                Ok(repo
+                    .head_name()
+                    .map_err(gix_error)?
+                    .map(|name| name.shorten().to_string())
+                    .unwrap_or_else(|| "HEAD".to_string()))
+            },
+            || Self::current_branch_cli(path),
+        )
+    }
+
+    fn current_branch_cli(path: &Path) -> GitResult<String> {
         let output = std::process::Command::new("git")
             .args(["rev-parse", "--abbrev-ref", "HEAD"])
             .current_dir(path)
@@ -294,6 +582,442 @@ impl GitService {
         Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
     }
 
+    /// Get the merge or rebase in progress in a worktree, if any
+    pub fn update_in_progress(&self, worktree: &Path) -> GitResult<Option<UpdateMode>> {
+        Self::with_fallback(
+            "state",
+            || {
+                let repo = gix::open(worktree).map_err(gix_error)?;
+                Ok(match repo.state() {
+                    Some(InProgress::Merge) => Some(UpdateMode::Merge),
+                    Some(
+                        InProgress::Rebase
+                        | InProgress::RebaseInteractive
+                        | InProgress::ApplyMailboxRebase,
+                    ) => Some(UpdateMode::Rebase),
+                    _ => None,
+                })
+            },
+            || Self::update_in_progress_cli(worktree),
+        )
+    }
+
+    fn update_in_progress_cli(worktree: &Path) -> GitResult<Option<UpdateMode>> {
+        let output = std::process::Command::new("git")
+            .args([
+                "rev-parse",
+                "--path-format=absolute",
+                "--git-path",
+                "MERGE_HEAD",
+                "--git-path",
+                "rebase-merge",
+                "--git-path",
+                "rebase-apply",
+            ])
+            .current_dir(worktree)
+            .output()
+            .map_err(|e| GitError::Operation(format!("Failed to locate git dir: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitError::Operation(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let exists: Vec<bool> = stdout.lines().map(|p| Path::new(p).exists()).collect();
+        Ok(match exists.as_slice() {
+            [_, true, _] | [_, _, true] => Some(UpdateMode::Rebase),
+            [true, _, _] => Some(UpdateMode::Merge),
+            _ => None,
+        })
+    }
+
+    /// List files with unresolved conflicts in a worktree, relative to its root
+    pub fn conflicted_files(&self, worktree: &Path) -> GitResult<Vec<PathBuf>> {
+        Self::with_fallback(
+            "index",
+            || {
+                let repo = gix::open(worktree).map_err(gix_error)?;
+                let index = repo.index_or_empty().map_err(gix_error)?;
+                let mut files: Vec<PathBuf> = index
+                    .entries()
+                    .iter()
+                    .filter(|entry| entry.stage_raw() != 0)
+                    .map(|entry| gix::path::from_bstr(entry.path(&index)).into_owned())
+                    .collect();
+                // Each conflicted file has an entry for every side
+                files.dedup();
+                Ok(files)
+            },
+            || {
+                let output = std::process::Command::new("git")
+                    .args(["diff", "--name-only", "--diff-filter=U"])
+                    .current_dir(worktree)
+                    .output()
+                    .map_err(|e| GitError::Operation(format!("Failed to list conflicts: {}", e)))?;
+
+                if !output.status.success() {
+                    return Err(GitError::Operation(
+                        String::from_utf8_lossy(&output.stderr).to_string(),
+                    ));
+                }
+
+                Ok(String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .map(PathBuf::from)
+                    .collect())
+            },
+        )
+    }
+
+    /// Stage conflicted files whose conflict markers were removed, returning
+    /// the files that still contain markers
+    pub fn stage_resolved(&self, worktree: &Path) -> GitResult<Vec<PathBuf>> {
+        let mut unresolved = Vec::new();
+        for file in self.conflicted_files(worktree)? {
+            let content = std::fs::read(worktree.join(&file)).unwrap_or_default();
+            if has_conflict_markers(&String::from_utf8_lossy(&content)) {
+                unresolved.push(file);
+            } else {
+                self.stage(worktree, Some(&file))?;
+            }
+        }
+        Ok(unresolved)
+    }
+
+    /// List the files with uncommitted changes in a worktree, untracked
+    /// directories expanded into their files
+    pub fn changed_files(&self, worktree: &Path) -> GitResult<Vec<ChangedFile>> {
+        Self::with_fallback(
+            "status",
+            || Self::changed_files_native(worktree),
+            || Self::changed_files_cli(worktree),
+        )
+    }
+
+    fn changed_files_native(worktree: &Path) -> GitResult<Vec<ChangedFile>> {
+        let repo = gix::open(worktree).map_err(gix_error)?;
+        let items = repo
+            .status(gix::progress::Discard)
+            .map_err(gix_error)?
+            .untracked_files(UntrackedFiles::Files)
+            .into_iter(None)
+            .map_err(gix_error)?;
+
+        let mut files: BTreeMap<BString, ChangedFile> = BTreeMap::new();
+        fn entry<'a>(
+            files: &'a mut BTreeMap<BString, ChangedFile>,
+            path: &gix::bstr::BStr,
+        ) -> &'a mut ChangedFile {
+            files
+                .entry(path.to_owned())
+                .or_insert_with(|| ChangedFile::new(gix::path::from_bstr(path).into_owned()))
+        }
+        for item in items {
+            match item.map_err(gix_error)? {
+                gix::status::Item::TreeIndex(change) => {
+                    entry(&mut files, change.location()).staged = true
+                }
+                gix::status::Item::IndexWorktree(item) => match item.summary() {
+                    Some(Summary::Added) => entry(&mut files, item.rela_path()).untracked = true,
+                    Some(_) => entry(&mut files, item.rela_path()).modified = true,
+                    None => {}
+                },
+            }
+        }
+        Ok(files.into_values().collect())
+    }
+
+    fn changed_files_cli(worktree: &Path) -> GitResult<Vec<ChangedFile>> {
+        let output = git_stdout(
+            worktree,
+            &["status", "--porcelain", "-z", "--untracked-files=all"],
+        )?;
+
+        let mut files = Vec::new();
+        let mut entries = output.split(|&b| b == 0).filter(|e| e.len() > 3);
+        while let Some(entry) = entries.next() {
+            let (x, y) = (entry[0], entry[1]);
+            // Renames and copies are followed by their source path
+            if matches!(x, b'R' | b'C') {
+                entries.next();
+            }
+            let mut file = ChangedFile::new(PathBuf::from(
+                String::from_utf8_lossy(&entry[3..]).into_owned(),
+            ));
+            if x == b'?' {
+                file.untracked = true;
+            } else {
+                file.staged = x != b' ';
+                file.modified = y != b' ';
+            }
+            files.push(file);
+        }
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(files)
+    }
+
+    /// Remove a file's staged changes from the index, keeping the working
+    /// tree as it is
+    pub fn unstage(&self, worktree: &Path, path: &Path) -> GitResult<()> {
+        let path = path
+            .to_str()
+            .ok_or_else(|| GitError::Operation("Path is not valid UTF-8".to_string()))?;
+        git_stdout(worktree, &["reset", "-q", "--", path])?;
+        Ok(())
+    }
+
+    /// Diff the working tree of a worktree, including untracked files,
+    /// against its `HEAD`
+    pub fn working_tree_diff(&self, worktree: &Path) -> GitResult<Vec<FileDiff>> {
+        Self::with_fallback(
+            "diff",
+            || Self::working_tree_diff_native(worktree),
+            || Self::diff_cli(worktree, "HEAD", None),
+        )
+    }
+
+    fn working_tree_diff_native(worktree: &Path) -> GitResult<Vec<FileDiff>> {
+        let repo = gix::open(worktree).map_err(gix_error)?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| GitError::Operation("Repository has no working tree".to_string()))?
+            .to_path_buf();
+        // An unborn branch has no tree, so everything is added
+        let head_tree = repo.head_commit().ok().and_then(|c| c.tree().ok());
+
+        let items = repo
+            .status(gix::progress::Discard)
+            .map_err(gix_error)?
+            .untracked_files(UntrackedFiles::Files)
+            .into_iter(None)
+            .map_err(gix_error)?;
+        let mut paths: BTreeSet<BString> = BTreeSet::new();
+        for item in items {
+            match item.map_err(gix_error)? {
+                gix::status::Item::TreeIndex(change) => {
+                    paths.insert(change.location().to_owned());
+                }
+                gix::status::Item::IndexWorktree(item) => {
+                    if item.summary().is_some() {
+                        paths.insert(item.rela_path().to_owned());
+                    }
+                }
+            }
+        }
+
+        Ok(paths
+            .iter()
+            .filter_map(|path| {
+                let path = gix::path::from_bstr(path).into_owned();
+                let old = head_tree
+                    .as_ref()
+                    .and_then(|tree| tree.lookup_entry_by_path(&path).ok().flatten())
+                    .filter(|entry| entry.mode().is_blob_or_symlink())
+                    .and_then(|entry| blob_text(&repo, entry.object_id()));
+                let new = std::fs::read(workdir.join(&path))
+                    .ok()
+                    .map(|data| diff_text(&data));
+                FileDiff::between(path, old.as_deref(), new.as_deref())
+            })
+            .collect())
+    }
+
+    /// Diff the commits of a worktree's branch against the commit it forked
+    /// from `base` at, like `git diff base...HEAD`
+    pub fn branch_diff(&self, worktree: &Path, base: &str) -> GitResult<Vec<FileDiff>> {
+        Self::with_fallback(
+            "diff",
+            || Self::branch_diff_native(worktree, base),
+            || {
+                let fork = git_stdout(worktree, &["merge-base", base, "HEAD"])?;
+                Self::diff_cli(
+                    worktree,
+                    String::from_utf8_lossy(&fork).trim(),
+                    Some("HEAD"),
+                )
+            },
+        )
+    }
+
+    fn branch_diff_native(worktree: &Path, base: &str) -> GitResult<Vec<FileDiff>> {
+        let repo = gix::open(worktree).map_err(gix_error)?;
+        let head = repo.head_commit().map_err(gix_error)?;
+        let base_id = repo
+            .rev_parse_single(base)
+            .map_err(|_| GitError::BranchNotFound(base.to_string()))?;
+        let fork = repo.merge_base(base_id, head.id).map_err(gix_error)?;
+
+        let old_tree = repo
+            .find_commit(fork)
+            .map_err(gix_error)?
+            .tree()
+            .map_err(gix_error)?;
+        let new_tree = head.tree().map_err(gix_error)?;
+        tree_diff(&repo, &old_tree, &new_tree)
+    }
+
+    /// List the commits on `branch` that are not on `base`, newest first,
+    /// like `git log base..branch`
+    pub fn branch_log(&self, branch: &str, base: &str) -> GitResult<Vec<CommitInfo>> {
+        Self::with_fallback(
+            "log",
+            || self.branch_log_native(branch, base),
+            || self.branch_log_cli(branch, base),
+        )
+    }
+
+    fn branch_log_native(&self, branch: &str, base: &str) -> GitResult<Vec<CommitInfo>> {
+        let repo = self.gix_repo()?;
+        let tip = repo
+            .rev_parse_single(branch)
+            .map_err(|_| GitError::BranchNotFound(branch.to_string()))?;
+        let hidden = repo
+            .rev_parse_single(base)
+            .map_err(|_| GitError::BranchNotFound(base.to_string()))?;
+        let walk = repo
+            .rev_walk([tip])
+            .with_hidden([hidden])
+            .sorting(Sorting::ByCommitTime(Default::default()))
+            .all()
+            .map_err(gix_error)?;
+
+        let mut commits = Vec::new();
+        for info in walk {
+            let commit = info.map_err(gix_error)?.object().map_err(gix_error)?;
+            let author = commit.author().map_err(gix_error)?;
+            let message = commit.message_raw().map_err(gix_error)?.to_string();
+            commits.push(CommitInfo {
+                id: commit.id.to_string(),
+                author: author.name.to_string(),
+                time: author.time().map_or(0, |t| t.seconds.max(0) as u64),
+                subject: message.lines().next().unwrap_or_default().to_string(),
+                message: message.trim_end().to_string(),
+            });
+        }
+        Ok(commits)
+    }
+
+    fn branch_log_cli(&self, branch: &str, base: &str) -> GitResult<Vec<CommitInfo>> {
+        // Report missing revisions the way the native walk does
+        for rev in [branch, base] {
+            git_stdout(
+                &self.repo_path,
+                &[
+                    "rev-parse",
+                    "--verify",
+                    "--quiet",
+                    &format!("{}^{{commit}}", rev),
+                ],
+            )
+            .map_err(|_| GitError::BranchNotFound(rev.to_string()))?;
+        }
+
+        let output = git_stdout(
+            &self.repo_path,
+            &[
+                "log",
+                "--format=%H%x1f%an%x1f%at%x1f%B%x1e",
+                &format!("{}..{}", base, branch),
+            ],
+        )?;
+
+        Ok(String::from_utf8_lossy(&output)
+            .split('\x1e')
+            .filter_map(|record| {
+                let mut fields = record.trim_start().splitn(4, '\x1f');
+                let id = fields.next().filter(|id| !id.is_empty())?;
+                let author = fields.next()?;
+                let time = fields.next()?.parse().unwrap_or(0);
+                let message = fields.next()?.trim_end();
+                Some(CommitInfo {
+                    id: id.to_string(),
+                    author: author.to_string(),
+                    time,
+                    subject: message.lines().next().unwrap_or_default().to_string(),
+                    message: message.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// Diff a commit against its first parent, like `git show`
+    pub fn commit_diff(&self, commit: &str) -> GitResult<Vec<FileDiff>> {
+        Self::with_fallback(
+            "diff",
+            || self.commit_diff_native(commit),
+            || {
+                let parents =
+                    git_stdout(&self.repo_path, &["rev-list", "--parents", "-n1", commit])?;
+                let parents = String::from_utf8_lossy(&parents);
+                // A root commit is diffed against the empty tree
+                let parent = parents
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or("4b825dc642cb6eb9a060e54bf8d69288fbee4904");
+                Self::diff_cli(&self.repo_path, parent, Some(commit))
+            },
+        )
+    }
+
+    fn commit_diff_native(&self, commit: &str) -> GitResult<Vec<FileDiff>> {
+        let repo = self.gix_repo()?;
+        let commit = repo
+            .rev_parse_single(commit)
+            .map_err(gix_error)?
+            .object()
+            .map_err(gix_error)?
+            .try_into_commit()
+            .map_err(gix_error)?;
+        let new_tree = commit.tree().map_err(gix_error)?;
+        let old_tree = match commit.parent_ids().next() {
+            Some(parent) => parent
+                .object()
+                .map_err(gix_error)?
+                .try_into_commit()
+                .map_err(gix_error)?
+                .tree()
+                .map_err(gix_error)?,
+            None => repo.empty_tree(),
+        };
+        tree_diff(&repo, &old_tree, &new_tree)
+    }
+
+    /// Diff `base` against `head`, or against the working tree (including
+    /// untracked files) if `head` is `None`
+    fn diff_cli(worktree: &Path, base: &str, head: Option<&str>) -> GitResult<Vec<FileDiff>> {
+        let mut args = vec!["diff", "--name-only", "-z", "--no-renames", base];
+        args.extend(head);
+        let mut paths: BTreeSet<String> = split_nul(&git_stdout(worktree, &args)?);
+        if head.is_none() {
+            let untracked = git_stdout(
+                worktree,
+                &["ls-files", "-z", "--others", "--exclude-standard"],
+            )?;
+            paths.extend(split_nul(&untracked));
+        }
+
+        let show = |rev: &str, path: &str| {
+            git_stdout(worktree, &["show", &format!("{}:{}", rev, path)])
+                .ok()
+                .map(|data| diff_text(&data))
+        };
+        Ok(paths
+            .into_iter()
+            .filter_map(|path| {
+                let old = show(base, &path);
+                let new = match head {
+                    Some(head) => show(head, &path),
+                    None => std::fs::read(worktree.join(&path))
+                        .ok()
+                        .map(|data| diff_text(&data)),
+                };
+                FileDiff::between(PathBuf::from(path), old.as_deref(), new.as_deref())
+            })
+            .collect())
+    }
+
     /// Get the main worktree path
     pub fn main_worktree(&self) -> GitResult<PathBuf> {
         let worktrees = self.list_worktrees()?;
@@ -325,6 +1049,274 @@ impl GitService {
 
         Ok(())
     }
+
+    /// Stage all changes in a worktree, limited to `pathspec` if given
+    pub fn stage(&self, worktree: &Path, pathspec: Option<&Path>) -> GitResult<()> {
+        let mut args = os_args(&["add", "-A"]);
+        if let Some(pathspec) = pathspec {
+            args.extend([OsStr::new("--"), pathspec.as_os_str()]);
+        }
+
+        let output = std::process::Command::new("git")
+            .args(&args)
+            .current_dir(worktree)
+            .output()
+            .map_err(|e| GitError::Operation(format!("Failed to stage changes: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitError::Operation(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check if a worktree has staged changes, limited to `pathspec` if given
+    pub fn has_staged_changes(&self, worktree: &Path, pathspec: Option<&Path>) -> GitResult<bool> {
+        let mut args = os_args(&["diff", "--cached", "--quiet"]);
+        if let Some(pathspec) = pathspec {
+            args.extend([OsStr::new("--"), pathspec.as_os_str()]);
+        }
+
+        let output = std::process::Command::new("git")
+            .args(&args)
+            .current_dir(worktree)
+            .output()
+            .map_err(|e| GitError::Operation(format!("Failed to check staged changes: {}", e)))?;
+
+        // `--quiet` exits with 1 when there are differences
+        match output.status.code() {
+            Some(0) => Ok(false),
+            Some(1) => Ok(true),
+            _ => Err(GitError::Operation(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            )),
+        }
+    }
+
+    /// Commit staged changes in a worktree and return the new commit hash.
+    ///
+    /// With a `pathspec`, only staged changes under it are committed.
+    pub fn commit(
+        &self,
+        worktree: &Path,
+        message: &str,
+        pathspec: Option<&Path>,
+    ) -> GitResult<String> {
+        let mut args = os_args(&["commit", "-m", message]);
+        if let Some(pathspec) = pathspec {
+            args.extend([OsStr::new("--"), pathspec.as_os_str()]);
+        }
+
+        let output = std::process::Command::new("git")
+            .args(&args)
+            .current_dir(worktree)
+            .output()
+            .map_err(|e| GitError::Operation(format!("Failed to commit: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitError::Operation(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            ));
+        }
+
+        let output = std::process::Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(worktree)
+            .output()
+            .map_err(|e| GitError::Operation(format!("Failed to read commit hash: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(GitError::Operation(
+                String::from_utf8_lossy(&output.stderr).to_string(),
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+/// Parse the output of `git worktree list --porcelain`, skipping a bare
+/// main repository
+fn parse_worktree_list(output: &str) -> Vec<Worktree> {
+    let mut worktrees = Vec::new();
+    // Records are separated by blank lines; the first one is the main worktree
+    for (index, record) in output.split("\n\n").enumerate() {
+        let mut path = None;
+        let mut branch = None;
+        let mut is_detached = false;
+        let mut locked = None;
+        let mut prunable = None;
+        for line in record.lines() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "worktree" => path = Some(PathBuf::from(value)),
+                "branch" => branch = Some(value.strip_prefix("refs/heads/").unwrap_or(value)),
+                "detached" => {
+                    branch = Some("(detached)");
+                    is_detached = true;
+                }
+                "locked" => locked = Some(value.to_string()),
+                "prunable" => prunable = Some(value.to_string()),
+                _ => {}
+            }
+        }
+
+        // A bare repository has no branch line
+        if let (Some(path), Some(branch)) = (path, branch) {
+            let mut wt = Worktree::new(path, branch.to_string(), index == 0);
+            wt.is_detached = is_detached;
+            wt.locked = locked;
+            wt.prunable = prunable;
+            worktrees.push(wt);
+        }
+    }
+    worktrees
+}
+
+/// Convert a gix error into a git operation error
+fn gix_error(e: impl std::fmt::Display) -> GitError {
+    GitError::Operation(e.to_string())
+}
+
+/// Diff two trees into per-file diffs, skipping submodules and directories
+fn tree_diff(
+    repo: &gix::Repository,
+    old_tree: &gix::Tree<'_>,
+    new_tree: &gix::Tree<'_>,
+) -> GitResult<Vec<FileDiff>> {
+    let changes = repo
+        .diff_tree_to_tree(old_tree, new_tree, gix::diff::Options::default())
+        .map_err(gix_error)?;
+
+    Ok(changes
+        .into_iter()
+        .filter_map(|change| {
+            let (location, old, new) = match change {
+                ChangeDetached::Addition {
+                    location,
+                    entry_mode,
+                    id,
+                    ..
+                } if entry_mode.is_blob_or_symlink() => (location, None, Some(id)),
+                ChangeDetached::Deletion {
+                    location,
+                    entry_mode,
+                    id,
+                    ..
+                } if entry_mode.is_blob_or_symlink() => (location, Some(id), None),
+                ChangeDetached::Modification {
+                    location,
+                    previous_entry_mode,
+                    previous_id,
+                    entry_mode,
+                    id,
+                } => (
+                    location,
+                    previous_entry_mode
+                        .is_blob_or_symlink()
+                        .then_some(previous_id),
+                    entry_mode.is_blob_or_symlink().then_some(id),
+                ),
+                _ => return None,
+            };
+            let path = gix::path::from_bstr(&location).into_owned();
+            let old = old.and_then(|id| blob_text(repo, id));
+            let new = new.and_then(|id| blob_text(repo, id));
+            FileDiff::between(path, old.as_deref(), new.as_deref())
+        })
+        .collect())
+}
+
+/// Get the branch checked out in a repository, or `(detached)`
+fn head_branch(repo: &gix::Repository) -> GitResult<String> {
+    Ok(repo
+        .head_name()
+        .map_err(gix_error)?
+        .map(|name| name.shorten().to_string())
+        .unwrap_or_else(|| "(detached)".to_string()))
+}
+
+/// Run git in a directory and return its stdout
+fn git_stdout(dir: &Path, args: &[&str]) -> GitResult<Vec<u8>> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .map_err(|e| GitError::Operation(format!("Failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(GitError::Operation(
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Convert git arguments so paths that are not valid UTF-8 can follow them
+fn os_args<'a>(args: &[&'a str]) -> Vec<&'a OsStr> {
+    args.iter().map(|arg| OsStr::new(*arg)).collect()
+}
+
+/// Split NUL-separated git output into paths
+fn split_nul(output: &[u8]) -> BTreeSet<String> {
+    output
+        .split(|&b| b == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| String::from_utf8_lossy(path).into_owned())
+        .collect()
+}
+
+/// Read a blob as text for diffing
+fn blob_text(repo: &gix::Repository, id: impl Into<gix::ObjectId>) -> Option<String> {
+    repo.find_object(id.into())
+        .ok()
+        .map(|object| diff_text(&object.data))
+}
+
+/// Decode file contents for diffing; binary files are summarized by size
+fn diff_text(data: &[u8]) -> String {
+    if data.contains(&0) {
+        format!("(binary file, {} bytes)\n", data.len())
+    } else {
+        String::from_utf8_lossy(data).into_owned()
+    }
+}
+
+/// Check if a file still contains conflict markers left by a merge
+fn has_conflict_markers(content: &str) -> bool {
+    content
+        .lines()
+        .any(|line| line.starts_with("<<<<<<< ") || line.starts_with(">>>>>>> "))
+}
+
+/// Resolve a full reference name to the commit it points to, if it exists
+fn resolve_ref(repo: &gix::Repository, name: &str) -> GitResult<Option<gix::ObjectId>> {
+    match repo.try_find_reference(name).map_err(gix_error)? {
+        Some(mut reference) => Ok(Some(reference.peel_to_id().map_err(gix_error)?.detach())),
+        None => Ok(None),
+    }
+}
+
+/// Count commits reachable from `tip` but not from `hidden`
+fn count_commits(
+    repo: &gix::Repository,
+    tip: gix::ObjectId,
+    hidden: gix::ObjectId,
+) -> GitResult<u32> {
+    let mut count = 0;
+    for info in repo
+        .rev_walk([tip])
+        .with_hidden([hidden])
+        .all()
+        .map_err(gix_error)?
+    {
+        info.map_err(gix_error)?;
+        count += 1;
+    }
+    Ok(count)
 }
 
 #[cfg(test)]
@@ -393,4 +1385,434 @@ mod tests {
         // Non-existent branch
         assert!(!git.branch_exists("nonexistent-branch").unwrap());
     }
+
+    fn git(path: &Path, args: &[&str]) {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(path)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_native_matches_cli() {
+        let (_temp, path) = create_test_repo();
+        let git_service = GitService::new(path.clone(), path.join(".worktrees")).unwrap();
+        let main = git_service.current_branch(&path).unwrap();
+
+        git(&path, &["branch", "003-foo"]);
+        let worktree_path = path.join(".worktrees").join("003-foo");
+        git_service
+            .create_worktree("003-foo", &worktree_path)
+            .unwrap();
+
+        // One staged + modified file, one staged file, one untracked directory
+        std::fs::write(worktree_path.join("README.md"), "# Staged").unwrap();
+        std::fs::write(worktree_path.join("new.md"), "new").unwrap();
+        git(&worktree_path, &["add", "README.md", "new.md"]);
+        std::fs::write(worktree_path.join("README.md"), "# Modified").unwrap();
+        std::fs::create_dir_all(worktree_path.join("scratch")).unwrap();
+        std::fs::write(worktree_path.join("scratch/a.txt"), "a").unwrap();
+        std::fs::write(worktree_path.join("scratch/b.txt"), "b").unwrap();
+
+        let native = GitService::worktree_status_native(&worktree_path).unwrap();
+        assert_eq!(
+            native,
+            WorktreeStatus::Dirty {
+                modified: 1,
+                staged: 2,
+                untracked: 1
+            }
+        );
+        assert_eq!(
+            native,
+            GitService::worktree_status_cli(&worktree_path).unwrap()
+        );
+
+        let listed = git_service.list_worktrees_native().unwrap();
+        let listed_cli = git_service.list_worktrees_cli().unwrap();
+        assert_eq!(listed.len(), 2);
+        for (native, cli) in listed.iter().zip(&listed_cli) {
+            assert_eq!(native.path, cli.path);
+            assert_eq!(native.branch, cli.branch);
+            assert_eq!(native.is_main, cli.is_main);
+        }
+
+        assert!(git_service.branch_exists_native(&main).unwrap());
+        assert!(!git_service
+            .branch_exists_native("nonexistent-branch")
+            .unwrap());
+    }
+
+    #[test]
+    fn test_stale_and_merged_worktrees() {
+        let (_temp, path) = create_test_repo();
+        let git_service = GitService::new(path.clone(), path.join(".worktrees")).unwrap();
+        let main = git_service.current_branch(&path).unwrap();
+
+        git(&path, &["checkout", "-q", "-b", "003-foo"]);
+        std::fs::write(path.join("plan.md"), "plan").unwrap();
+        git(&path, &["add", "."]);
+        git(&path, &["commit", "-q", "-m", "plan"]);
+        git(&path, &["checkout", "-q", &main]);
+        git(&path, &["merge", "-q", "--no-ff", "-m", "merge", "003-foo"]);
+        git(&path, &["branch", "004-bar"]);
+        assert!(git_service.is_merged("003-foo", &main).unwrap());
+        assert_eq!(
+            git_service.ancestry_cli("003-foo", &main).unwrap(),
+            (false, true)
+        );
+        assert!(!git_service.is_merged("004-bar", &main).unwrap());
+        assert_eq!(
+            git_service.ancestry_cli("004-bar", &main).unwrap(),
+            (true, true)
+        );
+
+        let worktrees = path.join(".worktrees");
+        git(
+            &path,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "--detach",
+                worktrees.join("detached").to_str().unwrap(),
+            ],
+        );
+        git(
+            &path,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                "--lock",
+                "--reason",
+                "usb drive",
+                worktrees.join("003-foo").to_str().unwrap(),
+                "003-foo",
+            ],
+        );
+        let missing = worktrees.join("004-bar");
+        git(
+            &path,
+            &[
+                "worktree",
+                "add",
+                "-q",
+                missing.to_str().unwrap(),
+                "004-bar",
+            ],
+        );
+        std::fs::remove_dir_all(&missing).unwrap();
+
+        let listed = git_service.list_worktrees_native().unwrap();
+        let listed_cli = git_service.list_worktrees_cli().unwrap();
+        let flags: Vec<_> = listed.iter().map(|wt| wt.flags()).collect();
+        assert_eq!(
+            flags,
+            [vec![], vec!["locked"], vec!["prunable"], vec!["detached"]]
+        );
+        assert_eq!(listed[1].locked.as_deref(), Some("usb drive"));
+        for (native, cli) in listed.iter().zip(&listed_cli) {
+            assert_eq!(native.flags(), cli.flags());
+            assert_eq!(native.locked, cli.locked);
+        }
+
+        assert_eq!(git_service.prune_worktrees().unwrap(), [missing]);
+        assert_eq!(git_service.list_worktrees().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_delete_branch_with_recovery() {
+        let (_temp, path) = create_test_repo();
+        let git_service = GitService::new(path.clone(), path.join(".worktrees")).unwrap();
+        let main = git_service.current_branch(&path).unwrap();
+
+        git(&path, &["checkout", "-q", "-b", "004-bar"]);
+        std::fs::write(path.join("plan.md"), "plan").unwrap();
+        git(&path, &["add", "."]);
+        git(&path, &["commit", "-q", "-m", "plan"]);
+        git(&path, &["checkout", "-q", &main]);
+
+        let safety = git_service.branch_safety("004-bar", &main).unwrap();
+        assert!(!safety.merged && !safety.remote_exists);
+        assert_eq!(safety.unpushed, 1);
+
+        // A remote branch at the same commit keeps the work safe
+        git(
+            &path,
+            &["update-ref", "refs/remotes/origin/004-bar", "004-bar"],
+        );
+        assert!(git_service
+            .branch_safety("004-bar", &main)
+            .unwrap()
+            .is_safe());
+
+        let recovery = git_service.delete_branch("004-bar").unwrap();
+        assert!(recovery.starts_with("refs/speck/trash/"));
+        assert!(recovery.ends_with("/004-bar"));
+        assert!(git_stdout(&path, &["rev-parse", "--verify", "refs/heads/004-bar"]).is_err());
+
+        git(&path, &["branch", "004-bar", &recovery]);
+        assert_eq!(git_service.branch_log("004-bar", &main).unwrap().len(), 1);
+        assert!(matches!(
+            git_service.delete_branch("missing"),
+            Err(GitError::BranchNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_update_conflicts() {
+        let (_temp, path) = create_test_repo();
+        let git_service = GitService::new(path.clone(), path.join(".worktrees")).unwrap();
+        let main = git_service.current_branch(&path).unwrap();
+
+        git(&path, &["checkout", "-q", "-b", "003-foo"]);
+        std::fs::write(path.join("README.md"), "# Feature").unwrap();
+        git(&path, &["commit", "-q", "-am", "feature"]);
+        git(&path, &["checkout", "-q", &main]);
+        std::fs::write(path.join("README.md"), "# Main").unwrap();
+        git(&path, &["commit", "-q", "-am", "main"]);
+        git(&path, &["checkout", "-q", "003-foo"]);
+        assert_eq!(git_service.update_in_progress(&path).unwrap(), None);
+
+        let output = std::process::Command::new("git")
+            .args(["merge", "--no-edit", &main])
+            .current_dir(&path)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert_eq!(
+            git_service.update_in_progress(&path).unwrap(),
+            Some(UpdateMode::Merge)
+        );
+        assert_eq!(
+            GitService::update_in_progress_cli(&path).unwrap(),
+            Some(UpdateMode::Merge)
+        );
+        let conflicts = vec![PathBuf::from("README.md")];
+        assert_eq!(git_service.conflicted_files(&path).unwrap(), conflicts);
+
+        // Files still containing markers are left unstaged
+        assert_eq!(git_service.stage_resolved(&path).unwrap(), conflicts);
+        std::fs::write(path.join("README.md"), "# Feature and main").unwrap();
+        assert!(git_service.stage_resolved(&path).unwrap().is_empty());
+        assert!(git_service.conflicted_files(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_changed_files_and_unstage() {
+        let (_temp, path) = create_test_repo();
+        let git_service = GitService::new(path.clone(), path.join(".worktrees")).unwrap();
+
+        std::fs::write(path.join("README.md"), "# Changed").unwrap();
+        std::fs::create_dir_all(path.join("specs/003-foo")).unwrap();
+        std::fs::write(path.join("specs/003-foo/spec.md"), "# Spec").unwrap();
+        git_service
+            .stage(&path, Some(Path::new("README.md")))
+            .unwrap();
+        std::fs::write(path.join("README.md"), "# Changed again").unwrap();
+
+        let files = git_service.changed_files(&path).unwrap();
+        assert_eq!(files, GitService::changed_files_cli(&path).unwrap());
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, PathBuf::from("README.md"));
+        assert!(files[0].staged && files[0].modified);
+        assert_eq!(files[1].path, PathBuf::from("specs/003-foo/spec.md"));
+        assert!(files[1].untracked);
+
+        git_service.unstage(&path, Path::new("README.md")).unwrap();
+        let files = git_service.changed_files(&path).unwrap();
+        assert!(!files[0].staged && files[0].modified);
+    }
+
+    #[test]
+    fn test_worktree_diffs() {
+        let (_temp, path) = create_test_repo();
+        let git_service = GitService::new(path.clone(), path.join(".worktrees")).unwrap();
+        let main = git_service.current_branch(&path).unwrap();
+
+        // A commit on main after the fork is not part of the branch's diff
+        git(&path, &["checkout", "-q", "-b", "003-foo"]);
+        std::fs::write(path.join("README.md"), "# Feature").unwrap();
+        std::fs::write(path.join("plan.md"), "one\ntwo\n").unwrap();
+        git(&path, &["add", "."]);
+        git(&path, &["commit", "-q", "-m", "feature"]);
+        git(&path, &["checkout", "-q", &main]);
+        std::fs::write(path.join("main.md"), "main").unwrap();
+        git(&path, &["add", "."]);
+        git(&path, &["commit", "-q", "-m", "main"]);
+        git(&path, &["checkout", "-q", "003-foo"]);
+
+        let branch = git_service.branch_diff(&path, &main).unwrap();
+        let paths: Vec<_> = branch.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [PathBuf::from("README.md"), PathBuf::from("plan.md")]
+        );
+        assert_eq!(branch[1].change, crate::domain::FileChange::Added);
+        let fork = git_stdout(&path, &["merge-base", &main, "HEAD"]).unwrap();
+        let fork = String::from_utf8_lossy(&fork);
+        assert_eq!(
+            branch,
+            GitService::diff_cli(&path, fork.trim(), Some("HEAD")).unwrap()
+        );
+
+        std::fs::write(path.join("plan.md"), "one\n2\n").unwrap();
+        std::fs::remove_file(path.join("README.md")).unwrap();
+        std::fs::create_dir_all(path.join("notes")).unwrap();
+        std::fs::write(path.join("notes/a.md"), "a").unwrap();
+
+        let working = GitService::working_tree_diff_native(&path).unwrap();
+        let changes: Vec<_> = working.iter().map(|f| (f.path.clone(), f.change)).collect();
+        assert_eq!(
+            changes,
+            [
+                (
+                    PathBuf::from("README.md"),
+                    crate::domain::FileChange::Deleted
+                ),
+                (
+                    PathBuf::from("notes/a.md"),
+                    crate::domain::FileChange::Added
+                ),
+                (
+                    PathBuf::from("plan.md"),
+                    crate::domain::FileChange::Modified
+                ),
+            ]
+        );
+        assert_eq!(working, GitService::diff_cli(&path, "HEAD", None).unwrap());
+    }
+
+    #[test]
+    fn test_branch_log() {
+        let (_temp, path) = create_test_repo();
+        let git_service = GitService::new(path.clone(), path.join(".worktrees")).unwrap();
+        let main = git_service.current_branch(&path).unwrap();
+
+        git(&path, &["checkout", "-q", "-b", "003-foo"]);
+        std::fs::write(path.join("plan.md"), "plan").unwrap();
+        git(&path, &["add", "."]);
+        git(
+            &path,
+            &["commit", "-q", "-m", "T001: Add plan\n\nCovers T002"],
+        );
+        std::fs::write(path.join("README.md"), "# Feature").unwrap();
+        git(&path, &["commit", "-q", "-am", "T003: Update readme"]);
+
+        let log = git_service.branch_log("003-foo", &main).unwrap();
+        assert_eq!(log, git_service.branch_log_cli("003-foo", &main).unwrap());
+        let subjects: Vec<_> = log.iter().map(|c| c.subject.as_str()).collect();
+        assert_eq!(subjects, ["T003: Update readme", "T001: Add plan"]);
+        assert_eq!(log[1].task_ids(), ["T001", "T002"]);
+        assert!(log[0].time > 0);
+
+        let diff = git_service.commit_diff(&log[1].id).unwrap();
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].path, PathBuf::from("plan.md"));
+        assert_eq!(
+            diff,
+            GitService::diff_cli(&path, &format!("{}^", log[1].id), Some(&log[1].id)).unwrap()
+        );
+
+        // A missing branch is reported as such, not as a failed log
+        assert!(matches!(
+            git_service.branch_log("missing", &main),
+            Err(GitError::BranchNotFound(b)) if b == "missing"
+        ));
+        assert!(matches!(
+            git_service.branch_log_cli("missing", &main),
+            Err(GitError::BranchNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_sync_status_ahead_behind() {
+        let (_temp, path) = create_test_repo();
+        let git_service = GitService::new(path.clone(), path.join(".worktrees")).unwrap();
+        let main = git_service.current_branch(&path).unwrap();
+
+        assert!(!git_service.sync_status_native(&main).unwrap().remote_exists);
+
+        // The remote is one commit behind after a local commit, and one ahead
+        // after a commit that only the remote has
+        git(
+            &path,
+            &[
+                "update-ref",
+                &format!("refs/remotes/origin/{}", main),
+                "HEAD",
+            ],
+        );
+        git(&path, &["commit", "--allow-empty", "-m", "local"]);
+        git(
+            &path,
+            &["checkout", "-q", "--detach", &format!("origin/{}", main)],
+        );
+        git(&path, &["commit", "--allow-empty", "-m", "remote"]);
+        git(
+            &path,
+            &[
+                "update-ref",
+                &format!("refs/remotes/origin/{}", main),
+                "HEAD",
+            ],
+        );
+        git(&path, &["checkout", "-q", &main]);
+
+        let native = git_service.sync_status_native(&main).unwrap();
+        assert_eq!(
+            (native.ahead, native.behind, native.remote_exists),
+            (1, 1, true)
+        );
+        let cli = git_service.sync_status_cli(&main).unwrap();
+        assert_eq!((cli.ahead, cli.behind), (native.ahead, native.behind));
+    }
+
+    #[test]
+    fn test_commit_spec_directory() {
+        let (_temp, path) = create_test_repo();
+        let git = GitService::new(path.clone(), path.join(".worktrees")).unwrap();
+
+        std::fs::create_dir_all(path.join("specs/003-foo")).unwrap();
+        std::fs::write(path.join("specs/003-foo/plan.md"), "# Plan").unwrap();
+        std::fs::write(path.join("notes.txt"), "scratch").unwrap();
+
+        let spec_dir = Path::new("specs/003-foo");
+        git.stage(&path, Some(spec_dir)).unwrap();
+        assert!(git.has_staged_changes(&path, Some(spec_dir)).unwrap());
+
+        let hash = git
+            .commit(&path, "speck: plan for 003-foo", Some(spec_dir))
+            .unwrap();
+        assert_eq!(hash.len(), 40);
+        assert!(!git.has_staged_changes(&path, None).unwrap());
+
+        // Changes outside the spec directory stay uncommitted
+        let status = git.worktree_status(&path).unwrap();
+        assert!(matches!(status, WorktreeStatus::Dirty { untracked: 1, .. }));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_pathspec() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let (_temp, path) = create_test_repo();
+        let git = GitService::new(path.clone(), path.join(".worktrees")).unwrap();
+
+        // The pathspec still limits staging when it is not valid UTF-8
+        let spec_dir = Path::new(OsStr::from_bytes(b"specs/003-f\xf6o"));
+        std::fs::create_dir_all(path.join(spec_dir)).unwrap();
+        std::fs::write(path.join(spec_dir).join("plan.md"), "# Plan").unwrap();
+        std::fs::write(path.join("notes.md"), "notes").unwrap();
+
+        git.stage(&path, Some(spec_dir)).unwrap();
+        assert!(git.has_staged_changes(&path, Some(spec_dir)).unwrap());
+        assert!(!git
+            .has_staged_changes(&path, Some(Path::new("notes.md")))
+            .unwrap());
+    }
 }
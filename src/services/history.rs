@@ -0,0 +1,135 @@
+//! Run history persisted as JSON lines under `.speck/`.
+
+use crate::domain::RunRecord;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Append-only log of finished workflow runs
+#[derive(Debug, Clone)]
+pub struct RunHistory {
+    path: PathBuf,
+}
+
+impl RunHistory {
+    /// Create a run history backed by a JSON lines file
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Get the path of the history file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a record to the history
+    pub fn append(&self, record: &RunRecord) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let line = serde_json::to_string(record).map_err(io::Error::other)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)
+    }
+
+    /// Load all records, oldest first, skipping lines that fail to parse
+    pub fn load(&self) -> io::Result<Vec<RunRecord>> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(record) => Some(record),
+                Err(e) => {
+                    tracing::warn!("Skipping malformed run history entry: {}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Get the session of the most recent run of a command for a spec that
+    /// reported one
+    pub fn last_session(&self, spec_id: &str, command: &str) -> io::Result<Option<String>> {
+        Ok(self
+            .load()?
+            .into_iter()
+            .rev()
+            .filter(|r| r.spec_id == spec_id && r.command == command)
+            .find_map(|r| r.session_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::RunOutcome;
+    use tempfile::TempDir;
+
+    fn record(command: &str, commit: Option<&str>) -> RunRecord {
+        RunRecord {
+            spec_id: "003-foo".to_string(),
+            command: command.to_string(),
+            finished_at: 1_700_000_000,
+            outcome: RunOutcome::Succeeded,
+            exit_code: Some(0),
+            duration_ms: Some(1200),
+            log_file: None,
+            commit: commit.map(str::to_string),
+            usage: None,
+            cost_usd: None,
+            attempt: 1,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn test_history_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let history = RunHistory::new(dir.path().join(".speck").join("history.jsonl"));
+        assert!(history.load().unwrap().is_empty());
+
+        history.append(&record("specify", None)).unwrap();
+        history.append(&record("plan", Some("abc123"))).unwrap();
+        fs::OpenOptions::new()
+            .append(true)
+            .open(history.path())
+            .unwrap()
+            .write_all(b"not json\n")
+            .unwrap();
+
+        let records = history.load().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].command, "plan");
+        assert_eq!(records[1].commit.as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_last_session() {
+        let dir = TempDir::new().unwrap();
+        let history = RunHistory::new(dir.path().join("history.jsonl"));
+
+        let mut first = record("plan", None);
+        first.session_id = Some("s-1".to_string());
+        let mut second = record("plan", None);
+        second.session_id = Some("s-2".to_string());
+        history.append(&first).unwrap();
+        history.append(&second).unwrap();
+        history.append(&record("plan", None)).unwrap();
+
+        assert_eq!(
+            history.last_session("003-foo", "plan").unwrap().as_deref(),
+            Some("s-2")
+        );
+        assert!(history.last_session("003-foo", "tasks").unwrap().is_none());
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! Implements JSON-RPC 2.0 over stdio transport for workflow commands.
 
+use crate::config::{AgentConfig, AgentPreset};
 use crate::domain::WorkflowCommandType;
 use crate::error::{McpError, McpResult};
 use serde::{Deserialize, Serialize};
@@ -248,9 +249,18 @@ impl McpClient {
         }
     }
 
+    /// Create a client that starts the configured agent as an MCP server
+    pub fn from_agent_config(agent: &AgentConfig) -> McpResult<Self> {
+        let args = agent
+            .mcp_args()
+            .ok_or_else(|| McpError::NoServerMode(agent.command()))?;
+        Ok(Self::new(agent.command(), args))
+    }
+
     /// Create a client with default claude command
     pub fn default_claude() -> Self {
-        Self::new("claude".to_string(), vec!["--mcp".to_string()])
+        let agent = AgentConfig::preset(AgentPreset::Claude);
+        Self::new(agent.command(), agent.mcp_args().unwrap_or_default())
     }
 
     /// Get the next request ID
@@ -484,6 +494,16 @@ impl Drop for McpClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_client_from_agent_config() {
+        let client =
+            McpClient::from_agent_config(&AgentConfig::preset(AgentPreset::Codex)).unwrap();
+        assert_eq!(client.args, vec!["mcp-server"]);
+
+        let err = McpClient::from_agent_config(&AgentConfig::preset(AgentPreset::Gemini)).err();
+        assert!(matches!(err, Some(McpError::NoServerMode(_))));
+    }
+
     #[test]
     fn test_json_rpc_request_serialization() {
         let request =
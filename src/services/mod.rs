@@ -3,15 +3,28 @@
 //! This module contains:
 //! - GitService: Git and worktree operations
 //! - SpecService: Specification discovery and management
+//! - RunHistory: Persisted records of finished workflow runs
 //! - McpService: MCP client for AI agent communication
 //! - ProcessService: Process execution and streaming
+//! - StatusWorker: Background worktree status refresh
+//! - FsWatcher: Filesystem watching for specs and worktrees
 
 mod git;
+mod history;
 pub mod mcp;
 pub mod process;
 mod spec;
+mod status;
+mod watcher;
 
 pub use git::GitService;
+pub use history::RunHistory;
 pub use mcp::McpClient;
-pub use process::{ProcessHandle, ProcessOutput, ProcessService, WorkflowRunner};
+pub use process::{
+    output_adapter, CancelPolicy, OutputAdapter, PassThroughAdapter, ProcessHandle, ProcessOutput,
+    ProcessService, PruneReport, RetryPolicy, SessionPatternAdapter, StreamJsonAdapter,
+    WorkflowContext, WorkflowRunner,
+};
 pub use spec::SpecService;
+pub use status::{StatusUpdate, StatusWorker};
+pub use watcher::{FsWatcher, WatchChanges};
@@ -2,16 +2,31 @@
 //!
 //! Handles spawning processes, streaming output, and log persistence.
 
-use crate::domain::{SpecId, WorkflowCommand, WorkflowCommandType};
-use crate::error::{AppError, Result};
-use std::fs::{self, OpenOptions};
-use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
-use std::process::{Child, Command, Stdio};
+use crate::config::{AgentConfig, LogConfig, OutputFormat, RetryConfig, WorkflowConfig};
+use crate::domain::{
+    AgentEvent, CancelOutcome, SpecId, Specification, TokenUsage, WorkflowCommand,
+    WorkflowCommandType, WorkflowPhase,
+};
+use crate::error::{AppError, McpErrorKind, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::mpsc;
 
+/// Interval at which the waiter thread polls for process exit
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Seconds in a day, for retention calculations
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
 /// Output event from a running process
 #[derive(Debug, Clone)]
 pub enum ProcessOutput {
@@ -19,18 +34,408 @@ pub enum ProcessOutput {
     Stdout(String),
     /// Line from stderr
     Stderr(String),
+    /// Structured event parsed from stdout by an output adapter
+    Event(AgentEvent),
     /// Process exited with code
     Exit(i32),
     /// Process was killed/terminated
     Terminated,
-    /// Error occurred
+    /// Process group exited after a cancellation request
+    Cancelled(CancelOutcome),
+    /// Process could not be waited on; no exit event follows
     Error(String),
 }
 
+/// Turns lines from an agent's stdout into output events
+pub trait OutputAdapter: Send {
+    /// Convert a stdout line into zero or more output events
+    fn adapt(&mut self, line: String) -> Vec<ProcessOutput>;
+}
+
+/// Create the adapter for an agent output format
+pub fn output_adapter(format: OutputFormat) -> Box<dyn OutputAdapter> {
+    match format {
+        OutputFormat::Text => Box::new(PassThroughAdapter),
+        OutputFormat::StreamJson => Box::new(StreamJsonAdapter::default()),
+    }
+}
+
+/// Adapter that forwards every line unchanged
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PassThroughAdapter;
+
+impl OutputAdapter for PassThroughAdapter {
+    fn adapt(&mut self, line: String) -> Vec<ProcessOutput> {
+        vec![ProcessOutput::Stdout(line)]
+    }
+}
+
+/// Adapter that reports a session id found in the agent's output, wrapping
+/// another adapter that handles the lines themselves
+pub struct SessionPatternAdapter {
+    inner: Box<dyn OutputAdapter>,
+    pattern: Regex,
+    session_id: Option<String>,
+}
+
+impl SessionPatternAdapter {
+    /// Wrap an adapter; the first capture group of `pattern` is the session id
+    pub fn new(inner: Box<dyn OutputAdapter>, pattern: Regex) -> Self {
+        Self {
+            inner,
+            pattern,
+            session_id: None,
+        }
+    }
+}
+
+impl OutputAdapter for SessionPatternAdapter {
+    fn adapt(&mut self, line: String) -> Vec<ProcessOutput> {
+        let session_id = self
+            .pattern
+            .captures(&line)
+            .and_then(|captures| captures.get(1))
+            .map(|m| m.as_str().to_string());
+
+        let mut outputs = self.inner.adapt(line);
+        if let Some(id) = session_id {
+            if self.session_id.as_ref() != Some(&id) {
+                self.session_id = Some(id.clone());
+                outputs.push(ProcessOutput::Event(AgentEvent::Session(id)));
+            }
+        }
+        outputs
+    }
+}
+
+/// Adapter for newline-delimited JSON event streams (`stream-json`)
+///
+/// Assistant text, tool calls, tool results and the final usage report
+/// become typed events; lines that are not JSON are passed through.
+#[derive(Debug, Default, Clone)]
+pub struct StreamJsonAdapter {
+    /// Model reported by the most recent assistant message
+    model: Option<String>,
+    /// Session id reported so far
+    session_id: Option<String>,
+}
+
+impl OutputAdapter for StreamJsonAdapter {
+    fn adapt(&mut self, line: String) -> Vec<ProcessOutput> {
+        let value: serde_json::Value = match serde_json::from_str(&line) {
+            Ok(value @ serde_json::Value::Object(_)) => value,
+            _ => return vec![ProcessOutput::Stdout(line)],
+        };
+
+        let content = || {
+            value["message"]["content"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        let mut events = Vec::new();
+        if let Some(id) = value["session_id"].as_str() {
+            if self.session_id.as_deref() != Some(id) {
+                self.session_id = Some(id.to_string());
+                events.push(AgentEvent::Session(id.to_string()));
+            }
+        }
+
+        events.extend(match value["type"].as_str() {
+            Some("assistant") => {
+                if let Some(model) = value["message"]["model"].as_str() {
+                    self.model = Some(model.to_string());
+                }
+                content()
+                    .iter()
+                    .filter_map(|item| match item["type"].as_str() {
+                        Some("text") => item["text"]
+                            .as_str()
+                            .map(|text| AgentEvent::Text(text.to_string())),
+                        Some("tool_use") => Some(AgentEvent::ToolCall {
+                            name: item["name"].as_str().unwrap_or("tool").to_string(),
+                            input: json_text(&item["input"]),
+                        }),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            Some("user") => content()
+                .iter()
+                .filter(|item| item["type"] == "tool_result")
+                .map(|item| AgentEvent::ToolResult {
+                    output: json_text(&item["content"]),
+                    is_error: item["is_error"].as_bool().unwrap_or(false),
+                })
+                .collect(),
+            Some("result") => {
+                let usage = &value["usage"];
+                vec![AgentEvent::Usage(TokenUsage {
+                    model: self.model.clone(),
+                    input_tokens: usage["input_tokens"].as_u64().unwrap_or(0),
+                    output_tokens: usage["output_tokens"].as_u64().unwrap_or(0),
+                })]
+            }
+            // System and other bookkeeping events are not shown
+            _ => Vec::new(),
+        });
+
+        events.into_iter().map(ProcessOutput::Event).collect()
+    }
+}
+
+/// Render a JSON value as text: strings and text blocks as-is, anything else
+/// as compact JSON
+fn json_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(|item| match item["text"].as_str() {
+                Some(text) => text.to_string(),
+                None => item.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Grace periods for escalating cancellation (SIGINT, then SIGTERM, then SIGKILL)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CancelPolicy {
+    /// Time to wait after SIGINT before escalating
+    pub interrupt_grace: Duration,
+    /// Time to wait after SIGTERM before escalating
+    pub terminate_grace: Duration,
+}
+
+impl CancelPolicy {
+    /// Build a policy from the workflow configuration
+    pub fn from_config(config: &WorkflowConfig) -> Self {
+        Self {
+            interrupt_grace: Duration::from_millis(config.interrupt_grace_ms),
+            terminate_grace: Duration::from_millis(config.terminate_grace_ms),
+        }
+    }
+}
+
+impl Default for CancelPolicy {
+    fn default() -> Self {
+        Self::from_config(&WorkflowConfig::default())
+    }
+}
+
+/// When and how often to retry a failed workflow command
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Total attempts, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub backoff: Duration,
+    /// Factor applied to the delay after each retry
+    pub backoff_multiplier: f64,
+    /// Upper bound for the delay
+    pub max_backoff: Duration,
+    /// Exit codes that trigger a retry; empty retries any non-zero exit
+    pub exit_codes: Vec<i32>,
+    /// Error kinds that trigger a retry when the agent cannot be run
+    pub errors: Vec<McpErrorKind>,
+}
+
+impl RetryPolicy {
+    /// Build a policy from a retry configuration
+    pub fn from_config(config: &RetryConfig) -> Self {
+        Self {
+            max_attempts: config.max_attempts.max(1),
+            backoff: Duration::from_millis(config.backoff_ms),
+            backoff_multiplier: config.backoff_multiplier.max(1.0),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+            exit_codes: config.exit_codes.clone(),
+            errors: config.errors.clone(),
+        }
+    }
+
+    /// Whether another attempt may follow the given one
+    pub fn allows_attempt_after(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts
+    }
+
+    /// Whether a command that exited with this code should be retried
+    pub fn retries_exit_code(&self, code: i32) -> bool {
+        code != 0 && (self.exit_codes.is_empty() || self.exit_codes.contains(&code))
+    }
+
+    /// Whether a command that could not be run should be retried
+    pub fn retries_error(&self, error: &AppError) -> bool {
+        let kind = match error {
+            AppError::Mcp(e) => e.kind(),
+            AppError::Process(_) => McpErrorKind::SpawnFailed,
+            AppError::Io(_) | AppError::IoString(_) => McpErrorKind::Io,
+            _ => return false,
+        };
+        self.errors.contains(&kind)
+    }
+
+    /// Delay before the given attempt (2 for the first retry)
+    pub fn delay_before(&self, attempt: u32) -> Duration {
+        let retries = attempt.saturating_sub(2).min(32) as i32;
+        self.backoff
+            .mul_f64(self.backoff_multiplier.powi(retries))
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from_config(&RetryConfig::default())
+    }
+}
+
+/// Spec context exported to workflow processes as `SPECK_*` variables
+#[derive(Debug, Clone)]
+pub struct WorkflowContext {
+    /// Spec identifier (e.g. `001-feature-name`)
+    pub spec_id: String,
+    /// Spec directory (inside the worktree when one is set)
+    pub spec_dir: PathBuf,
+    /// Spec directory relative to the working directory
+    pub spec_path: PathBuf,
+    /// Branch associated with the spec
+    pub branch: String,
+    /// Worktree checked out for the spec, if any
+    pub worktree: Option<PathBuf>,
+    /// Current workflow phase
+    pub phase: WorkflowPhase,
+    /// User-defined environment, applied after the `SPECK_*` variables
+    pub env: Vec<(String, String)>,
+    /// Agent session to continue, if any
+    pub session_id: Option<String>,
+    /// Log created for the first attempt before it starts, so pre-run hooks
+    /// can write to it
+    pub log_file: Option<PathBuf>,
+}
+
+impl WorkflowContext {
+    /// Create a context for a specification
+    pub fn new(spec: &Specification) -> Self {
+        Self {
+            spec_id: spec.id.as_str().to_string(),
+            spec_dir: spec.directory.clone(),
+            spec_path: PathBuf::from("."),
+            branch: spec.branch.clone(),
+            worktree: None,
+            phase: spec.phase,
+            env: Vec::new(),
+            session_id: None,
+            log_file: None,
+        }
+    }
+
+    /// Run inside the spec's worktree, with the spec at `spec_path` relative
+    /// to the worktree root
+    pub fn in_worktree(mut self, worktree: PathBuf, spec_path: PathBuf) -> Self {
+        self.spec_dir = worktree.join(&spec_path);
+        self.spec_path = spec_path;
+        self.worktree = Some(worktree);
+        self
+    }
+
+    /// Directory the workflow process runs in: the worktree root if set,
+    /// otherwise the spec directory
+    pub fn working_dir(&self) -> &Path {
+        self.worktree.as_deref().unwrap_or(&self.spec_dir)
+    }
+
+    /// Set user-defined environment variables
+    pub fn with_env(mut self, env: Vec<(String, String)>) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// Continue a previous agent session
+    pub fn with_session(mut self, session_id: Option<String>) -> Self {
+        self.session_id = session_id;
+        self
+    }
+
+    /// Log the first attempt to an already created log
+    pub fn with_log_file(mut self, log_file: PathBuf) -> Self {
+        self.log_file = Some(log_file);
+        self
+    }
+
+    /// Environment variables for a command run in this context
+    pub fn env_vars(
+        &self,
+        command_type: WorkflowCommandType,
+        log_file: &Path,
+    ) -> Vec<(String, String)> {
+        let worktree = self
+            .worktree
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+
+        let mut vars = vec![
+            ("SPECK_SPEC_ID".to_string(), self.spec_id.clone()),
+            (
+                "SPECK_SPEC_DIR".to_string(),
+                self.spec_dir.display().to_string(),
+            ),
+            ("SPECK_BRANCH".to_string(), self.branch.clone()),
+            ("SPECK_WORKTREE".to_string(), worktree),
+            (
+                "SPECK_PHASE".to_string(),
+                self.phase.display_name().to_lowercase(),
+            ),
+            (
+                "SPECK_COMMAND".to_string(),
+                command_type.config_key().to_string(),
+            ),
+            ("SPECK_LOG_FILE".to_string(), log_file.display().to_string()),
+        ];
+        if let Some(ref session_id) = self.session_id {
+            vars.push(("SPECK_SESSION_ID".to_string(), session_id.clone()));
+        }
+        vars.extend(self.env.iter().cloned());
+        vars
+    }
+
+    /// Expand `{tool}`, `{spec_id}`, `{spec_dir}`, `{worktree}`, `{phase}` and
+    /// `{session_id}` in an agent argument template
+    pub fn render_args(
+        &self,
+        template: &[String],
+        command_type: WorkflowCommandType,
+    ) -> Vec<String> {
+        let worktree = self.working_dir().display().to_string();
+        let spec_dir = self.spec_path.display().to_string();
+        let phase = self.phase.display_name().to_lowercase();
+
+        template
+            .iter()
+            .map(|arg| {
+                arg.replace("{tool}", command_type.tool_name())
+                    .replace("{spec_id}", &self.spec_id)
+                    .replace("{spec_dir}", &spec_dir)
+                    .replace("{worktree}", &worktree)
+                    .replace("{phase}", &phase)
+                    .replace(
+                        "{session_id}",
+                        self.session_id.as_deref().unwrap_or_default(),
+                    )
+            })
+            .collect()
+    }
+}
+
 /// Process handle for a running command
 pub struct ProcessHandle {
-    /// Child process
-    child: Child,
+    /// Child process (shared with the waiter thread)
+    child: Arc<Mutex<Child>>,
     /// Start time
     start_time: Instant,
     /// Output receiver
@@ -39,9 +444,86 @@ pub struct ProcessHandle {
     log_file: Option<PathBuf>,
     /// Is running
     running: Arc<Mutex<bool>>,
+    /// Process ID (also the process group ID)
+    pid: u32,
+    /// Last cancellation step taken, if cancellation was requested
+    cancel_outcome: Arc<Mutex<Option<CancelOutcome>>>,
+    /// Stdin pipe, if the process accepts input and it has not been closed
+    stdin: Option<ChildStdin>,
 }
 
 impl ProcessHandle {
+    /// Check if the process stdin is open for writing
+    pub fn has_stdin(&self) -> bool {
+        self.stdin.is_some() && self.is_running()
+    }
+
+    /// Write a line to the process stdin, recording it in the log as `[IN]`
+    pub fn write_stdin(&mut self, line: &str) -> Result<()> {
+        let stdin = self
+            .stdin
+            .as_mut()
+            .ok_or_else(|| AppError::Process("stdin is closed".to_string()))?;
+        writeln!(stdin, "{}", line)
+            .and_then(|_| stdin.flush())
+            .map_err(|e| AppError::Process(format!("Failed to write to stdin: {}", e)))?;
+
+        self.append_log(&format!("[IN] {}", line));
+        Ok(())
+    }
+
+    /// Close the process stdin, signalling end of input
+    pub fn close_stdin(&mut self) {
+        if self.stdin.take().is_some() {
+            self.append_log("[IN] <EOF>");
+        }
+    }
+
+    /// Append a line to the run log, if any
+    fn append_log(&self, line: &str) {
+        if let Some(path) = &self.log_file {
+            if let Ok(mut f) = OpenOptions::new().append(true).open(path) {
+                let _ = writeln!(f, "{}", line);
+            }
+        }
+    }
+
+    /// Get the process ID (also the process group ID)
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Check if cancellation has been requested
+    pub fn is_cancelling(&self) -> bool {
+        self.cancel_outcome.lock().unwrap().is_some()
+    }
+
+    /// Request graceful cancellation of the whole process group.
+    ///
+    /// Sends SIGINT, then SIGTERM, then SIGKILL, waiting for the group to exit
+    /// between steps. Returns immediately; the outcome is reported as
+    /// [`ProcessOutput::Cancelled`] once the process has exited.
+    pub fn cancel(&self, policy: CancelPolicy) {
+        if !self.is_running() || self.is_cancelling() {
+            return;
+        }
+        *self.cancel_outcome.lock().unwrap() = Some(CancelOutcome::Interrupted);
+
+        #[cfg(unix)]
+        {
+            let pid = self.pid;
+            let outcome = self.cancel_outcome.clone();
+            std::thread::spawn(move || escalate_cancel(pid, policy, outcome));
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = policy;
+            *self.cancel_outcome.lock().unwrap() = Some(CancelOutcome::Killed);
+            let _ = self.child.lock().unwrap().kill();
+        }
+    }
+
     /// Check if the process is still running
     pub fn is_running(&self) -> bool {
         *self.running.lock().unwrap()
@@ -62,10 +544,16 @@ impl ProcessHandle {
         self.output_rx.try_recv().ok()
     }
 
-    /// Kill the process
+    /// Kill the process (and its process group) immediately
     pub fn kill(&mut self) -> Result<()> {
         *self.running.lock().unwrap() = false;
+
+        #[cfg(unix)]
+        signal_group(self.pid, libc::SIGKILL);
+
         self.child
+            .lock()
+            .unwrap()
             .kill()
             .map_err(|e| AppError::Process(e.to_string()))?;
         Ok(())
@@ -73,12 +561,62 @@ impl ProcessHandle {
 
     /// Wait for the process to complete
     pub fn wait(&mut self) -> Result<i32> {
-        let status = self
-            .child
-            .wait()
-            .map_err(|e| AppError::Process(e.to_string()))?;
-        *self.running.lock().unwrap() = false;
-        Ok(status.code().unwrap_or(-1))
+        loop {
+            let status = self
+                .child
+                .lock()
+                .unwrap()
+                .try_wait()
+                .map_err(|e| AppError::Process(e.to_string()))?;
+            if let Some(status) = status {
+                *self.running.lock().unwrap() = false;
+                return Ok(status.code().unwrap_or(-1));
+            }
+            std::thread::sleep(EXIT_POLL_INTERVAL);
+        }
+    }
+}
+
+/// Result of a log retention pass
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PruneReport {
+    /// Number of log files removed
+    pub removed: usize,
+    /// Number of log files compressed
+    pub compressed: usize,
+    /// Bytes reclaimed by removal and compression
+    pub bytes_freed: u64,
+}
+
+impl std::fmt::Display for PruneReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "removed {} log(s), compressed {} log(s), freed {} KiB",
+            self.removed,
+            self.compressed,
+            self.bytes_freed / 1024
+        )
+    }
+}
+
+/// A workflow log file found in the log directory
+#[derive(Debug, Clone)]
+struct LogEntry {
+    path: PathBuf,
+    spec_id: String,
+    modified: SystemTime,
+    size: u64,
+    compressed: bool,
+}
+
+impl LogEntry {
+    /// Age of the log in whole days
+    fn age_days(&self, now: SystemTime) -> u64 {
+        now.duration_since(self.modified)
+            .unwrap_or_default()
+            .as_secs()
+            / SECS_PER_DAY
     }
 }
 
@@ -86,12 +624,28 @@ impl ProcessHandle {
 pub struct ProcessService {
     /// Log directory
     log_dir: PathBuf,
+    /// Log retention limits
+    retention: LogConfig,
 }
 
 impl ProcessService {
     /// Create a new process service
     pub fn new(log_dir: PathBuf) -> Self {
-        Self { log_dir }
+        Self {
+            log_dir,
+            retention: LogConfig::default(),
+        }
+    }
+
+    /// Set the log retention limits
+    pub fn with_retention(mut self, retention: LogConfig) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Get the log directory
+    pub fn log_dir(&self) -> &Path {
+        &self.log_dir
     }
 
     /// Ensure log directory exists
@@ -102,27 +656,42 @@ impl ProcessService {
         Ok(())
     }
 
-    /// Generate log file path for a command
-    fn log_file_path(&self, command_type: WorkflowCommandType, spec_id: &str) -> PathBuf {
+    /// Generate log file path for one attempt of a command; retries get
+    /// their own log so earlier attempts are kept
+    fn log_file_path(
+        &self,
+        command_type: WorkflowCommandType,
+        spec_id: &str,
+        attempt: u32,
+    ) -> PathBuf {
         let timestamp = chrono_lite_timestamp();
-        let filename = format!("{}-{}-{}.log", spec_id, command_type.tool_name(), timestamp);
+        let filename = if attempt > 1 {
+            format!(
+                "{}-{}-{}-{}.log",
+                spec_id,
+                command_type.tool_name(),
+                timestamp,
+                attempt
+            )
+        } else {
+            format!("{}-{}-{}.log", spec_id, command_type.tool_name(), timestamp)
+        };
         self.log_dir.join(filename)
     }
 
-    /// Spawn a workflow command process
-    pub fn spawn_workflow(
+    /// Create the log for one attempt of a command, starting with a header
+    pub fn create_log(
         &self,
         command_type: WorkflowCommandType,
-        spec_id: &str,
-        spec_directory: &PathBuf,
-        mcp_command: &str,
-        mcp_args: &[String],
-    ) -> Result<ProcessHandle> {
+        context: &WorkflowContext,
+        attempt: u32,
+    ) -> Result<PathBuf> {
         self.ensure_log_dir()?;
 
-        let log_file_path = self.log_file_path(command_type, spec_id);
+        let spec_id = context.spec_id.as_str();
+        let working_dir = context.working_dir();
+        let log_file_path = self.log_file_path(command_type, spec_id, attempt);
 
-        // Create log file
         let mut log_file = OpenOptions::new()
             .create(true)
             .write(true)
@@ -140,23 +709,68 @@ impl ProcessService {
         .map_err(|e| AppError::io(e.to_string()))?;
         writeln!(log_file, "# Started: {}", chrono_lite_timestamp())
             .map_err(|e| AppError::io(e.to_string()))?;
-        writeln!(log_file, "# Directory: {}", spec_directory.display())
+        writeln!(log_file, "# Directory: {}", working_dir.display())
             .map_err(|e| AppError::io(e.to_string()))?;
+        if attempt > 1 {
+            writeln!(log_file, "# Attempt: {}", attempt)
+                .map_err(|e| AppError::io(e.to_string()))?;
+        }
         writeln!(log_file, "---").map_err(|e| AppError::io(e.to_string()))?;
+        Ok(log_file_path)
+    }
+
+    /// Spawn a workflow command process
+    pub fn spawn_workflow(
+        &self,
+        command_type: WorkflowCommandType,
+        context: &WorkflowContext,
+        agent_command: &str,
+        arg_template: &[String],
+    ) -> Result<ProcessHandle> {
+        self.spawn_workflow_with_adapter(
+            command_type,
+            context,
+            agent_command,
+            arg_template,
+            Box::new(PassThroughAdapter),
+            1,
+        )
+    }
+
+    /// Spawn a workflow command, parsing its stdout with an output adapter
+    pub fn spawn_workflow_with_adapter(
+        &self,
+        command_type: WorkflowCommandType,
+        context: &WorkflowContext,
+        agent_command: &str,
+        arg_template: &[String],
+        mut adapter: Box<dyn OutputAdapter>,
+        attempt: u32,
+    ) -> Result<ProcessHandle> {
+        let working_dir = context.working_dir();
+        let log_file_path = match context.log_file.clone().filter(|_| attempt == 1) {
+            Some(path) => path,
+            None => self.create_log(command_type, context, attempt)?,
+        };
 
         // Build command arguments
-        let mut args = mcp_args.to_vec();
-        args.push(command_type.tool_name().to_string());
+        let args = context.render_args(arg_template, command_type);
 
-        // Spawn the process
-        let mut child = Command::new(mcp_command)
+        // Spawn the process in its own process group
+        let mut command = Command::new(agent_command);
+        command
             .args(&args)
-            .current_dir(spec_directory)
+            .current_dir(working_dir)
+            .envs(context.env_vars(command_type, &log_file_path))
+            .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
+            .stderr(Stdio::piped());
+        set_process_group(&mut command);
+        let mut child = command
             .spawn()
             .map_err(|e| AppError::Process(format!("Failed to spawn process: {}", e)))?;
 
+        let stdin = child.stdin.take();
         let stdout = child.stdout.take();
         let stderr = child.stderr.take();
 
@@ -164,12 +778,14 @@ impl ProcessService {
         let (output_tx, output_rx) = mpsc::unbounded_channel();
         let running = Arc::new(Mutex::new(true));
 
+        let mut readers = Vec::new();
+
         // Spawn thread to read stdout
         if let Some(stdout) = stdout {
             let tx = output_tx.clone();
             let running_clone = running.clone();
             let log_path = log_file_path.clone();
-            std::thread::spawn(move || {
+            readers.push(std::thread::spawn(move || {
                 let reader = BufReader::new(stdout);
                 for line in reader.lines() {
                     if !*running_clone.lock().unwrap() {
@@ -181,15 +797,21 @@ impl ProcessService {
                             if let Ok(mut f) = OpenOptions::new().append(true).open(&log_path) {
                                 let _ = writeln!(f, "[OUT] {}", text);
                             }
-                            let _ = tx.send(ProcessOutput::Stdout(text));
+                            for output in adapter.adapt(text) {
+                                let _ = tx.send(output);
+                            }
                         }
                         Err(e) => {
-                            let _ = tx.send(ProcessOutput::Error(e.to_string()));
+                            // The process keeps running; its exit is still reported
+                            let _ = tx.send(ProcessOutput::Stderr(format!(
+                                "Failed to read output: {}",
+                                e
+                            )));
                             break;
                         }
                     }
                 }
-            });
+            }));
         }
 
         // Spawn thread to read stderr
@@ -197,7 +819,7 @@ impl ProcessService {
             let tx = output_tx.clone();
             let running_clone = running.clone();
             let log_path = log_file_path.clone();
-            std::thread::spawn(move || {
+            readers.push(std::thread::spawn(move || {
                 let reader = BufReader::new(stderr);
                 for line in reader.lines() {
                     if !*running_clone.lock().unwrap() {
@@ -212,20 +834,38 @@ impl ProcessService {
                             let _ = tx.send(ProcessOutput::Stderr(text));
                         }
                         Err(e) => {
-                            let _ = tx.send(ProcessOutput::Error(e.to_string()));
+                            // The process keeps running; its exit is still reported
+                            let _ = tx.send(ProcessOutput::Stderr(format!(
+                                "Failed to read output: {}",
+                                e
+                            )));
                             break;
                         }
                     }
                 }
-            });
+            }));
         }
 
+        let pid = child.id();
+        let child = Arc::new(Mutex::new(child));
+        let cancel_outcome = Arc::new(Mutex::new(None));
+        spawn_waiter(
+            child.clone(),
+            readers,
+            output_tx,
+            running.clone(),
+            cancel_outcome.clone(),
+        );
+
         Ok(ProcessHandle {
             child,
             start_time: Instant::now(),
             output_rx,
             log_file: Some(log_file_path),
             running,
+            pid,
+            cancel_outcome,
+            stdin,
         })
     }
 
@@ -236,11 +876,39 @@ impl ProcessService {
         args: &[String],
         working_dir: &PathBuf,
     ) -> Result<ProcessHandle> {
-        let mut child = Command::new(command)
+        self.spawn_command_with_env(command, args, &[], working_dir)
+    }
+
+    /// Spawn a simple command with extra environment variables
+    pub fn spawn_command_with_env(
+        &self,
+        command: &str,
+        args: &[String],
+        env: &[(&str, &str)],
+        working_dir: &PathBuf,
+    ) -> Result<ProcessHandle> {
+        self.spawn_command_logged(command, args, env, working_dir, None)
+    }
+
+    /// Spawn a simple command, appending its output to a log if given
+    pub fn spawn_command_logged(
+        &self,
+        command: &str,
+        args: &[String],
+        env: &[(&str, &str)],
+        working_dir: &PathBuf,
+        log_file: Option<PathBuf>,
+    ) -> Result<ProcessHandle> {
+        let mut command = Command::new(command);
+        command
             .args(args)
+            .envs(env.iter().copied())
             .current_dir(working_dir)
+            .stdin(Stdio::null())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
+            .stderr(Stdio::piped());
+        set_process_group(&mut command);
+        let mut child = command
             .spawn()
             .map_err(|e| AppError::Process(format!("Failed to spawn process: {}", e)))?;
 
@@ -250,11 +918,14 @@ impl ProcessService {
         let (output_tx, output_rx) = mpsc::unbounded_channel();
         let running = Arc::new(Mutex::new(true));
 
+        let mut readers = Vec::new();
+
         // Spawn thread to read stdout
         if let Some(stdout) = stdout {
             let tx = output_tx.clone();
             let running_clone = running.clone();
-            std::thread::spawn(move || {
+            let log_path = log_file.clone();
+            readers.push(std::thread::spawn(move || {
                 let reader = BufReader::new(stdout);
                 for line in reader.lines() {
                     if !*running_clone.lock().unwrap() {
@@ -262,22 +933,32 @@ impl ProcessService {
                     }
                     match line {
                         Ok(text) => {
+                            if let Some(ref path) = log_path {
+                                if let Ok(mut f) = OpenOptions::new().append(true).open(path) {
+                                    let _ = writeln!(f, "[OUT] {}", text);
+                                }
+                            }
                             let _ = tx.send(ProcessOutput::Stdout(text));
                         }
                         Err(e) => {
-                            let _ = tx.send(ProcessOutput::Error(e.to_string()));
+                            // The process keeps running; its exit is still reported
+                            let _ = tx.send(ProcessOutput::Stderr(format!(
+                                "Failed to read output: {}",
+                                e
+                            )));
                             break;
                         }
                     }
                 }
-            });
+            }));
         }
 
         // Spawn thread to read stderr
         if let Some(stderr) = stderr {
             let tx = output_tx.clone();
             let running_clone = running.clone();
-            std::thread::spawn(move || {
+            let log_path = log_file.clone();
+            readers.push(std::thread::spawn(move || {
                 let reader = BufReader::new(stderr);
                 for line in reader.lines() {
                     if !*running_clone.lock().unwrap() {
@@ -285,25 +966,275 @@ impl ProcessService {
                     }
                     match line {
                         Ok(text) => {
+                            if let Some(ref path) = log_path {
+                                if let Ok(mut f) = OpenOptions::new().append(true).open(path) {
+                                    let _ = writeln!(f, "[ERR] {}", text);
+                                }
+                            }
                             let _ = tx.send(ProcessOutput::Stderr(text));
                         }
                         Err(e) => {
-                            let _ = tx.send(ProcessOutput::Error(e.to_string()));
+                            // The process keeps running; its exit is still reported
+                            let _ = tx.send(ProcessOutput::Stderr(format!(
+                                "Failed to read output: {}",
+                                e
+                            )));
                             break;
                         }
                     }
                 }
-            });
+            }));
         }
 
+        let pid = child.id();
+        let child = Arc::new(Mutex::new(child));
+        let cancel_outcome = Arc::new(Mutex::new(None));
+        spawn_waiter(
+            child.clone(),
+            readers,
+            output_tx,
+            running.clone(),
+            cancel_outcome.clone(),
+        );
+
         Ok(ProcessHandle {
             child,
             start_time: Instant::now(),
             output_rx,
-            log_file: None,
+            log_file,
             running,
+            pid,
+            cancel_outcome,
+            stdin: None,
         })
     }
+
+    /// Enforce the configured log retention limits.
+    ///
+    /// Removes logs that are too old, beyond the per-spec count, or beyond the
+    /// total size budget (oldest first), then gzips the remaining old logs.
+    pub fn prune_logs(&self) -> Result<PruneReport> {
+        let mut report = PruneReport::default();
+        if !self.log_dir.exists() {
+            return Ok(report);
+        }
+
+        let now = SystemTime::now();
+        let mut entries = self.scan_logs()?;
+        // Newest first
+        entries.sort_by_key(|e| std::cmp::Reverse(e.modified));
+
+        let mut keep = Vec::with_capacity(entries.len());
+        let mut per_spec: HashMap<String, usize> = HashMap::new();
+
+        for entry in entries {
+            let too_old = self.retention.max_age_days > 0
+                && entry.age_days(now) >= self.retention.max_age_days;
+
+            let count = per_spec.entry(entry.spec_id.clone()).or_insert(0);
+            *count += 1;
+            let too_many = self.retention.max_per_spec > 0 && *count > self.retention.max_per_spec;
+
+            if too_old || too_many {
+                remove_log(&entry, &mut report);
+            } else {
+                keep.push(entry);
+            }
+        }
+
+        if self.retention.max_total_size_mb > 0 {
+            let budget = self.retention.max_total_size_mb * 1024 * 1024;
+            let mut total: u64 = keep.iter().map(|e| e.size).sum();
+            // Drop the oldest logs until we fit
+            while total > budget {
+                match keep.pop() {
+                    Some(entry) => {
+                        total -= entry.size;
+                        remove_log(&entry, &mut report);
+                    }
+                    None => break,
+                }
+            }
+        }
+
+        if self.retention.compress_after_days > 0 {
+            for entry in keep.iter().filter(|e| !e.compressed) {
+                if entry.age_days(now) < self.retention.compress_after_days {
+                    continue;
+                }
+                match compress_log(&entry.path) {
+                    Ok(compressed_size) => {
+                        report.compressed += 1;
+                        report.bytes_freed += entry.size.saturating_sub(compressed_size);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to compress log {:?}: {}", entry.path, e);
+                    }
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Collect all workflow logs in the log directory
+    fn scan_logs(&self) -> Result<Vec<LogEntry>> {
+        let pattern = Regex::new(r"^(\d{3}-.+)-speckit\.[a-z]+-\d+(?:-\d+)?\.log(\.gz)?$").unwrap();
+        let mut entries = Vec::new();
+
+        let dir = fs::read_dir(&self.log_dir).map_err(|e| AppError::io(e.to_string()))?;
+        for entry in dir.flatten() {
+            let path = entry.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let Some(captures) = pattern.captures(name) else {
+                continue;
+            };
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+
+            entries.push(LogEntry {
+                spec_id: captures[1].to_string(),
+                compressed: captures.get(2).is_some(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                size: metadata.len(),
+                path,
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+/// Put the command in its own process group so the whole tree can be signalled
+fn set_process_group(command: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(not(unix))]
+    let _ = command;
+}
+
+/// Send a signal to a process group, returning false if the group no longer exists
+#[cfg(unix)]
+fn signal_group(pgid: u32, signal: libc::c_int) -> bool {
+    // SAFETY: kill(2) has no memory-safety preconditions
+    unsafe { libc::kill(-(pgid as libc::pid_t), signal) == 0 }
+}
+
+/// Escalate cancellation of a process group until it exits
+#[cfg(unix)]
+fn escalate_cancel(pgid: u32, policy: CancelPolicy, outcome: Arc<Mutex<Option<CancelOutcome>>>) {
+    let steps = [
+        (
+            libc::SIGINT,
+            CancelOutcome::Interrupted,
+            policy.interrupt_grace,
+        ),
+        (
+            libc::SIGTERM,
+            CancelOutcome::Terminated,
+            policy.terminate_grace,
+        ),
+    ];
+
+    for (signal, step, grace) in steps {
+        *outcome.lock().unwrap() = Some(step);
+        if !signal_group(pgid, signal) {
+            return;
+        }
+
+        // Signal 0 only checks whether any member of the group is still alive
+        let deadline = Instant::now() + grace;
+        while Instant::now() < deadline {
+            if !signal_group(pgid, 0) {
+                return;
+            }
+            std::thread::sleep(EXIT_POLL_INTERVAL);
+        }
+    }
+
+    *outcome.lock().unwrap() = Some(CancelOutcome::Killed);
+    signal_group(pgid, libc::SIGKILL);
+}
+
+/// Spawn a thread that reaps the child and waits for the readers to drain,
+/// then reports the exit on the output channel.
+fn spawn_waiter(
+    child: Arc<Mutex<Child>>,
+    readers: Vec<JoinHandle<()>>,
+    tx: mpsc::UnboundedSender<ProcessOutput>,
+    running: Arc<Mutex<bool>>,
+    cancel_outcome: Arc<Mutex<Option<CancelOutcome>>>,
+) {
+    std::thread::spawn(move || {
+        // Reap the child promptly so cancellation sees the group go away
+        let status = loop {
+            let status = child.lock().unwrap().try_wait();
+            match status {
+                Ok(Some(status)) => break status,
+                Ok(None) => std::thread::sleep(EXIT_POLL_INTERVAL),
+                Err(e) => {
+                    *running.lock().unwrap() = false;
+                    let _ = tx.send(ProcessOutput::Error(e.to_string()));
+                    return;
+                }
+            }
+        };
+
+        // Readers finish at EOF, so all output is sent before the exit event
+        for reader in readers {
+            let _ = reader.join();
+        }
+
+        let was_running = std::mem::replace(&mut *running.lock().unwrap(), false);
+        let cancelled = *cancel_outcome.lock().unwrap();
+        let event = match (cancelled, status.code()) {
+            (Some(outcome), _) => ProcessOutput::Cancelled(outcome),
+            (None, Some(code)) if was_running => ProcessOutput::Exit(code),
+            _ => ProcessOutput::Terminated,
+        };
+        let _ = tx.send(event);
+    });
+}
+
+/// Delete a log file, recording it in the report
+fn remove_log(entry: &LogEntry, report: &mut PruneReport) {
+    match fs::remove_file(&entry.path) {
+        Ok(()) => {
+            report.removed += 1;
+            report.bytes_freed += entry.size;
+        }
+        Err(e) => tracing::warn!("Failed to remove log {:?}: {}", entry.path, e),
+    }
+}
+
+/// Gzip a log file in place (`foo.log` -> `foo.log.gz`), returning the compressed size
+fn compress_log(path: &Path) -> io::Result<u64> {
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let gz_path = PathBuf::from(gz_name);
+
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(&gz_path)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    let output = encoder.finish()?;
+
+    // Keep the original timestamp so age-based retention still applies
+    if let Ok(modified) = fs::metadata(path).and_then(|m| m.modified()) {
+        let _ = output.set_modified(modified);
+    }
+    let size = output.metadata()?.len();
+    fs::remove_file(path)?;
+
+    Ok(size)
 }
 
 /// Generate a simple timestamp without chrono dependency
@@ -320,44 +1251,204 @@ fn chrono_lite_timestamp() -> String {
 /// Workflow command runner that integrates with the domain model
 pub struct WorkflowRunner {
     process_service: ProcessService,
-    mcp_command: String,
-    mcp_args: Vec<String>,
+    agent_command: String,
+    arg_template: Vec<String>,
+    cancel_policy: CancelPolicy,
+    output_format: OutputFormat,
+    retry_policy: RetryPolicy,
+    command_retry_policies: HashMap<String, RetryPolicy>,
+    resume_template: Option<Vec<String>>,
+    session_pattern: Option<Regex>,
 }
 
 impl WorkflowRunner {
-    /// Create a new workflow runner
-    pub fn new(log_dir: PathBuf, mcp_command: String, mcp_args: Vec<String>) -> Self {
+    /// Create a new workflow runner with an agent command and argument template
+    pub fn new(log_dir: PathBuf, agent_command: String, arg_template: Vec<String>) -> Self {
         Self {
             process_service: ProcessService::new(log_dir),
-            mcp_command,
-            mcp_args,
+            agent_command,
+            arg_template,
+            cancel_policy: CancelPolicy::default(),
+            output_format: OutputFormat::default(),
+            retry_policy: RetryPolicy::default(),
+            command_retry_policies: HashMap::new(),
+            resume_template: None,
+            session_pattern: None,
         }
     }
 
+    /// Create a runner for the configured agent
+    pub fn from_agent_config(log_dir: PathBuf, agent: &AgentConfig) -> Self {
+        let mut runner = Self::new(log_dir, agent.command(), agent.args())
+            .with_output_format(agent.output_format);
+        runner.resume_template = agent.resume_args();
+        runner.session_pattern =
+            agent
+                .session_pattern
+                .as_deref()
+                .and_then(|pattern| match Regex::new(pattern) {
+                    Ok(regex) => Some(regex),
+                    Err(e) => {
+                        tracing::warn!("Ignoring invalid agent.session_pattern: {}", e);
+                        None
+                    }
+                });
+        runner
+    }
+
+    /// Check if the agent can continue a previous session
+    pub fn supports_resume(&self) -> bool {
+        self.resume_template.is_some()
+    }
+
+    /// Set the format used to parse the agent's stdout
+    pub fn with_output_format(mut self, format: OutputFormat) -> Self {
+        self.output_format = format;
+        self
+    }
+
     /// Create with default settings
     pub fn default_with_log_dir(log_dir: PathBuf) -> Self {
-        Self::new(log_dir, "claude".to_string(), vec!["--mcp".to_string()])
+        Self::from_agent_config(log_dir, &AgentConfig::default())
+    }
+
+    /// Set the log retention limits
+    pub fn with_log_retention(mut self, retention: LogConfig) -> Self {
+        self.process_service = self.process_service.with_retention(retention);
+        self
+    }
+
+    /// Enforce log retention on the workflow log directory
+    pub fn prune_logs(&self) -> Result<PruneReport> {
+        self.process_service.prune_logs()
+    }
+
+    /// Set the cancellation grace periods
+    pub fn with_cancel_policy(mut self, policy: CancelPolicy) -> Self {
+        self.cancel_policy = policy;
+        self
+    }
+
+    /// Get the cancellation grace periods
+    pub fn cancel_policy(&self) -> CancelPolicy {
+        self.cancel_policy
+    }
+
+    /// Set the retry policies from the workflow configuration
+    pub fn with_retry_policies(mut self, config: &WorkflowConfig) -> Self {
+        self.retry_policy = RetryPolicy::from_config(&config.retry);
+        self.command_retry_policies = config
+            .commands
+            .iter()
+            .filter_map(|(name, command)| {
+                let retry = command.retry.as_ref()?;
+                Some((name.clone(), RetryPolicy::from_config(retry)))
+            })
+            .collect();
+        self
+    }
+
+    /// Get the retry policy for a workflow command
+    pub fn retry_policy(&self, command_type: WorkflowCommandType) -> &RetryPolicy {
+        self.command_retry_policies
+            .get(command_type.config_key())
+            .unwrap_or(&self.retry_policy)
+    }
+
+    /// Create the log for the first attempt of a command ahead of starting
+    /// it, so pre-run hooks can write to it
+    pub fn create_run_log(
+        &self,
+        command_type: WorkflowCommandType,
+        context: &WorkflowContext,
+    ) -> Result<PathBuf> {
+        self.process_service.create_log(command_type, context, 1)
+    }
+
+    /// Start a hook shell command in a working directory, appending its
+    /// output to the run's log if given
+    pub fn spawn_hook(
+        &self,
+        run: &str,
+        working_dir: &PathBuf,
+        log_file: Option<&Path>,
+    ) -> Result<ProcessHandle> {
+        if let Some(path) = log_file {
+            if let Ok(mut f) = OpenOptions::new().append(true).open(path) {
+                let _ = writeln!(f, "# Hook: {}", run);
+            }
+        }
+        self.process_service.spawn_command_logged(
+            "sh",
+            &["-c".to_string(), run.to_string()],
+            &[],
+            working_dir,
+            log_file.map(Path::to_path_buf),
+        )
+    }
+
+    /// Run `git` with the given arguments in a directory.
+    ///
+    /// Credential prompts and editors are disabled, since they would draw
+    /// over the TUI; merges and rebases keep their default messages.
+    pub fn spawn_git(&self, args: &[String], working_dir: &PathBuf) -> Result<ProcessHandle> {
+        self.process_service.spawn_command_with_env(
+            "git",
+            args,
+            &[("GIT_TERMINAL_PROMPT", "0"), ("GIT_EDITOR", "true")],
+            working_dir,
+        )
     }
 
     /// Start a workflow command
     pub fn start_command(
         &self,
         command_type: WorkflowCommandType,
-        spec_id: &str,
-        spec_directory: &PathBuf,
+        context: &WorkflowContext,
     ) -> Result<(WorkflowCommand, ProcessHandle)> {
-        let handle = self.process_service.spawn_workflow(
+        self.start_attempt(command_type, context, 1)
+    }
+
+    /// Start one attempt of a workflow command, logged to its own file
+    pub fn start_attempt(
+        &self,
+        command_type: WorkflowCommandType,
+        context: &WorkflowContext,
+        attempt: u32,
+    ) -> Result<(WorkflowCommand, ProcessHandle)> {
+        tracing::info!(
+            "Starting {} for {} (attempt {})",
+            command_type.config_key(),
+            context.spec_id,
+            attempt
+        );
+        // Continuing a session swaps in the resume template, if the agent has one
+        let arg_template = match (&context.session_id, &self.resume_template) {
+            (Some(_), Some(resume)) => resume,
+            _ => &self.arg_template,
+        };
+        let mut adapter = output_adapter(self.output_format);
+        if let Some(ref pattern) = self.session_pattern {
+            adapter = Box::new(SessionPatternAdapter::new(adapter, pattern.clone()));
+        }
+
+        let handle = self.process_service.spawn_workflow_with_adapter(
             command_type,
-            spec_id,
-            spec_directory,
-            &self.mcp_command,
-            &self.mcp_args,
+            context,
+            &self.agent_command,
+            arg_template,
+            adapter,
+            attempt,
         )?;
 
         // Parse spec_id into SpecId
+        let spec_id = context.spec_id.as_str();
         let parsed_spec_id = SpecId::parse(spec_id).unwrap_or_else(|_| SpecId::new(0, spec_id));
 
-        let mut command = WorkflowCommand::new(command_type, parsed_spec_id);
+        let max_attempts = self.retry_policy(command_type).max_attempts;
+        let mut command =
+            WorkflowCommand::new(command_type, parsed_spec_id).with_attempt(attempt, max_attempts);
+        command.session_id = context.session_id.clone();
         command.start();
         if let Some(log_path) = handle.log_file() {
             command.log_path = Some(log_path.clone());
@@ -377,10 +1468,655 @@ mod tests {
         let temp = TempDir::new().unwrap();
         let service = ProcessService::new(temp.path().to_path_buf());
 
-        let path = service.log_file_path(WorkflowCommandType::Specify, "001-test");
+        let path = service.log_file_path(WorkflowCommandType::Specify, "001-test", 1);
         assert!(path.to_string_lossy().contains("001-test"));
         assert!(path.to_string_lossy().contains("speckit.specify"));
         assert!(path.extension().unwrap() == "log");
+
+        let retry = service.log_file_path(WorkflowCommandType::Specify, "001-test", 2);
+        assert!(retry.to_string_lossy().ends_with("-2.log"));
+    }
+
+    fn write_log(dir: &Path, name: &str, size: usize, age_days: u64) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, "x".repeat(size)).unwrap();
+        let modified = SystemTime::now() - Duration::from_secs(age_days * SECS_PER_DAY);
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        path
+    }
+
+    fn retention(max_age_days: u64, max_per_spec: usize, compress_after_days: u64) -> LogConfig {
+        LogConfig {
+            max_age_days,
+            max_per_spec,
+            max_total_size_mb: 0,
+            compress_after_days,
+        }
+    }
+
+    #[test]
+    fn test_prune_logs_age_and_count() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let old = write_log(dir, "001-a-speckit.plan-100.log", 10, 40);
+        let a1 = write_log(dir, "001-a-speckit.plan-200.log", 10, 3);
+        let a2 = write_log(dir, "001-a-speckit.plan-300.log", 10, 2);
+        let a3 = write_log(dir, "001-a-speckit.tasks-400.log", 10, 1);
+        let b1 = write_log(dir, "002-b-speckit.specify-500.log", 10, 5);
+        let unrelated = write_log(dir, "notes.txt", 10, 90);
+
+        let service = ProcessService::new(dir.to_path_buf()).with_retention(retention(30, 2, 0));
+        let report = service.prune_logs().unwrap();
+
+        assert_eq!(report.removed, 2);
+        assert!(!old.exists());
+        assert!(!a1.exists());
+        assert!(a2.exists() && a3.exists() && b1.exists());
+        assert!(unrelated.exists());
+    }
+
+    #[test]
+    fn test_prune_logs_total_size() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let oldest = write_log(dir, "001-a-speckit.plan-100.log", 600 * 1024, 3);
+        let newest = write_log(dir, "002-b-speckit.plan-200.log", 600 * 1024, 1);
+
+        let mut config = retention(0, 0, 0);
+        config.max_total_size_mb = 1;
+        let service = ProcessService::new(dir.to_path_buf()).with_retention(config);
+        let report = service.prune_logs().unwrap();
+
+        assert_eq!(report.removed, 1);
+        assert!(!oldest.exists());
+        assert!(newest.exists());
+    }
+
+    #[test]
+    fn test_prune_logs_compresses_old_logs() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let old = write_log(dir, "001-a-speckit.plan-100.log", 4096, 2);
+        let fresh = write_log(dir, "001-a-speckit.plan-200.log", 4096, 0);
+
+        let service = ProcessService::new(dir.to_path_buf()).with_retention(retention(0, 0, 1));
+        let report = service.prune_logs().unwrap();
+
+        assert_eq!(report.compressed, 1);
+        assert!(!old.exists());
+        assert!(dir.join("001-a-speckit.plan-100.log.gz").exists());
+        assert!(fresh.exists());
+
+        // Compressed logs still count towards the per-spec limit
+        let service = ProcessService::new(dir.to_path_buf()).with_retention(retention(0, 1, 0));
+        let report = service.prune_logs().unwrap();
+        assert_eq!(report.removed, 1);
+        assert!(!dir.join("001-a-speckit.plan-100.log.gz").exists());
+    }
+
+    #[test]
+    fn test_spawn_command_reports_exit() {
+        let temp = TempDir::new().unwrap();
+        let service = ProcessService::new(temp.path().to_path_buf());
+
+        let mut handle = service
+            .spawn_command(
+                "sh",
+                &["-c".to_string(), "echo hello; exit 3".to_string()],
+                &temp.path().to_path_buf(),
+            )
+            .unwrap();
+
+        let mut events = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            match handle.try_recv() {
+                Some(ProcessOutput::Exit(code)) => {
+                    events.push(code);
+                    break;
+                }
+                Some(_) => {}
+                None => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+
+        assert_eq!(events, vec![3]);
+        assert!(!handle.is_running());
+    }
+
+    #[test]
+    fn test_spawn_git_syncs_with_bare_remote() {
+        use crate::config::PullMode;
+        use crate::domain::GitOp;
+        use crate::services::GitService;
+
+        let temp = TempDir::new().unwrap();
+        let git = |dir: &Path, args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {:?} failed", args);
+        };
+        let clone = |name: &str| {
+            git(temp.path(), &["clone", "-q", "remote.git", name]);
+            let dir = temp.path().join(name);
+            git(&dir, &["config", "user.email", "test@test.com"]);
+            git(&dir, &["config", "user.name", "Test"]);
+            dir
+        };
+        git(temp.path(), &["init", "-q", "--bare", "remote.git"]);
+        let local = clone("local");
+
+        let runner = WorkflowRunner::new(temp.path().join("logs"), "true".to_string(), Vec::new());
+        let run = |op: GitOp, dir: &PathBuf| {
+            let mut handle = runner.spawn_git(&op.git_args("feature"), dir).unwrap();
+            loop {
+                match wait_for_output(&mut handle) {
+                    Some(ProcessOutput::Exit(code)) => return code,
+                    Some(_) => {}
+                    None => panic!("{} did not finish", op.display_name()),
+                }
+            }
+        };
+
+        // Push a new branch, setting its upstream
+        git(&local, &["checkout", "-q", "-b", "feature"]);
+        git(
+            &local,
+            &["commit", "-q", "--allow-empty", "-m", "Initial commit"],
+        );
+        assert_eq!(run(GitOp::Push { set_upstream: true }, &local), 0);
+        let service = GitService::new(local.clone(), local.join(".worktrees")).unwrap();
+        assert!(service.sync_status("feature").unwrap().is_synced());
+
+        // Fetch and fast-forward a commit pushed from another clone
+        let other = clone("other");
+        git(&other, &["checkout", "-q", "feature"]);
+        git(
+            &other,
+            &["commit", "-q", "--allow-empty", "-m", "Remote change"],
+        );
+        git(&other, &["push", "-q"]);
+        assert_eq!(run(GitOp::Fetch, &local), 0);
+        assert_eq!(service.sync_status("feature").unwrap().behind, 1);
+        assert_eq!(run(GitOp::Pull(PullMode::FfOnly), &local), 0);
+        assert!(service.sync_status("feature").unwrap().is_synced());
+
+        // Diverged branches need a rebase
+        git(
+            &other,
+            &["commit", "-q", "--allow-empty", "-m", "Another change"],
+        );
+        git(&other, &["push", "-q"]);
+        git(
+            &local,
+            &["commit", "-q", "--allow-empty", "-m", "Local change"],
+        );
+        assert_ne!(run(GitOp::Pull(PullMode::FfOnly), &local), 0);
+        assert_eq!(run(GitOp::Pull(PullMode::Rebase), &local), 0);
+        assert_eq!(
+            run(
+                GitOp::Push {
+                    set_upstream: false
+                },
+                &local
+            ),
+            0
+        );
+        assert!(service.sync_status("feature").unwrap().is_synced());
+    }
+
+    #[test]
+    fn test_spawn_workflow_exports_context() {
+        let temp = TempDir::new().unwrap();
+        let spec_dir = temp.path().join("003-foo");
+        fs::create_dir_all(&spec_dir).unwrap();
+        let service = ProcessService::new(temp.path().join("logs"));
+
+        let context = WorkflowContext {
+            spec_id: "003-foo".to_string(),
+            spec_dir: spec_dir.clone(),
+            spec_path: PathBuf::from("."),
+            branch: "003-foo".to_string(),
+            worktree: None,
+            phase: WorkflowPhase::Tasks,
+            env: vec![("AGENT_PROFILE".to_string(), "fast".to_string())],
+            session_id: None,
+            log_file: None,
+        };
+        let script =
+            "echo \"$SPECK_SPEC_ID|$SPECK_BRANCH|$SPECK_PHASE|$SPECK_COMMAND|$AGENT_PROFILE\"; \
+                      test -f \"$SPECK_LOG_FILE\" && echo log-ok";
+        let mut handle = service
+            .spawn_workflow(
+                WorkflowCommandType::Tasks,
+                &context,
+                "sh",
+                &["-c".to_string(), script.to_string()],
+            )
+            .unwrap();
+
+        let mut lines = Vec::new();
+        while let Some(event) = wait_for_output(&mut handle) {
+            match event {
+                ProcessOutput::Stdout(line) => lines.push(line),
+                _ => break,
+            }
+        }
+
+        assert_eq!(lines[0], "003-foo|003-foo|tasks|tasks|fast");
+        assert_eq!(lines[1], "log-ok");
+    }
+
+    #[test]
+    fn test_spawn_workflow_runs_in_worktree() {
+        let temp = TempDir::new().unwrap();
+        let worktree = temp.path().join("wt");
+        fs::create_dir_all(worktree.join("specs/003-foo")).unwrap();
+        let service = ProcessService::new(temp.path().join("logs"));
+
+        let context = WorkflowContext {
+            spec_id: "003-foo".to_string(),
+            spec_dir: temp.path().join("main/specs/003-foo"),
+            spec_path: PathBuf::new(),
+            branch: "003-foo".to_string(),
+            worktree: None,
+            phase: WorkflowPhase::Specify,
+            env: Vec::new(),
+            session_id: None,
+            log_file: None,
+        }
+        .in_worktree(worktree.clone(), PathBuf::from("specs/003-foo"));
+        assert_eq!(context.spec_dir, worktree.join("specs/003-foo"));
+
+        let script = "pwd; echo \"$1\"; test -d \"$1\" && echo spec-ok";
+        let template = ["-c", script, "{tool}", "{spec_dir}"].map(String::from);
+        let mut handle = service
+            .spawn_workflow(WorkflowCommandType::Specify, &context, "sh", &template)
+            .unwrap();
+
+        let mut lines = Vec::new();
+        while let Some(event) = wait_for_output(&mut handle) {
+            match event {
+                ProcessOutput::Stdout(line) => lines.push(line),
+                _ => break,
+            }
+        }
+
+        assert_eq!(
+            fs::canonicalize(&lines[0]).unwrap(),
+            fs::canonicalize(&worktree).unwrap()
+        );
+        assert_eq!(lines[1], "specs/003-foo");
+        assert_eq!(lines[2], "spec-ok");
+    }
+
+    #[test]
+    fn test_hooks_write_to_run_log() {
+        let temp = TempDir::new().unwrap();
+        let spec_dir = temp.path().join("003-foo");
+        fs::create_dir_all(&spec_dir).unwrap();
+        let runner = WorkflowRunner::new(
+            temp.path().join("logs"),
+            "sh".to_string(),
+            ["-c", "echo agent"].map(String::from).to_vec(),
+        );
+
+        let mut context = WorkflowContext {
+            spec_id: "003-foo".to_string(),
+            spec_dir: spec_dir.clone(),
+            spec_path: PathBuf::from("."),
+            branch: "003-foo".to_string(),
+            worktree: None,
+            phase: WorkflowPhase::Specify,
+            env: Vec::new(),
+            session_id: None,
+            log_file: None,
+        };
+        let log = runner
+            .create_run_log(WorkflowCommandType::Specify, &context)
+            .unwrap();
+        context = context.with_log_file(log.clone());
+
+        // A pre-run hook, then the first attempt, share the log
+        let mut hook = runner
+            .spawn_hook("echo hook; echo oops >&2", &spec_dir, Some(&log))
+            .unwrap();
+        assert!(matches!(
+            wait_for_end(&mut hook),
+            Some(ProcessOutput::Exit(0))
+        ));
+        let (command, mut handle) = runner
+            .start_command(WorkflowCommandType::Specify, &context)
+            .unwrap();
+        assert_eq!(command.log_path.as_ref(), Some(&log));
+        assert!(matches!(
+            wait_for_end(&mut handle),
+            Some(ProcessOutput::Exit(0))
+        ));
+
+        let content = fs::read_to_string(&log).unwrap();
+        let body: Vec<&str> = content.lines().skip_while(|l| *l != "---").collect();
+        assert_eq!(
+            body,
+            [
+                "---",
+                "# Hook: echo hook; echo oops >&2",
+                "[OUT] hook",
+                "[ERR] oops",
+                "[OUT] agent"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stdin_forwarding() {
+        let temp = TempDir::new().unwrap();
+        let spec_dir = temp.path().join("003-foo");
+        fs::create_dir_all(&spec_dir).unwrap();
+        let service = ProcessService::new(temp.path().join("logs"));
+
+        let context = WorkflowContext {
+            spec_id: "003-foo".to_string(),
+            spec_dir,
+            spec_path: PathBuf::from("."),
+            branch: "003-foo".to_string(),
+            worktree: None,
+            phase: WorkflowPhase::Implement,
+            env: Vec::new(),
+            session_id: None,
+            log_file: None,
+        };
+        let script = "read answer; echo \"got $answer\"; cat; echo done";
+        let template = ["-c", script].map(String::from);
+        let mut handle = service
+            .spawn_workflow(WorkflowCommandType::Implement, &context, "sh", &template)
+            .unwrap();
+        assert!(handle.has_stdin());
+
+        handle.write_stdin("y").unwrap();
+        assert!(matches!(
+            wait_for_output(&mut handle),
+            Some(ProcessOutput::Stdout(line)) if line == "got y"
+        ));
+
+        // `cat` only finishes once stdin is closed
+        handle.close_stdin();
+        assert!(!handle.has_stdin());
+        assert!(handle.write_stdin("late").is_err());
+        assert!(matches!(
+            wait_for_output(&mut handle),
+            Some(ProcessOutput::Stdout(line)) if line == "done"
+        ));
+        assert!(matches!(
+            wait_for_end(&mut handle),
+            Some(ProcessOutput::Exit(0))
+        ));
+
+        let log = fs::read_to_string(handle.log_file().unwrap()).unwrap();
+        assert!(log.contains("[IN] y\n"));
+        assert!(log.contains("[IN] <EOF>"));
+    }
+
+    #[test]
+    fn test_render_args_placeholders() {
+        let context = WorkflowContext {
+            spec_id: "003-foo".to_string(),
+            spec_dir: PathBuf::new(),
+            spec_path: PathBuf::new(),
+            branch: "003-foo".to_string(),
+            worktree: None,
+            phase: WorkflowPhase::Clarify,
+            env: Vec::new(),
+            session_id: None,
+            log_file: None,
+        }
+        .with_session(Some("abc-123".to_string()))
+        .in_worktree(
+            PathBuf::from("/repo/.worktrees/003-foo"),
+            PathBuf::from("specs/003-foo"),
+        );
+
+        let template = [
+            "exec",
+            "/{tool} {spec_dir}",
+            "--id={spec_id}",
+            "--cwd={worktree}",
+            "{phase}",
+            "--resume={session_id}",
+            "{unknown}",
+        ]
+        .map(String::from);
+        let args = context.render_args(&template, WorkflowCommandType::Plan);
+
+        assert_eq!(
+            args,
+            vec![
+                "exec",
+                "/speckit.plan specs/003-foo",
+                "--id=003-foo",
+                "--cwd=/repo/.worktrees/003-foo",
+                "clarify",
+                "--resume=abc-123",
+                "{unknown}",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_session_pattern_adapter() {
+        let pattern = Regex::new(r"session id: (\S+)").unwrap();
+        let mut adapter = SessionPatternAdapter::new(Box::new(PassThroughAdapter), pattern);
+
+        let outputs = adapter.adapt("session id: s-42".to_string());
+        assert!(matches!(&outputs[0], ProcessOutput::Stdout(text) if text == "session id: s-42"));
+        assert!(
+            matches!(&outputs[1], ProcessOutput::Event(AgentEvent::Session(id)) if id == "s-42")
+        );
+
+        // The same session is only reported once
+        assert_eq!(adapter.adapt("session id: s-42".to_string()).len(), 1);
+    }
+
+    #[test]
+    fn test_stream_json_adapter() {
+        let mut adapter = StreamJsonAdapter::default();
+        let mut adapt = |line: &str| -> Vec<AgentEvent> {
+            adapter
+                .adapt(line.to_string())
+                .into_iter()
+                .map(|output| match output {
+                    ProcessOutput::Event(event) => event,
+                    ProcessOutput::Stdout(text) => AgentEvent::Text(format!("raw: {}", text)),
+                    other => panic!("unexpected output: {:?}", other),
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            adapt(r#"{"type":"system","subtype":"init","session_id":"abc-123"}"#),
+            vec![AgentEvent::Session("abc-123".to_string())]
+        );
+        assert_eq!(
+            adapt(
+                r#"{"type":"assistant","message":{"model":"claude-sonnet","content":[
+                    {"type":"text","text":"Reading the spec"},
+                    {"type":"tool_use","name":"Read","input":{"file_path":"spec.md"}}]}}"#
+                    .replace('\n', "")
+                    .as_str()
+            ),
+            vec![
+                AgentEvent::Text("Reading the spec".to_string()),
+                AgentEvent::ToolCall {
+                    name: "Read".to_string(),
+                    input: r#"{"file_path":"spec.md"}"#.to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            adapt(
+                r##"{"type":"user","message":{"content":[{"type":"tool_result","content":"# Spec","is_error":false}]}}"##
+            ),
+            vec![AgentEvent::ToolResult {
+                output: "# Spec".to_string(),
+                is_error: false,
+            }]
+        );
+        assert_eq!(
+            adapt(
+                r#"{"type":"result","session_id":"abc-123","usage":{"input_tokens":1200,"output_tokens":340}}"#
+            ),
+            vec![AgentEvent::Usage(TokenUsage {
+                model: Some("claude-sonnet".to_string()),
+                input_tokens: 1200,
+                output_tokens: 340,
+            })]
+        );
+        assert_eq!(
+            adapt("plain text"),
+            vec![AgentEvent::Text("raw: plain text".to_string())]
+        );
+    }
+
+    /// Wait for the next event from the process
+    fn wait_for_output(handle: &mut ProcessHandle) -> Option<ProcessOutput> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if let Some(event) = handle.try_recv() {
+                return Some(event);
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        None
+    }
+
+    /// Collect output until the process reports how it ended
+    fn wait_for_end(handle: &mut ProcessHandle) -> Option<ProcessOutput> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            match handle.try_recv() {
+                Some(
+                    event @ (ProcessOutput::Exit(_)
+                    | ProcessOutput::Cancelled(_)
+                    | ProcessOutput::Terminated),
+                ) => return Some(event),
+                Some(_) => {}
+                None => std::thread::sleep(Duration::from_millis(10)),
+            }
+        }
+        None
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cancel_escalates_and_kills_process_group() {
+        let temp = TempDir::new().unwrap();
+        let service = ProcessService::new(temp.path().to_path_buf());
+        let pid_file = temp.path().join("grandchild.pid");
+
+        // The shell ignores SIGINT and SIGTERM and leaves a grandchild behind
+        let script = format!(
+            "trap '' INT TERM; sleep 30 & echo $! > {}; echo ready; wait",
+            pid_file.display()
+        );
+        let mut handle = service
+            .spawn_command(
+                "sh",
+                &["-c".to_string(), script],
+                &temp.path().to_path_buf(),
+            )
+            .unwrap();
+
+        while !matches!(handle.try_recv(), Some(ProcessOutput::Stdout(_))) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let grandchild: u32 = fs::read_to_string(&pid_file)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+
+        handle.cancel(CancelPolicy {
+            interrupt_grace: Duration::from_millis(100),
+            terminate_grace: Duration::from_millis(100),
+        });
+        assert!(handle.is_cancelling());
+
+        let event = wait_for_end(&mut handle);
+        assert!(matches!(
+            event,
+            Some(ProcessOutput::Cancelled(CancelOutcome::Killed))
+        ));
+        // The grandchild may linger as a zombie until init reaps it
+        let stat = fs::read_to_string(format!("/proc/{}/stat", grandchild)).unwrap_or_default();
+        assert!(stat.is_empty() || stat.contains(") Z "));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cancel_stops_at_sigint() {
+        let temp = TempDir::new().unwrap();
+        let service = ProcessService::new(temp.path().to_path_buf());
+
+        let mut handle = service
+            .spawn_command(
+                "sh",
+                &["-c".to_string(), "echo ready; sleep 30".to_string()],
+                &temp.path().to_path_buf(),
+            )
+            .unwrap();
+        while !matches!(handle.try_recv(), Some(ProcessOutput::Stdout(_))) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        handle.cancel(CancelPolicy::default());
+
+        let event = wait_for_end(&mut handle);
+        assert!(matches!(
+            event,
+            Some(ProcessOutput::Cancelled(CancelOutcome::Interrupted))
+        ));
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy::from_config(&RetryConfig {
+            max_attempts: 3,
+            backoff_ms: 1000,
+            max_backoff_ms: 3000,
+            exit_codes: vec![75],
+            ..RetryConfig::default()
+        });
+
+        assert!(policy.allows_attempt_after(2));
+        assert!(!policy.allows_attempt_after(3));
+        assert!(policy.retries_exit_code(75));
+        assert!(!policy.retries_exit_code(1));
+        assert!(!RetryPolicy::default().retries_exit_code(0));
+        assert!(RetryPolicy::default().retries_exit_code(1));
+
+        assert_eq!(policy.delay_before(2), Duration::from_secs(1));
+        assert_eq!(policy.delay_before(3), Duration::from_secs(2));
+        assert_eq!(policy.delay_before(4), Duration::from_secs(3));
+
+        // The defaults cover the failures starting an agent produces
+        assert!(policy.retries_error(&AppError::Process("not found".to_string())));
+        assert!(policy.retries_error(&AppError::io("log dir is read-only")));
+        assert!(!policy.retries_error(&AppError::Mcp(crate::error::McpError::Timeout)));
+
+        let timeouts = RetryPolicy::from_config(&RetryConfig {
+            errors: vec![McpErrorKind::Timeout],
+            ..RetryConfig::default()
+        });
+        assert!(timeouts.retries_error(&AppError::Mcp(crate::error::McpError::Timeout)));
+        assert!(!timeouts.retries_error(&AppError::Process("not found".to_string())));
     }
 
     #[test]
@@ -0,0 +1,258 @@
+//! Background worker computing worktree status off the UI thread.
+//!
+//! Refresh requests are debounced and coalesced, then run as jobs on a
+//! bounded number of threads. Results stream back over a channel and are
+//! picked up by the app on its next tick.
+
+use crate::domain::{Worktree, WorktreeStatus, WorktreeSyncStatus};
+use crate::services::GitService;
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Spinner frames shown next to rows whose status is being refreshed
+const SPINNER_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// A result computed by the worker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatusUpdate {
+    /// Working tree status of the worktree at a path
+    Status {
+        path: PathBuf,
+        status: Option<WorktreeStatus>,
+    },
+    /// Ahead/behind counts of a branch against its remote
+    Sync {
+        branch: String,
+        sync: Option<WorktreeSyncStatus>,
+    },
+}
+
+/// One unit of work
+#[derive(Debug, Clone)]
+enum StatusJob {
+    Status(PathBuf),
+    Sync(String),
+}
+
+impl StatusJob {
+    fn run(self, git: &GitService) -> StatusUpdate {
+        match self {
+            Self::Status(path) => {
+                let status = git.worktree_status(&path).ok();
+                StatusUpdate::Status { path, status }
+            }
+            Self::Sync(branch) => {
+                let sync = git.sync_status(&branch).ok();
+                StatusUpdate::Sync { branch, sync }
+            }
+        }
+    }
+}
+
+/// A refresh waiting for its debounce delay to pass
+#[derive(Debug)]
+struct QueuedRefresh {
+    worktrees: Vec<Worktree>,
+    include_sync: bool,
+    due: Instant,
+}
+
+/// Computes worktree and sync status on background threads
+pub struct StatusWorker {
+    git: Arc<GitService>,
+    max_jobs: usize,
+    debounce: Duration,
+    queued: Option<QueuedRefresh>,
+    /// Worktrees whose status is being computed
+    refreshing_paths: HashSet<PathBuf>,
+    /// Branches whose sync status is being computed
+    refreshing_branches: HashSet<String>,
+    started_at: Instant,
+    tx: mpsc::UnboundedSender<StatusUpdate>,
+    rx: mpsc::UnboundedReceiver<StatusUpdate>,
+}
+
+impl StatusWorker {
+    /// Create a worker running at most `max_jobs` jobs at once, starting
+    /// refreshes `debounce` after the first request
+    pub fn new(git: Arc<GitService>, max_jobs: usize, debounce: Duration) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            git,
+            max_jobs: max_jobs.max(1),
+            debounce,
+            queued: None,
+            refreshing_paths: HashSet::new(),
+            refreshing_branches: HashSet::new(),
+            started_at: Instant::now(),
+            tx,
+            rx,
+        }
+    }
+
+    /// Request a refresh of the status (and optionally the sync status) of
+    /// the given worktrees. Requests made before the refresh starts are
+    /// merged into it, so worktrees can be refreshed one at a time.
+    pub fn request(&mut self, worktrees: &[Worktree], include_sync: bool) {
+        match self.queued {
+            Some(ref mut queued) => {
+                for wt in worktrees {
+                    if !queued.worktrees.iter().any(|w| w.path == wt.path) {
+                        queued.worktrees.push(wt.clone());
+                    }
+                }
+                queued.include_sync |= include_sync;
+            }
+            None => {
+                self.queued = Some(QueuedRefresh {
+                    worktrees: worktrees.to_vec(),
+                    include_sync,
+                    due: Instant::now() + self.debounce,
+                });
+            }
+        }
+    }
+
+    /// Start a queued refresh once its delay has passed and the previous
+    /// refresh has finished, and collect the results that arrived since the
+    /// last call
+    pub fn poll(&mut self) -> Vec<StatusUpdate> {
+        let mut updates = Vec::new();
+        while let Ok(update) = self.rx.try_recv() {
+            match &update {
+                StatusUpdate::Status { path, .. } => {
+                    self.refreshing_paths.remove(path);
+                }
+                StatusUpdate::Sync { branch, .. } => {
+                    self.refreshing_branches.remove(branch);
+                }
+            }
+            updates.push(update);
+        }
+
+        let due = self
+            .queued
+            .as_ref()
+            .is_some_and(|queued| Instant::now() >= queued.due);
+        if due && !self.is_busy() {
+            if let Some(queued) = self.queued.take() {
+                self.start(queued);
+            }
+        }
+
+        updates
+    }
+
+    /// Check if a refresh is running
+    pub fn is_busy(&self) -> bool {
+        !self.refreshing_paths.is_empty() || !self.refreshing_branches.is_empty()
+    }
+
+    /// Check if a refresh is running or waiting to start
+    pub fn is_pending(&self) -> bool {
+        self.is_busy() || self.queued.is_some()
+    }
+
+    /// Worktrees whose status is being refreshed
+    pub fn refreshing_paths(&self) -> &HashSet<PathBuf> {
+        &self.refreshing_paths
+    }
+
+    /// Branches whose sync status is being refreshed
+    pub fn refreshing_branches(&self) -> &HashSet<String> {
+        &self.refreshing_branches
+    }
+
+    /// Current spinner frame
+    pub fn spinner(&self) -> &'static str {
+        let tick = self.started_at.elapsed().as_millis() / 100;
+        SPINNER_FRAMES[tick as usize % SPINNER_FRAMES.len()]
+    }
+
+    /// Run the jobs of a refresh on up to `max_jobs` threads
+    fn start(&mut self, refresh: QueuedRefresh) {
+        let mut jobs = VecDeque::new();
+        for wt in &refresh.worktrees {
+            if self.refreshing_paths.insert(wt.path.clone()) {
+                jobs.push_back(StatusJob::Status(wt.path.clone()));
+            }
+            if refresh.include_sync && self.refreshing_branches.insert(wt.branch.clone()) {
+                jobs.push_back(StatusJob::Sync(wt.branch.clone()));
+            }
+        }
+
+        let threads = self.max_jobs.min(jobs.len());
+        let jobs = Arc::new(Mutex::new(jobs));
+        for _ in 0..threads {
+            let jobs = Arc::clone(&jobs);
+            let git = Arc::clone(&self.git);
+            let tx = self.tx.clone();
+            std::thread::spawn(move || loop {
+                let job = jobs.lock().ok().and_then(|mut jobs| jobs.pop_front());
+                let Some(job) = job else {
+                    break;
+                };
+                if tx.send(job.run(&git)).is_err() {
+                    break;
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_worker_streams_statuses() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().to_path_buf();
+        for args in [
+            &["init", "-q"][..],
+            &["config", "user.email", "test@test.com"],
+            &["config", "user.name", "Test"],
+            &["commit", "-q", "--allow-empty", "-m", "Initial commit"],
+        ] {
+            std::process::Command::new("git")
+                .args(args)
+                .current_dir(&path)
+                .output()
+                .unwrap();
+        }
+        std::fs::write(path.join("notes.txt"), "scratch").unwrap();
+
+        let git = Arc::new(GitService::new(path.clone(), path.join(".worktrees")).unwrap());
+        let worktrees = git.list_worktrees().unwrap();
+        let mut worker = StatusWorker::new(git, 2, Duration::ZERO);
+
+        // Repeated requests coalesce into one refresh
+        worker.request(&worktrees, false);
+        worker.request(&worktrees, true);
+        assert!(worker.poll().is_empty());
+        assert!(worker.is_busy());
+        assert_eq!(worker.refreshing_branches().len(), 1);
+
+        let mut updates = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while worker.is_busy() && Instant::now() < deadline {
+            updates.extend(worker.poll());
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(updates.len(), 2);
+        assert!(updates.contains(&StatusUpdate::Status {
+            path: worktrees[0].path.clone(),
+            status: Some(WorktreeStatus::Dirty {
+                modified: 0,
+                staged: 0,
+                untracked: 1
+            }),
+        }));
+        assert!(!worker.is_pending());
+    }
+}
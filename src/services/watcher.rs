@@ -0,0 +1,307 @@
+//! Filesystem watcher for the specs directory and worktrees.
+//!
+//! Raw notify events are classified by the area they touch and coalesced
+//! until no new event has arrived for the debounce delay, so a burst of
+//! writes (an agent saving several artifacts, a checkout) results in a single
+//! refresh. A steady stream of events is flushed at least every
+//! [`MAX_DELAY_FACTOR`] debounce periods.
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Changes are applied after at most this many debounce periods, even if
+/// events keep arriving
+const MAX_DELAY_FACTOR: u32 = 10;
+
+/// Changes collected from a burst of filesystem events
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WatchChanges {
+    /// Names of spec directories whose contents changed
+    pub spec_dirs: BTreeSet<String>,
+    /// Spec directories were created or removed
+    pub specs_changed: bool,
+    /// Worktree roots with changed files
+    pub worktrees: BTreeSet<PathBuf>,
+    /// Worktrees were added or removed (an entry of `.git/worktrees` was
+    /// created or removed)
+    pub worktree_list_changed: bool,
+}
+
+impl WatchChanges {
+    /// Check if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.spec_dirs.is_empty()
+            && !self.specs_changed
+            && self.worktrees.is_empty()
+            && !self.worktree_list_changed
+    }
+}
+
+/// Paths the watcher classifies events against
+#[derive(Debug, Clone)]
+struct WatchedAreas {
+    specs_dir: PathBuf,
+    worktrees_dir: PathBuf,
+    worktrees: Vec<PathBuf>,
+}
+
+impl WatchedAreas {
+    /// Record the areas touched by a changed path
+    fn classify(&self, path: &Path, changes: &mut WatchChanges) {
+        if let Ok(rest) = path.strip_prefix(&self.specs_dir) {
+            let mut components = rest.components();
+            match (components.next(), components.next()) {
+                (Some(Component::Normal(name)), Some(_)) => {
+                    changes
+                        .spec_dirs
+                        .insert(name.to_string_lossy().into_owned());
+                }
+                // A spec directory itself was created, removed or renamed
+                _ => changes.specs_changed = true,
+            }
+        }
+
+        // Object writes, lock files and speck's own state don't change any
+        // status
+        if is_ignored(path) {
+            return;
+        }
+
+        if let Ok(rest) = path.strip_prefix(&self.worktrees_dir) {
+            let mut components = rest.components();
+            match (components.next(), components.next()) {
+                // A linked worktree's git dir (its index, HEAD, ...) changed
+                (Some(Component::Normal(name)), Some(_)) => {
+                    match self
+                        .worktrees
+                        .iter()
+                        .find(|root| root.file_name() == Some(name))
+                    {
+                        Some(root) => {
+                            changes.worktrees.insert(root.clone());
+                        }
+                        // Not listed yet, so it is being added
+                        None => changes.worktree_list_changed = true,
+                    }
+                }
+                // An entry was created or removed
+                _ => changes.worktree_list_changed = true,
+            }
+            return;
+        }
+
+        // Nested worktrees belong to the innermost root
+        if let Some(root) = self
+            .worktrees
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+        {
+            changes.worktrees.insert(root.clone());
+        }
+    }
+
+    /// Directories to watch recursively, leaving out those already covered
+    /// by another watched directory
+    fn roots(&self) -> Vec<PathBuf> {
+        let mut candidates: Vec<PathBuf> = self.worktrees.clone();
+        candidates.push(self.specs_dir.clone());
+        candidates.push(self.worktrees_dir.clone());
+        candidates.retain(|path| path.exists());
+        candidates.sort();
+        candidates.dedup();
+
+        let mut roots: Vec<PathBuf> = Vec::new();
+        for path in candidates {
+            if !roots.iter().any(|root| path.starts_with(root)) {
+                roots.push(path);
+            }
+        }
+        roots
+    }
+}
+
+/// Check if a path is inside a git object store, a reflog or `.speck`, or
+/// is a git lock file
+fn is_ignored(path: &Path) -> bool {
+    let mut in_git_dir = false;
+    for component in path.components() {
+        match component.as_os_str().to_str() {
+            Some(".speck") => return true,
+            Some(".git") => in_git_dir = true,
+            Some("objects") | Some("logs") if in_git_dir => return true,
+            _ => {}
+        }
+    }
+    // Git writes `index.lock`, `HEAD.lock`, ... before renaming them into
+    // place, which is reported separately
+    in_git_dir && path.extension().is_some_and(|ext| ext == "lock")
+}
+
+/// Watches the specs directory, every worktree and `.git/worktrees`
+pub struct FsWatcher {
+    watcher: RecommendedWatcher,
+    areas: WatchedAreas,
+    roots: Vec<PathBuf>,
+    debounce: Duration,
+    pending: WatchChanges,
+    first_event: Option<Instant>,
+    last_event: Option<Instant>,
+    rx: mpsc::UnboundedReceiver<PathBuf>,
+}
+
+impl FsWatcher {
+    /// Start watching `specs_dir` and the worktree registry at
+    /// `worktrees_dir` (usually `.git/worktrees`)
+    pub fn new(
+        specs_dir: PathBuf,
+        worktrees_dir: PathBuf,
+        debounce: Duration,
+    ) -> notify::Result<Self> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let watcher =
+            notify::recommended_watcher(
+                move |result: notify::Result<notify::Event>| match result {
+                    Ok(event) => {
+                        for path in event.paths {
+                            let _ = tx.send(path);
+                        }
+                    }
+                    Err(e) => tracing::warn!("Filesystem watch error: {}", e),
+                },
+            )?;
+
+        // Events carry resolved paths, like the worktree paths git reports
+        let resolve = |path: PathBuf| path.canonicalize().unwrap_or(path);
+        let mut fs_watcher = Self {
+            watcher,
+            areas: WatchedAreas {
+                specs_dir: resolve(specs_dir),
+                worktrees_dir: resolve(worktrees_dir),
+                worktrees: Vec::new(),
+            },
+            roots: Vec::new(),
+            debounce,
+            pending: WatchChanges::default(),
+            first_event: None,
+            last_event: None,
+            rx,
+        };
+        fs_watcher.update_roots();
+        Ok(fs_watcher)
+    }
+
+    /// Watch a new set of worktrees
+    pub fn set_worktrees(&mut self, worktrees: Vec<PathBuf>) {
+        if worktrees == self.areas.worktrees {
+            return;
+        }
+        self.areas.worktrees = worktrees;
+        self.update_roots();
+    }
+
+    /// Collect events that arrived since the last call, returning the
+    /// changes once events have stopped arriving for the debounce delay
+    pub fn poll(&mut self) -> Option<WatchChanges> {
+        while let Ok(path) = self.rx.try_recv() {
+            self.areas.classify(&path, &mut self.pending);
+            let now = Instant::now();
+            self.first_event.get_or_insert(now);
+            self.last_event = Some(now);
+        }
+
+        let quiet = self
+            .last_event
+            .is_some_and(|at| at.elapsed() >= self.debounce);
+        let overdue = self
+            .first_event
+            .is_some_and(|at| at.elapsed() >= self.debounce * MAX_DELAY_FACTOR);
+        if !quiet && !overdue {
+            return None;
+        }
+
+        self.first_event = None;
+        self.last_event = None;
+        let changes = std::mem::take(&mut self.pending);
+        (!changes.is_empty()).then_some(changes)
+    }
+
+    /// Watch the current roots, unwatching those no longer needed
+    fn update_roots(&mut self) {
+        let roots = self.areas.roots();
+        for old in self.roots.iter().filter(|root| !roots.contains(root)) {
+            if let Err(e) = self.watcher.unwatch(old) {
+                tracing::debug!("Failed to unwatch {}: {}", old.display(), e);
+            }
+        }
+        for new in roots.iter().filter(|root| !self.roots.contains(root)) {
+            if let Err(e) = self.watcher.watch(new, RecursiveMode::Recursive) {
+                tracing::warn!("Failed to watch {}: {}", new.display(), e);
+            }
+        }
+        self.roots = roots;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_changes() {
+        let areas = WatchedAreas {
+            specs_dir: PathBuf::from("/repo/specs"),
+            worktrees_dir: PathBuf::from("/repo/.git/worktrees"),
+            worktrees: vec![
+                PathBuf::from("/repo"),
+                PathBuf::from("/repo/.worktrees/003-foo"),
+            ],
+        };
+
+        let mut changes = WatchChanges::default();
+        areas.classify(Path::new("/repo/specs/003-foo/plan.md"), &mut changes);
+        areas.classify(
+            Path::new("/repo/.worktrees/003-foo/src/main.rs"),
+            &mut changes,
+        );
+        areas.classify(Path::new("/repo/.git/objects/ab/cdef"), &mut changes);
+        areas.classify(Path::new("/repo/.speck/logs/plan.log"), &mut changes);
+        assert_eq!(changes.spec_dirs, BTreeSet::from(["003-foo".to_string()]));
+        assert!(!changes.specs_changed);
+        assert_eq!(
+            changes.worktrees,
+            BTreeSet::from([
+                PathBuf::from("/repo"),
+                PathBuf::from("/repo/.worktrees/003-foo")
+            ])
+        );
+
+        let mut changes = WatchChanges::default();
+        areas.classify(Path::new("/repo/specs/004-bar"), &mut changes);
+        areas.classify(Path::new("/repo/.git/worktrees/004-bar"), &mut changes);
+        assert!(changes.specs_changed);
+        assert!(changes.worktree_list_changed);
+        assert_eq!(changes.worktrees, BTreeSet::from([PathBuf::from("/repo")]));
+
+        // Git dir changes of a listed worktree only refresh that worktree
+        let mut changes = WatchChanges::default();
+        areas.classify(
+            Path::new("/repo/.git/worktrees/003-foo/index.lock"),
+            &mut changes,
+        );
+        areas.classify(Path::new("/repo/.git/index.lock"), &mut changes);
+        assert!(changes.is_empty());
+        areas.classify(
+            Path::new("/repo/.git/worktrees/003-foo/index"),
+            &mut changes,
+        );
+        assert!(!changes.worktree_list_changed);
+        assert_eq!(
+            changes.worktrees,
+            BTreeSet::from([PathBuf::from("/repo/.worktrees/003-foo")])
+        );
+    }
+}
@@ -40,6 +40,9 @@ pub enum Action {
     NewSpec,
     DeleteWorktree,
     CancelCommand,
+    UsageSummary,
+    ToggleMark,
+    MarkPhase,
 
     // Misc
     Help,
@@ -125,8 +128,11 @@ impl InputHandler {
             KeyCode::Char('n') => Some(Action::NewSpec),
             KeyCode::Char('d') => Some(Action::DeleteWorktree),
             KeyCode::Char('c') => Some(Action::CancelCommand),
+            KeyCode::Char('m') => Some(Action::ToggleMark),
+            KeyCode::Char('M') => Some(Action::MarkPhase),
 
             // Misc
+            KeyCode::Char('u') => Some(Action::UsageSummary),
             KeyCode::Char('?') => Some(Action::Help),
             KeyCode::F(5) => Some(Action::Refresh),
 
@@ -213,6 +219,12 @@ mod tests {
             handler.handle_key(key_r, InputMode::Normal),
             Some(Action::RunWorkflow)
         );
+
+        let key_m = KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE);
+        assert_eq!(
+            handler.handle_key(key_m, InputMode::Normal),
+            Some(Action::ToggleMark)
+        );
     }
 
     #[test]
@@ -1,11 +1,16 @@
 //! Main layout rendering for the TUI.
 
 use crate::app::{App, AppView, DocType};
+use crate::domain::{Batch, DiffScope, Pipeline, StepState, WorktreeStatus};
+use crate::ui::widgets::commit::{CommitFocus, CommitViewWidget};
+use crate::ui::widgets::commit_log::CommitLogWidget;
+use crate::ui::widgets::diff_view::DiffViewWidget;
 use crate::ui::widgets::help::HelpWidget;
 use crate::ui::widgets::output_panel::OutputPanelWidget;
 use crate::ui::widgets::spec_detail::SpecDetailWidget;
 use crate::ui::widgets::spec_list::SpecListWidget;
-use crate::ui::widgets::text_input::NewSpecDialog;
+use crate::ui::widgets::text_input::{NewSpecDialog, TextInputWidget};
+use crate::ui::widgets::usage_summary::UsageSummaryWidget;
 use crate::ui::widgets::worktree_list::{ConfirmDialog, WorktreeListWidget};
 use ratatui::{
     prelude::*,
@@ -20,11 +25,33 @@ pub fn draw(frame: &mut Frame, app: &App) {
         AppView::Overview => draw_overview(frame, app, area),
         AppView::SpecDetail(id) => draw_spec_detail(frame, app, area, id),
         AppView::WorktreeManagement => draw_worktree_management(frame, app, area),
+        AppView::WorktreeDiff => draw_worktree_diff(frame, app, area),
+        AppView::CommitView => draw_commit_view(frame, app, area),
+        AppView::CommitDiff => draw_commit_diff(frame, app, area),
         AppView::DocumentView(doc_type) => draw_document_view(frame, app, area, doc_type),
-        AppView::DocumentEdit(doc_type) => draw_document_edit(frame, app, area, doc_type),
+        AppView::DocumentEdit(doc_type) => {
+            let header = match app.selected_spec() {
+                Some(spec) => format!(
+                    "Edit: {} - {}",
+                    doc_type_filename(doc_type),
+                    spec.id.as_str()
+                ),
+                None => format!("Edit: {}", doc_type_filename(doc_type)),
+            };
+            draw_document_edit(frame, app, area, &header)
+        }
+        AppView::FileEdit => {
+            let header = match app.editor_state.file_path() {
+                Some(path) => format!("Edit: {}", path.display()),
+                None => "Edit".to_string(),
+            };
+            draw_document_edit(frame, app, area, &header)
+        }
+        AppView::ArtifactReview => draw_artifact_review(frame, app, area),
         AppView::CommandOutput => draw_command_output(frame, app, area),
         AppView::WorkflowMenu => draw_workflow_menu(frame, app, area),
         AppView::NewSpec => draw_new_spec(frame, app, area),
+        AppView::UsageSummary => draw_usage_summary(frame, app, area),
         AppView::Help => draw_help(frame, app, area),
     }
 
@@ -104,32 +131,126 @@ fn draw_overview(frame: &mut Frame, app: &App, area: Rect) {
                 .alignment(Alignment::Center);
         frame.render_widget(empty_msg, chunks[1]);
     } else {
-        let spec_list = SpecListWidget::new(
+        let mut spec_list = SpecListWidget::new(
             &app.specs,
             &app.worktrees,
             &app.worktree_statuses,
             app.selected_spec_index,
-        );
+        )
+        .marked(&app.marked_specs);
+        if let Some(worker) = app.status_worker() {
+            spec_list = spec_list.refreshing(worker.refreshing_paths(), worker.spinner());
+        }
         frame.render_widget(spec_list, chunks[1]);
     }
 
     // Footer with keybindings
-    let footer_text = " j/k: Navigate | Enter: Select | w: Switch worktree | r: Run | n: New | q: Quit | ?: Help ";
+    let footer_text = if app.marked_specs.is_empty() {
+        " j/k: Navigate | Enter: Select | w: Switch worktree | r: Run | m: Mark | n: New | u: Usage | q: Quit | ?: Help ".to_string()
+    } else {
+        format!(
+            " j/k: Navigate | m: Mark | M: Mark phase | r: Run for {} marked | Esc: Clear marks ",
+            app.marked_specs.len()
+        )
+    };
     let footer = Paragraph::new(footer_text)
         .style(Style::default().fg(Color::DarkGray))
         .block(Block::default().borders(Borders::TOP));
     frame.render_widget(footer, chunks[2]);
 }
 
-/// Draw spec detail view (placeholder)
-fn draw_spec_detail(frame: &mut Frame, _app: &App, area: Rect, spec_id: &str) {
-    let content = Paragraph::new(format!("Spec Detail: {}\n\n(Not yet implemented)", spec_id))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Specification Details"),
+/// Draw spec detail view with the commits of the spec branch
+fn draw_spec_detail(frame: &mut Frame, app: &App, area: Rect, spec_id: &str) {
+    // Create layout: header, commit log, footer
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Commit log
+            Constraint::Length(3), // Footer
+        ])
+        .split(area);
+
+    let main_branch = &app.project.config.git.main_branch;
+    let branch = app
+        .specs
+        .iter()
+        .find(|s| s.id.as_str() == spec_id)
+        .map(|s| s.branch.as_str())
+        .unwrap_or(spec_id);
+    let header = Paragraph::new(format!("Spec: {} - {}", spec_id, branch))
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+    frame.render_widget(header, chunks[0]);
+
+    if app.commit_log.commits.is_empty() {
+        let empty = Paragraph::new(format!("No commits on {} beyond {}", branch, main_branch))
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[1]);
+    } else {
+        let title = format!(
+            " Commits not on {} ({}) ",
+            main_branch,
+            app.commit_log.commits.len()
+        );
+        frame.render_widget(
+            CommitLogWidget::new(&app.commit_log).title(&title),
+            chunks[1],
         );
-    frame.render_widget(content, area);
+    }
+
+    let footer_text =
+        " j/k: Select commit | Enter: View diff | r: Refresh | w: Switch worktree | Esc: Back ";
+    let footer = Paragraph::new(footer_text)
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().borders(Borders::TOP));
+    frame.render_widget(footer, chunks[2]);
+}
+
+/// Draw the diff of a commit opened from a spec's commit log
+fn draw_commit_diff(frame: &mut Frame, app: &App, area: Rect) {
+    // Create layout: header, diff view, footer
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Diff view
+            Constraint::Length(3), // Footer
+        ])
+        .split(area);
+
+    let header_text = match app.commit_log.selected_commit() {
+        Some(commit) => format!("Commit {}: {}", commit.short_id(), commit.subject),
+        None => "Commit".to_string(),
+    };
+    let header = Paragraph::new(header_text)
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+    frame.render_widget(header, chunks[0]);
+
+    if app.commit_diff.is_empty() {
+        let empty = Paragraph::new("No file changes")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[1]);
+    } else {
+        frame.render_widget(DiffViewWidget::new(&app.commit_diff), chunks[1]);
+    }
+
+    let footer_text = " j/k: Select file | n/N: Next/Prev hunk | PgUp/PgDn: Scroll | Esc: Back ";
+    let footer = Paragraph::new(footer_text)
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().borders(Borders::TOP));
+    frame.render_widget(footer, chunks[2]);
 }
 
 /// Draw worktree management view
@@ -162,18 +283,25 @@ fn draw_worktree_management(frame: &mut Frame, app: &App, area: Rect) {
             .alignment(Alignment::Center);
         frame.render_widget(empty_msg, chunks[1]);
     } else {
-        let worktree_widget = WorktreeListWidget::new(
+        let mut worktree_widget = WorktreeListWidget::new(
             &app.worktrees,
             &app.worktree_statuses,
             app.worktree_management_state.selected_index,
         )
         .sync_statuses(&app.worktree_sync_statuses);
+        if let Some(worker) = app.status_worker() {
+            worktree_widget = worktree_widget.refreshing(
+                worker.refreshing_paths(),
+                worker.refreshing_branches(),
+                worker.spinner(),
+            );
+        }
         frame.render_widget(worktree_widget, chunks[1]);
     }
 
     // Footer with keybindings
     let footer_text =
-        " j/k: Navigate | Enter: Switch | d: Delete | D: Force Delete | r: Refresh | q: Back ";
+        " j/k: Navigate | Enter: Switch | f/p/P: Fetch/Pull/Push | v: Diff | c: Commit | u: Update from main | C/A: Continue/Abort | d/D: Delete | x: Clean up | r: Refresh | q: Back ";
     let footer = Paragraph::new(footer_text)
         .style(Style::default().fg(Color::DarkGray))
         .block(Block::default().borders(Borders::TOP));
@@ -181,18 +309,66 @@ fn draw_worktree_management(frame: &mut Frame, app: &App, area: Rect) {
 
     // Draw confirmation dialog if showing
     if app.worktree_management_state.showing_confirm {
-        let popup_area = centered_rect(50, 30, area);
+        let state = &app.worktree_management_state;
+        let main_branch = &app.project.config.git.main_branch;
+        let popup_area = if state.pending_cleanup.is_empty() && state.pending_branch.is_none() {
+            centered_rect(50, 30, area)
+        } else {
+            centered_rect(60, 45, area)
+        };
 
-        let message = if let Some(ref path) = app.worktree_management_state.pending_delete {
-            format!(
-                "Delete worktree at:\n{}\n\nThis cannot be undone!",
-                path.display()
+        let (title, message) = if !state.pending_cleanup.is_empty() {
+            let branches: Vec<String> = state
+                .pending_cleanup
+                .iter()
+                .map(|wt| format!("- {}", wt.branch))
+                .collect();
+            (
+                "Confirm Cleanup",
+                format!(
+                    "Remove {} worktrees merged into {}?\n{}\n\nTheir branches are kept.",
+                    branches.len(),
+                    main_branch,
+                    branches.join("\n")
+                ),
+            )
+        } else if let (Some(path), Some(branch)) = (&state.pending_delete, &state.pending_branch) {
+            let safety = match state.branch_safety {
+                Some(ref safety) => match safety.warning(main_branch) {
+                    Some(warning) => format!("Warning: {}!", warning),
+                    None if safety.merged => format!("Merged into {}.", main_branch),
+                    None => "Pushed to origin.".to_string(),
+                },
+                None => "Could not check whether it is merged or pushed.".to_string(),
+            };
+            let choice = if state.delete_branch {
+                "Yes (a recovery ref is kept in refs/speck/trash/)"
+            } else {
+                "No"
+            };
+            (
+                "Confirm Delete",
+                format!(
+                    "Delete worktree at:\n{}\n\n[b] Also delete branch {}: {}\n{}",
+                    path.display(),
+                    branch,
+                    choice,
+                    safety
+                ),
+            )
+        } else if let Some(ref path) = state.pending_delete {
+            (
+                "Confirm Delete",
+                format!(
+                    "Delete worktree at:\n{}\n\nThis cannot be undone!",
+                    path.display()
+                ),
             )
         } else {
-            "Delete this worktree?".to_string()
+            ("Confirm Delete", "Delete this worktree?".to_string())
         };
 
-        let dialog = ConfirmDialog::new("Confirm Delete", &message)
+        let dialog = ConfirmDialog::new(title, &message)
             .yes_selected(app.worktree_management_state.confirm_yes_selected);
         frame.render_widget(dialog, popup_area);
     }
@@ -246,7 +422,7 @@ fn draw_document_view(frame: &mut Frame, app: &App, area: Rect, doc_type: &DocTy
 }
 
 /// Draw document edit view with editor widget
-fn draw_document_edit(frame: &mut Frame, app: &App, area: Rect, doc_type: &DocType) {
+fn draw_document_edit(frame: &mut Frame, app: &App, area: Rect, header_text: &str) {
     // Create layout: header, editor, status bar, footer
     let chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -258,17 +434,6 @@ fn draw_document_edit(frame: &mut Frame, app: &App, area: Rect, doc_type: &DocTy
         ])
         .split(area);
 
-    // Header with spec info
-    let header_text = if let Some(spec) = app.selected_spec() {
-        format!(
-            "Edit: {} - {}",
-            doc_type_filename(doc_type),
-            spec.id.as_str()
-        )
-    } else {
-        format!("Edit: {}", doc_type_filename(doc_type))
-    };
-
     let header = Paragraph::new(header_text)
         .style(
             Style::default()
@@ -320,15 +485,154 @@ fn draw_document_edit(frame: &mut Frame, app: &App, area: Rect, doc_type: &DocTy
     frame.render_widget(footer, chunks[3]);
 }
 
+/// Draw the review of artifact changes made by a workflow run
+fn draw_artifact_review(frame: &mut Frame, app: &App, area: Rect) {
+    // Create layout: header, diff view, footer
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Diff view
+            Constraint::Length(3), // Footer
+        ])
+        .split(area);
+
+    let header_text = match app.active_context {
+        Some(ref context) => format!("Review changes: {}", context.spec_id),
+        None => "Review changes".to_string(),
+    };
+    let header = Paragraph::new(header_text)
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+    frame.render_widget(header, chunks[0]);
+
+    frame.render_widget(DiffViewWidget::new(&app.artifact_review), chunks[1]);
+
+    let footer_text = " j/k: Select file | PgUp/PgDn: Scroll | a: Accept | A: Accept all | r: Revert | e: Edit | Esc: Back ";
+    let footer = Paragraph::new(footer_text)
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().borders(Borders::TOP));
+    frame.render_widget(footer, chunks[2]);
+}
+
+/// Draw the diff view of a worktree's changes
+fn draw_worktree_diff(frame: &mut Frame, app: &App, area: Rect) {
+    // Create layout: header, diff view, footer
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Diff view
+            Constraint::Length(3), // Footer
+        ])
+        .split(area);
+
+    let branch = app
+        .diff_worktree
+        .as_ref()
+        .map(|wt| wt.branch.as_str())
+        .unwrap_or_default();
+    let header_text = match app.worktree_diff_scope {
+        DiffScope::WorkingTree => format!("Uncommitted changes: {}", branch),
+        DiffScope::Branch => format!(
+            "Changes: {} vs {}",
+            branch, app.project.config.git.main_branch
+        ),
+    };
+    let header = Paragraph::new(header_text)
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+    frame.render_widget(header, chunks[0]);
+
+    if app.worktree_diff.is_empty() {
+        let empty = Paragraph::new("No changes")
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center);
+        frame.render_widget(empty, chunks[1]);
+    } else {
+        let title = format!(
+            " {} ({}) ",
+            app.worktree_diff_scope.display_name(),
+            app.worktree_diff.files.len()
+        );
+        frame.render_widget(
+            DiffViewWidget::new(&app.worktree_diff).title(&title),
+            chunks[1],
+        );
+    }
+
+    let footer_text = " j/k: Select file | n/N: Next/Prev hunk | PgUp/PgDn: Scroll | Tab: Uncommitted/Branch | c: Commit | r: Refresh | Esc: Back ";
+    let footer = Paragraph::new(footer_text)
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().borders(Borders::TOP));
+    frame.render_widget(footer, chunks[2]);
+}
+
+/// Draw the commit view for staging and committing a worktree's changes
+fn draw_commit_view(frame: &mut Frame, app: &App, area: Rect) {
+    // Create layout: header, files and message, footer
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Files and message
+            Constraint::Length(3), // Footer
+        ])
+        .split(area);
+
+    let branch = app
+        .commit_worktree
+        .as_ref()
+        .map(|wt| wt.branch.as_str())
+        .unwrap_or_default();
+    let status = WorktreeStatus::from_changes(&app.commit_view.files);
+    let header = Paragraph::new(format!("Commit: {} - {}", branch, status))
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+    frame.render_widget(header, chunks[0]);
+
+    frame.render_widget(CommitViewWidget::new(&app.commit_view), chunks[1]);
+
+    let footer_text = match app.commit_view.focus {
+        CommitFocus::Files => {
+            " j/k: Select file | Space: Stage/Unstage | a: Stage all | Tab: Edit message | Ctrl+S: Commit | r: Refresh | Esc: Back "
+        }
+        CommitFocus::Message => " Ctrl+S: Commit | Tab/Esc: Back to files ",
+    };
+    let footer = Paragraph::new(footer_text)
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().borders(Borders::TOP));
+    frame.render_widget(footer, chunks[2]);
+}
+
 /// Draw command output view with streaming output
 fn draw_command_output(frame: &mut Frame, app: &App, area: Rect) {
-    // Create layout: header, output, footer
+    // Create layout: header, output, stdin input (while accepted), footer
+    let input_height = if app.accepts_stdin() { 3 } else { 0 };
+    let header_height = if app.pipeline.is_some() || app.batch.is_some() {
+        4
+    } else {
+        3
+    };
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3), // Header with command info
-            Constraint::Min(0),    // Output panel
-            Constraint::Length(3), // Footer with keybindings
+            Constraint::Length(header_height), // Header with command info
+            Constraint::Min(0),                // Output panel
+            Constraint::Length(input_height),  // Stdin input line
+            Constraint::Length(3),             // Footer with keybindings
         ])
         .split(area);
 
@@ -342,39 +646,134 @@ fn draw_command_output(frame: &mut Frame, app: &App, area: Rect) {
             }
             crate::domain::ExecutionState::Completed { .. } => "✗ Completed (error)",
             crate::domain::ExecutionState::Failed { .. } => "✗ Failed",
-            crate::domain::ExecutionState::Cancelled => "⊘ Cancelled",
+            crate::domain::ExecutionState::Cancelling { .. } => "⊘ Cancelling...",
+            crate::domain::ExecutionState::Cancelled { .. } => "⊘ Cancelled",
         };
-        format!("{} - {}", cmd.command_type.display_name(), state_indicator)
+        match app.hook_run {
+            Some(ref hooks) => format!(
+                "{} - {} (running {}s)",
+                cmd.command_type.display_name(),
+                state_indicator,
+                hooks.phase.display_name()
+            ),
+            None => format!("{} - {}", cmd.command_type.display_name(), state_indicator),
+        }
+    } else if let Some(ref run) = app.git_run {
+        run.summary()
     } else {
         "No command".to_string()
     };
 
-    let header = Paragraph::new(header_text)
-        .style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )
-        .block(Block::default().borders(Borders::BOTTOM));
+    let mut header_lines = vec![Line::styled(
+        header_text,
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    )];
+    if let Some(ref pipeline) = app.pipeline {
+        header_lines.push(pipeline_line(pipeline));
+    } else if let Some(ref batch) = app.batch {
+        header_lines.push(batch_line(batch));
+    }
+
+    let header = Paragraph::new(header_lines).block(Block::default().borders(Borders::BOTTOM));
     frame.render_widget(header, chunks[0]);
 
     // Output panel
     let output_widget =
         OutputPanelWidget::new(app.output_buffer.lines(), app.active_command.as_ref())
             .scroll_offset(app.output_buffer.scroll_offset())
-            .auto_scroll(app.output_buffer.is_auto_scroll());
+            .auto_scroll(app.output_buffer.is_auto_scroll())
+            .blocks(
+                app.output_buffer.expanded_blocks(),
+                app.output_buffer.selected_block(),
+            );
     frame.render_widget(output_widget, chunks[1]);
 
+    // Stdin input line
+    if app.accepts_stdin() {
+        let input_widget = TextInputWidget::new(&app.stdin_input.value, app.stdin_input.cursor)
+            .title("Input")
+            .placeholder("Type a reply for the agent")
+            .focused(app.stdin_focused);
+        frame.render_widget(input_widget, chunks[2]);
+    }
+
     // Footer with keybindings
-    let footer_text = if app.is_command_running() {
+    let cancelling = app
+        .active_command
+        .as_ref()
+        .is_some_and(|cmd| cmd.state.is_cancelling());
+    let footer_text = if cancelling {
+        " Cancelling... | j/k: Scroll | G: Bottom "
+    } else if app.is_pipeline_awaiting_review() && !app.artifact_review.is_empty() {
+        " n: Next step | s: Stop pipeline | d: Review changes | j/k: Scroll | G: Bottom "
+    } else if app.is_pipeline_awaiting_review() {
+        " n: Next step | s: Stop pipeline | q/Esc: Back | j/k: Scroll | G: Bottom "
+    } else if app.stdin_focused {
+        " Enter: Send | Ctrl+D: Close stdin | Esc: Stop typing "
+    } else if app.accepts_stdin() {
+        " i: Input | Ctrl+D: Close stdin | c: Cancel | j/k: Scroll | G: Bottom "
+    } else if app.is_command_running() {
         " c: Cancel | j/k: Scroll | G: Bottom "
+    } else if app.has_update_conflicts() {
+        " C: Continue | A: Abort | q/Esc: Back | j/k: Scroll | G: Bottom "
+    } else if !app.artifact_review.is_empty() {
+        " d: Review changes | q/Esc: Back | j/k: Scroll | G: Bottom "
     } else {
         " q/Esc: Back | j/k: Scroll | G: Bottom "
     };
     let footer = Paragraph::new(footer_text)
         .style(Style::default().fg(Color::DarkGray))
         .block(Block::default().borders(Borders::TOP));
-    frame.render_widget(footer, chunks[2]);
+    frame.render_widget(footer, chunks[3]);
+}
+
+/// Render pipeline steps as `✓ Specify → ▶ Clarify → ○ Plan`
+fn pipeline_line(pipeline: &Pipeline) -> Line<'static> {
+    let mut spans = vec![Span::styled(
+        "Pipeline: ",
+        Style::default().fg(Color::DarkGray),
+    )];
+    for (idx, step) in pipeline.steps.iter().enumerate() {
+        if idx > 0 {
+            spans.push(Span::styled(" → ", Style::default().fg(Color::DarkGray)));
+        }
+        spans.push(Span::styled(
+            format!("{} {}", step.state.indicator(), step.command.display_name()),
+            step_style(&step.state),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Render batch progress as `Tasks: ✓ 001-foo  ▶ 002-bar  ○ 003-baz`
+fn batch_line(batch: &Batch) -> Line<'static> {
+    let mut spans = vec![Span::styled(
+        format!("{}: ", batch.command.display_name()),
+        Style::default().fg(Color::DarkGray),
+    )];
+    for (idx, entry) in batch.entries.iter().enumerate() {
+        if idx > 0 {
+            spans.push(Span::raw("  "));
+        }
+        spans.push(Span::styled(
+            format!("{} {}", entry.state.indicator(), entry.spec_id),
+            step_style(&entry.state),
+        ));
+    }
+    Line::from(spans)
+}
+
+/// Color for a pipeline step or batch entry state
+fn step_style(state: &StepState) -> Style {
+    match state {
+        StepState::Pending | StepState::Skipped => Style::default().fg(Color::DarkGray),
+        StepState::Running => Style::default().fg(Color::Yellow),
+        StepState::Succeeded => Style::default().fg(Color::Green),
+        StepState::AwaitingReview => Style::default().fg(Color::Magenta),
+        StepState::Failed(_) => Style::default().fg(Color::Red),
+    }
 }
 
 /// Draw workflow command selection menu
@@ -387,7 +786,9 @@ fn draw_workflow_menu(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(ratatui::widgets::Clear, popup_area);
 
     // Get spec info for title
-    let title = if let Some(spec) = app.selected_spec() {
+    let title = if !app.marked_specs.is_empty() {
+        format!(" Run Workflow - {} specs ", app.marked_specs.len())
+    } else if let Some(spec) = app.selected_spec() {
         format!(" Run Workflow - {} ", spec.id.as_str())
     } else {
         " Run Workflow ".to_string()
@@ -408,7 +809,15 @@ fn draw_workflow_menu(frame: &mut Frame, app: &App, area: Rect) {
                 Style::default()
             };
 
-            let text = format!("  {}  ", cmd_type.display_name());
+            let has_session = app
+                .workflow_sessions
+                .get(idx)
+                .is_some_and(|session| session.is_some());
+            let text = if has_session {
+                format!("  {}  ↻", cmd_type.display_name())
+            } else {
+                format!("  {}  ", cmd_type.display_name())
+            };
             ListItem::new(text).style(style)
         })
         .collect();
@@ -435,7 +844,18 @@ fn draw_workflow_menu(frame: &mut Frame, app: &App, area: Rect) {
         width: popup_area.width,
         height: 1,
     };
-    let hint = Paragraph::new(" Enter: Run | Esc: Cancel ")
+    let can_continue = app
+        .workflow_sessions
+        .get(app.selected_workflow_index)
+        .is_some_and(|session| session.is_some());
+    let hint_text = if can_continue {
+        " Enter: Run | a: Pipeline | c: Continue last session | Esc: Cancel "
+    } else if app.marked_specs.is_empty() {
+        " Enter: Run | a: Run pipeline from here | Esc: Cancel "
+    } else {
+        " Enter: Run for each marked spec | Esc: Cancel "
+    };
+    let hint = Paragraph::new(hint_text)
         .style(Style::default().fg(Color::DarkGray))
         .alignment(Alignment::Center);
     frame.render_widget(hint, hint_area);
@@ -453,6 +873,35 @@ fn draw_new_spec(frame: &mut Frame, app: &App, area: Rect) {
     frame.render_widget(dialog, popup_area);
 }
 
+/// Draw the token usage summary
+fn draw_usage_summary(frame: &mut Frame, app: &App, area: Rect) {
+    // Create layout: header, summary, footer
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Summary
+            Constraint::Length(3), // Footer
+        ])
+        .split(area);
+
+    let header = Paragraph::new("Token usage")
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )
+        .block(Block::default().borders(Borders::BOTTOM));
+    frame.render_widget(header, chunks[0]);
+
+    frame.render_widget(UsageSummaryWidget::new(&app.usage_view_state), chunks[1]);
+
+    let footer = Paragraph::new(" j/k: Scroll runs | PgUp/PgDn: Page | Esc: Back ")
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().borders(Borders::TOP));
+    frame.render_widget(footer, chunks[2]);
+}
+
 /// Draw help view showing all keybindings
 fn draw_help(frame: &mut Frame, app: &App, area: Rect) {
     // We need a mutable reference to help_view_state, but we only have an immutable app reference.
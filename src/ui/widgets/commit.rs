@@ -0,0 +1,240 @@
+//! Commit view widget for staging files and writing a commit message.
+
+use super::editor::convert_key_event;
+use crate::domain::ChangedFile;
+use crossterm::event::KeyEvent;
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph},
+};
+use tui_textarea::{CursorMove, TextArea};
+
+/// Which part of the commit view receives keys
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CommitFocus {
+    /// The list of changed files
+    #[default]
+    Files,
+    /// The commit message editor
+    Message,
+}
+
+/// State for the commit view
+#[derive(Debug, Clone, Default)]
+pub struct CommitViewState {
+    /// Files with uncommitted changes
+    pub files: Vec<ChangedFile>,
+    /// Index of the selected file
+    pub selected: usize,
+    /// Part of the view receiving keys
+    pub focus: CommitFocus,
+    /// Commit message editor
+    message: TextArea<'static>,
+}
+
+impl CommitViewState {
+    /// Create a new state with a prefilled commit message
+    pub fn new(files: Vec<ChangedFile>, prefill: &str) -> Self {
+        let mut state = Self {
+            files,
+            ..Self::default()
+        };
+        state.reset_message(prefill);
+        state
+    }
+
+    /// Replace the changed files, keeping the selected path if it is still listed
+    pub fn set_files(&mut self, files: Vec<ChangedFile>) {
+        let selected = self.selected_file().map(|f| f.path.clone());
+        self.files = files;
+        self.selected = selected
+            .and_then(|path| self.files.iter().position(|f| f.path == path))
+            .unwrap_or(self.selected)
+            .min(self.files.len().saturating_sub(1));
+    }
+
+    /// Get the selected file
+    pub fn selected_file(&self) -> Option<&ChangedFile> {
+        self.files.get(self.selected)
+    }
+
+    /// Select the next file
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.files.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Select the previous file
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Count the files with staged changes
+    pub fn staged_count(&self) -> usize {
+        self.files.iter().filter(|f| f.staged).count()
+    }
+
+    /// Get the commit message, trimmed of surrounding whitespace
+    pub fn message(&self) -> String {
+        self.message.lines().join("\n").trim().to_string()
+    }
+
+    /// Replace the commit message, leaving the cursor at its end
+    pub fn reset_message(&mut self, prefill: &str) {
+        self.message = TextArea::new(prefill.lines().map(String::from).collect());
+        self.message.move_cursor(CursorMove::Bottom);
+        self.message.move_cursor(CursorMove::End);
+        self.set_focus(self.focus);
+    }
+
+    /// Pass a key to the commit message editor
+    pub fn handle_message_key(&mut self, key: KeyEvent) {
+        self.message.input(convert_key_event(key));
+    }
+
+    /// Move focus to the file list or the message editor
+    pub fn set_focus(&mut self, focus: CommitFocus) {
+        self.focus = focus;
+        // Only show the editor cursor while the message has focus
+        let cursor = match focus {
+            CommitFocus::Message => Style::default().add_modifier(Modifier::REVERSED),
+            CommitFocus::Files => Style::default(),
+        };
+        self.message.set_cursor_style(cursor);
+        self.message.set_cursor_line_style(Style::default());
+    }
+}
+
+/// Widget showing the changed files above the commit message editor
+pub struct CommitViewWidget<'a> {
+    /// State holding files, selection and message
+    state: &'a CommitViewState,
+}
+
+impl<'a> CommitViewWidget<'a> {
+    /// Create a new commit view widget
+    pub fn new(state: &'a CommitViewState) -> Self {
+        Self { state }
+    }
+
+    fn focus_style(&self, focus: CommitFocus) -> Style {
+        if self.state.focus == focus {
+            Style::default().fg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        }
+    }
+
+    fn render_file_list(&self, area: Rect, buf: &mut Buffer) {
+        let lines: Vec<Line> = self
+            .state
+            .files
+            .iter()
+            .enumerate()
+            .map(|(idx, file)| {
+                let marker = if idx == self.state.selected {
+                    "> "
+                } else {
+                    "  "
+                };
+                let (checkbox, color) = if file.is_fully_staged() {
+                    ("[x]", Color::Green)
+                } else if file.staged {
+                    ("[~]", Color::Yellow)
+                } else {
+                    ("[ ]", Color::Reset)
+                };
+                let status_color = if file.untracked {
+                    Color::Red
+                } else {
+                    Color::Yellow
+                };
+                let mut line = Line::from(vec![
+                    Span::raw(marker),
+                    Span::styled(checkbox, Style::default().fg(color)),
+                    Span::raw(" "),
+                    Span::styled(file.indicator(), Style::default().fg(status_color)),
+                    Span::raw(format!(" {}", file.path.display())),
+                ]);
+                if idx == self.state.selected {
+                    line = line.style(Style::default().add_modifier(Modifier::BOLD));
+                }
+                line
+            })
+            .collect();
+
+        let title = format!(
+            " Files ({}/{} staged) ",
+            self.state.staged_count(),
+            self.state.files.len()
+        );
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.focus_style(CommitFocus::Files))
+                    .title(title),
+            )
+            .render(area, buf);
+    }
+
+    fn render_message(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.focus_style(CommitFocus::Message))
+            .title(" Commit message ");
+        let inner = block.inner(area);
+        block.render(area, buf);
+        self.state.message.render(inner, buf);
+    }
+}
+
+impl Widget for CommitViewWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(8)])
+            .split(area);
+
+        self.render_file_list(chunks[0], buf);
+        self.render_message(chunks[1], buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+    use std::path::PathBuf;
+
+    fn file(name: &str, staged: bool) -> ChangedFile {
+        let mut file = ChangedFile::new(PathBuf::from(name));
+        file.staged = staged;
+        file.modified = !staged;
+        file
+    }
+
+    #[test]
+    fn test_commit_view_state() {
+        let mut state = CommitViewState::new(
+            vec![file("a.md", true), file("b.md", false), file("c.md", false)],
+            "003-foo: ",
+        );
+        assert_eq!(state.staged_count(), 1);
+
+        state.select_next();
+        state.select_next();
+        state.set_files(vec![file("b.md", true), file("c.md", true)]);
+        assert_eq!(state.selected_file().unwrap().path, PathBuf::from("c.md"));
+        assert_eq!(state.staged_count(), 2);
+
+        // Typing continues after the prefilled spec id
+        for c in "Add plan".chars() {
+            state.handle_message_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(state.message(), "003-foo: Add plan");
+        state.reset_message("");
+        assert!(state.message().is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+//! Commit log widget listing a spec branch's commits and the tasks they
+//! mention.
+
+use super::usage_summary::format_age;
+use crate::domain::{find_task, CommitInfo};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// State for the commit log
+#[derive(Debug, Default, Clone)]
+pub struct CommitLogState {
+    /// Commits on the branch, newest first
+    pub commits: Vec<CommitInfo>,
+    /// Index of the selected commit
+    pub selected: usize,
+    /// Content of the spec's tasks.md, used to look up mentioned tasks
+    pub tasks: Option<String>,
+}
+
+impl CommitLogState {
+    /// Create a new state for a branch's commits
+    pub fn new(commits: Vec<CommitInfo>, tasks: Option<String>) -> Self {
+        Self {
+            commits,
+            selected: 0,
+            tasks,
+        }
+    }
+
+    /// Get the selected commit
+    pub fn selected_commit(&self) -> Option<&CommitInfo> {
+        self.commits.get(self.selected)
+    }
+
+    /// Select the next (older) commit
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.commits.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Select the previous (newer) commit
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    /// Get the selected commit's task ids with their tasks.md lines, if found
+    pub fn linked_tasks(&self) -> Vec<(String, Option<&str>)> {
+        let Some(commit) = self.selected_commit() else {
+            return Vec::new();
+        };
+        commit
+            .task_ids()
+            .into_iter()
+            .map(|id| {
+                let task = self
+                    .tasks
+                    .as_deref()
+                    .and_then(|tasks| find_task(tasks, &id));
+                (id, task)
+            })
+            .collect()
+    }
+}
+
+/// Widget showing the commit list above the selected commit's message
+/// and linked tasks
+pub struct CommitLogWidget<'a> {
+    /// State holding commits and selection
+    state: &'a CommitLogState,
+    /// Title of the commit list
+    title: &'a str,
+}
+
+impl<'a> CommitLogWidget<'a> {
+    /// Create a new commit log widget
+    pub fn new(state: &'a CommitLogState) -> Self {
+        Self {
+            state,
+            title: " Commits ",
+        }
+    }
+
+    /// Set the title of the commit list
+    pub fn title(mut self, title: &'a str) -> Self {
+        self.title = title;
+        self
+    }
+
+    fn render_commit_list(&self, area: Rect, buf: &mut Buffer) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let lines: Vec<Line> = self
+            .state
+            .commits
+            .iter()
+            .enumerate()
+            .map(|(idx, commit)| {
+                let marker = if idx == self.state.selected {
+                    "> "
+                } else {
+                    "  "
+                };
+                let mut spans = vec![
+                    Span::raw(marker),
+                    Span::styled(commit.short_id(), Style::default().fg(Color::Yellow)),
+                    Span::styled(
+                        format!(" {:>9} ", format_age(now.saturating_sub(commit.time))),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(
+                        format!("{} ", commit.author),
+                        Style::default().fg(Color::Blue),
+                    ),
+                    Span::raw(commit.subject.clone()),
+                ];
+                for id in commit.task_ids() {
+                    spans.push(Span::styled(
+                        format!(" [{}]", id),
+                        Style::default().fg(Color::Magenta),
+                    ));
+                }
+                let mut line = Line::from(spans);
+                if idx == self.state.selected {
+                    line = line.style(Style::default().add_modifier(Modifier::BOLD));
+                }
+                line
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .title(self.title),
+            )
+            .render(area, buf);
+    }
+
+    fn render_details(&self, area: Rect, buf: &mut Buffer) {
+        let Some(commit) = self.state.selected_commit() else {
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Details ")
+                .render(area, buf);
+            return;
+        };
+
+        let mut lines: Vec<Line> = commit.message.lines().map(Line::raw).collect();
+        let tasks = self.state.linked_tasks();
+        if !tasks.is_empty() {
+            lines.push(Line::raw(""));
+            lines.push(Line::styled(
+                "Tasks:",
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            for (id, task) in tasks {
+                lines.push(match task {
+                    Some(task) => {
+                        Line::styled(format!("  {}", task), Style::default().fg(Color::Magenta))
+                    }
+                    None => Line::styled(
+                        format!("  {} (not in tasks.md)", id),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                });
+            }
+        }
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" {} ", commit.short_id())),
+            )
+            .render(area, buf);
+    }
+}
+
+impl Widget for CommitLogWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(area);
+
+        self.render_commit_list(chunks[0], buf);
+        self.render_details(chunks[1], buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(id: &str, message: &str) -> CommitInfo {
+        CommitInfo {
+            id: id.to_string(),
+            author: "Dev".to_string(),
+            time: 0,
+            subject: message.lines().next().unwrap_or_default().to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_linked_tasks() {
+        let mut state = CommitLogState::new(
+            vec![
+                commit("b", "T002: Add tests\n\nFollows T009"),
+                commit("a", "Initial"),
+            ],
+            Some("- [x] T002 Add tests for the parser\n".to_string()),
+        );
+        assert_eq!(
+            state.linked_tasks(),
+            vec![
+                (
+                    "T002".to_string(),
+                    Some("- [x] T002 Add tests for the parser")
+                ),
+                ("T009".to_string(), None),
+            ]
+        );
+
+        state.select_next();
+        state.select_next();
+        assert_eq!(state.selected_commit().unwrap().id, "a");
+        assert!(state.linked_tasks().is_empty());
+    }
+}
@@ -0,0 +1,310 @@
+//! Diff view widget showing changed files and their unified diffs.
+
+use crate::domain::{DiffLineKind, FileChange, FileDiff};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Paragraph},
+};
+
+/// State for the diff view
+#[derive(Debug, Default, Clone)]
+pub struct DiffViewState {
+    /// Changed files
+    pub files: Vec<FileDiff>,
+    /// Index of the selected file
+    pub selected: usize,
+    /// Scroll offset within the selected file's diff (in lines)
+    pub scroll: usize,
+}
+
+impl DiffViewState {
+    /// Create a new state for a set of changed files
+    pub fn new(files: Vec<FileDiff>) -> Self {
+        Self {
+            files,
+            selected: 0,
+            scroll: 0,
+        }
+    }
+
+    /// Check if there are no files to show
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Get the selected file
+    pub fn selected_file(&self) -> Option<&FileDiff> {
+        self.files.get(self.selected)
+    }
+
+    /// Select the next file
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.files.len() {
+            self.selected += 1;
+            self.scroll = 0;
+        }
+    }
+
+    /// Select the previous file
+    pub fn select_previous(&mut self) {
+        if self.selected > 0 {
+            self.selected -= 1;
+            self.scroll = 0;
+        }
+    }
+
+    /// Scroll the diff up by n lines
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll = self.scroll.saturating_sub(n);
+    }
+
+    /// Scroll the diff down by n lines
+    pub fn scroll_down(&mut self, n: usize) {
+        let max = self
+            .selected_file()
+            .map(|f| diff_lines(f).len().saturating_sub(1))
+            .unwrap_or(0);
+        self.scroll = (self.scroll + n).min(max);
+    }
+
+    /// Scroll to the next hunk, moving on to the next file after the last one
+    pub fn next_hunk(&mut self) {
+        match self.hunk_offsets().into_iter().find(|&at| at > self.scroll) {
+            Some(at) => self.scroll = at,
+            None => self.select_next(),
+        }
+    }
+
+    /// Scroll to the previous hunk, moving back to the last hunk of the
+    /// previous file before the first one
+    pub fn previous_hunk(&mut self) {
+        match self
+            .hunk_offsets()
+            .into_iter()
+            .rfind(|&at| at < self.scroll)
+        {
+            Some(at) => self.scroll = at,
+            None if self.selected > 0 => {
+                self.select_previous();
+                self.scroll = self.hunk_offsets().last().copied().unwrap_or(0);
+            }
+            None => {}
+        }
+    }
+
+    /// Line offsets of the selected file's hunk headers
+    fn hunk_offsets(&self) -> Vec<usize> {
+        let Some(file) = self.selected_file() else {
+            return Vec::new();
+        };
+        let mut offset = 0;
+        file.hunks
+            .iter()
+            .map(|hunk| {
+                let at = offset;
+                offset += hunk.lines.len() + 1;
+                at
+            })
+            .collect()
+    }
+
+    /// Select the file at a path, if it is still listed
+    pub fn select_path(&mut self, path: &std::path::Path) {
+        if let Some(idx) = self.files.iter().position(|f| f.path == path) {
+            self.selected = idx;
+        }
+    }
+
+    /// Remove the selected file from the view
+    pub fn remove_selected(&mut self) -> Option<FileDiff> {
+        if self.selected >= self.files.len() {
+            return None;
+        }
+        let file = self.files.remove(self.selected);
+        if self.selected >= self.files.len() {
+            self.selected = self.files.len().saturating_sub(1);
+        }
+        self.scroll = 0;
+        Some(file)
+    }
+}
+
+/// Widget showing a file list next to the selected file's diff
+pub struct DiffViewWidget<'a> {
+    /// State holding files and selection
+    state: &'a DiffViewState,
+    /// Title of the file list
+    title: &'a str,
+}
+
+impl<'a> DiffViewWidget<'a> {
+    /// Create a new diff view widget
+    pub fn new(state: &'a DiffViewState) -> Self {
+        Self {
+            state,
+            title: " Changed files ",
+        }
+    }
+
+    /// Set the title of the file list
+    pub fn title(mut self, title: &'a str) -> Self {
+        self.title = title;
+        self
+    }
+
+    fn render_file_list(&self, area: Rect, buf: &mut Buffer) {
+        let lines: Vec<Line> = self
+            .state
+            .files
+            .iter()
+            .enumerate()
+            .map(|(idx, file)| {
+                let marker = if idx == self.state.selected {
+                    "> "
+                } else {
+                    "  "
+                };
+                let status_color = match file.change {
+                    FileChange::Added => Color::Green,
+                    FileChange::Modified => Color::Yellow,
+                    FileChange::Deleted => Color::Red,
+                };
+                let mut line = Line::from(vec![
+                    Span::raw(marker),
+                    Span::styled(file.change.indicator(), Style::default().fg(status_color)),
+                    Span::raw(format!(" {} ", file.path.display())),
+                    Span::styled(
+                        format!("+{}", file.additions()),
+                        Style::default().fg(Color::Green),
+                    ),
+                    Span::raw(" "),
+                    Span::styled(
+                        format!("-{}", file.deletions()),
+                        Style::default().fg(Color::Red),
+                    ),
+                ]);
+                if idx == self.state.selected {
+                    line = line.style(Style::default().add_modifier(Modifier::BOLD));
+                }
+                line
+            })
+            .collect();
+
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .title(self.title),
+            )
+            .render(area, buf);
+    }
+
+    fn render_diff(&self, area: Rect, buf: &mut Buffer) {
+        let (title, lines) = match self.state.selected_file() {
+            Some(file) => (format!(" {} ", file.path.display()), diff_lines(file)),
+            None => (" Diff ".to_string(), Vec::new()),
+        };
+
+        let visible: Vec<Line> = lines.into_iter().skip(self.state.scroll).collect();
+
+        Paragraph::new(visible)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .render(area, buf);
+    }
+}
+
+impl Widget for DiffViewWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+            .split(area);
+
+        self.render_file_list(chunks[0], buf);
+        self.render_diff(chunks[1], buf);
+    }
+}
+
+/// Build the colored lines of a file's unified diff
+fn diff_lines(file: &FileDiff) -> Vec<Line<'static>> {
+    if file.hunks.is_empty() {
+        return vec![Line::from(Span::styled(
+            "(empty file)",
+            Style::default().fg(Color::DarkGray),
+        ))];
+    }
+
+    let mut lines = Vec::new();
+    for hunk in &file.hunks {
+        lines.push(Line::from(Span::styled(
+            hunk.header(),
+            Style::default().fg(Color::Cyan),
+        )));
+        for line in &hunk.lines {
+            let style = match line.kind {
+                DiffLineKind::Context => Style::default(),
+                DiffLineKind::Added => Style::default().fg(Color::Green),
+                DiffLineKind::Removed => Style::default().fg(Color::Red),
+            };
+            lines.push(Line::from(Span::styled(
+                format!("{}{}", line.kind.prefix(), line.content),
+                style,
+            )));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn file(name: &str) -> FileDiff {
+        FileDiff::between(PathBuf::from(name), Some("a\n"), Some("b\n")).unwrap()
+    }
+
+    #[test]
+    fn test_diff_view_selection() {
+        let mut state = DiffViewState::new(vec![file("plan.md"), file("spec.md")]);
+
+        state.select_next();
+        state.scroll_down(100);
+        assert_eq!(state.selected, 1);
+        // Hunk header plus removed and added line
+        assert_eq!(state.scroll, 2);
+
+        let removed = state.remove_selected().unwrap();
+        assert_eq!(removed.path, PathBuf::from("spec.md"));
+        assert_eq!(state.selected, 0);
+        assert_eq!(state.scroll, 0);
+
+        state.remove_selected();
+        assert!(state.is_empty());
+        assert!(state.remove_selected().is_none());
+    }
+
+    #[test]
+    fn test_hunk_navigation() {
+        let old: String = (0..20).map(|i| format!("{}\n", i)).collect();
+        let new = old
+            .replacen("\n1\n", "\none\n", 1)
+            .replacen("\n18\n", "\neighteen\n", 1);
+        let two_hunks =
+            FileDiff::between(PathBuf::from("plan.md"), Some(&old), Some(&new)).unwrap();
+        assert_eq!(two_hunks.hunks.len(), 2);
+        let mut state = DiffViewState::new(vec![two_hunks, file("spec.md")]);
+
+        // The first hunk has 6 lines below its header
+        state.next_hunk();
+        assert_eq!((state.selected, state.scroll), (0, 7));
+        state.next_hunk();
+        assert_eq!((state.selected, state.scroll), (1, 0));
+
+        state.previous_hunk();
+        assert_eq!((state.selected, state.scroll), (0, 7));
+        state.previous_hunk();
+        assert_eq!((state.selected, state.scroll), (0, 0));
+    }
+}
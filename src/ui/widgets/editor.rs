@@ -197,7 +197,7 @@ pub enum EditorAction {
 }
 
 /// Convert crossterm KeyEvent to tui-textarea Input
-fn convert_key_event(key: KeyEvent) -> Input {
+pub(crate) fn convert_key_event(key: KeyEvent) -> Input {
     let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
     let alt = key.modifiers.contains(KeyModifiers::ALT);
     let shift = key.modifiers.contains(KeyModifiers::SHIFT);
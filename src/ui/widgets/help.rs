@@ -34,17 +34,67 @@ const HELP_SECTIONS: &[(&str, &[(&str, &str)])] = &[
             ("n", "Create new specification"),
             ("v", "View document (spec.md)"),
             ("e", "Edit document"),
-            ("r", "Run workflow command"),
+            ("r", "Run workflow command (for marked specs, if any)"),
+            ("m", "Mark spec for a batch run"),
+            ("M", "Mark all specs in the selected phase"),
+            ("Esc", "Clear marks"),
+            ("u", "Show token usage and cost"),
             ("F5", "Refresh specs & worktrees"),
         ],
     ),
+    (
+        "Spec Detail",
+        &[
+            ("j/k", "Select commit on the spec branch"),
+            ("Enter/d", "View the commit's diff"),
+            ("r", "Reload the commit log"),
+        ],
+    ),
+    (
+        "Workflow Menu",
+        &[
+            ("Enter", "Run the selected command"),
+            ("a", "Run a pipeline from the selected command"),
+            ("c", "Continue the command's last agent session (↻)"),
+        ],
+    ),
     (
         "Worktree Management",
         &[
             ("w", "Switch to spec's worktree"),
             ("W", "Open worktree manager"),
-            ("d", "Delete worktree (with confirm)"),
+            ("d", "Delete worktree (with confirm, b: also its branch)"),
             ("D", "Force delete worktree"),
+            ("f", "Fetch from origin"),
+            ("p", "Pull the branch (git.pull_mode)"),
+            ("P", "Push the branch, setting upstream if needed"),
+            ("v", "View the worktree's changes"),
+            ("c", "Stage and commit the worktree's changes"),
+            ("x", "Prune missing worktrees, remove merged ones"),
+            ("u", "Update from main (git.update_mode)"),
+            ("C", "Continue the update after resolving conflicts"),
+            ("A", "Abort the update"),
+        ],
+    ),
+    (
+        "Worktree Diff",
+        &[
+            ("j/k", "Select changed file"),
+            ("n/N", "Next/previous hunk"),
+            ("PgUp/PgDn", "Scroll diff"),
+            ("Tab", "Switch between uncommitted and branch changes"),
+            ("r", "Reload the diff"),
+            ("c", "Stage and commit these changes"),
+        ],
+    ),
+    (
+        "Commit",
+        &[
+            ("j/k", "Select changed file"),
+            ("Space", "Stage or unstage the file"),
+            ("a", "Stage all changes"),
+            ("Tab", "Switch between files and message"),
+            ("Ctrl+S", "Commit staged changes"),
         ],
     ),
     (
@@ -64,11 +114,32 @@ const HELP_SECTIONS: &[(&str, &[(&str, &str)])] = &[
     (
         "Command Output",
         &[
-            ("c", "Cancel running command"),
+            ("c", "Cancel running command or pending retry"),
+            ("C/A", "Continue/abort an update stopped on conflicts"),
+            ("n", "Pipeline: continue after review"),
+            ("s", "Pipeline: stop at review gate"),
+            ("i", "Type input for the command"),
+            ("Enter", "Send input line"),
+            ("Ctrl+D", "Close command input"),
+            ("d", "Review artifact changes"),
+            ("[/]", "Select previous/next tool block"),
+            ("o", "Expand/collapse selected tool block"),
+            ("O", "Expand/collapse all tool blocks"),
             ("↑/↓", "Scroll output"),
             ("G", "Scroll to bottom"),
         ],
     ),
+    (
+        "Review Changes",
+        &[
+            ("j/k", "Select changed file"),
+            ("PgUp/PgDn", "Scroll diff"),
+            ("a", "Accept file"),
+            ("A", "Accept all files"),
+            ("r", "Revert file to its pre-run content"),
+            ("e", "Edit file"),
+        ],
+    ),
 ];
 
 /// State for the help view
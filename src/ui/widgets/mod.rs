@@ -1,9 +1,13 @@
 //! Reusable UI widgets for speck.
 
+pub mod commit;
+pub mod commit_log;
+pub mod diff_view;
 pub mod editor;
 pub mod help;
 pub mod output_panel;
 pub mod spec_detail;
 pub mod spec_list;
 pub mod text_input;
+pub mod usage_summary;
 pub mod worktree_list;
@@ -1,10 +1,11 @@
 //! Output panel widget for displaying streaming command output.
 
-use crate::domain::{ExecutionState, OutputLine, OutputStream, WorkflowCommand};
+use crate::domain::{AgentEvent, ExecutionState, OutputLine, OutputStream, WorkflowCommand};
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState, Wrap},
 };
+use std::collections::BTreeSet;
 use std::fmt;
 use std::time::Instant;
 
@@ -21,6 +22,10 @@ pub struct OutputPanelWidget<'a> {
     scroll_offset: usize,
     /// Is auto-scroll enabled
     auto_scroll: bool,
+    /// Line indices of expanded tool call and result blocks
+    expanded: Option<&'a BTreeSet<usize>>,
+    /// Line index of the selected block
+    selected: Option<usize>,
 }
 
 impl<'a> OutputPanelWidget<'a> {
@@ -31,9 +36,18 @@ impl<'a> OutputPanelWidget<'a> {
             command,
             scroll_offset: 0,
             auto_scroll: true,
+            expanded: None,
+            selected: None,
         }
     }
 
+    /// Set which blocks are expanded and which one is selected
+    pub fn blocks(mut self, expanded: &'a BTreeSet<usize>, selected: Option<usize>) -> Self {
+        self.expanded = Some(expanded);
+        self.selected = selected;
+        self
+    }
+
     /// Set the scroll offset
     pub fn scroll_offset(mut self, offset: usize) -> Self {
         self.scroll_offset = offset;
@@ -57,20 +71,27 @@ impl<'a> OutputPanelWidget<'a> {
                     ExecutionState::Completed { exit_code, .. } if *exit_code == 0 => "✓",
                     ExecutionState::Completed { .. } => "✗",
                     ExecutionState::Failed { .. } => "✗",
-                    ExecutionState::Cancelled => "⊘",
+                    ExecutionState::Cancelling { .. } | ExecutionState::Cancelled { .. } => "⊘",
                 };
                 let state_name = match &cmd.state {
                     ExecutionState::Pending => "Pending",
                     ExecutionState::Running { .. } => "Running",
                     ExecutionState::Completed { .. } => "Completed",
                     ExecutionState::Failed { .. } => "Failed",
-                    ExecutionState::Cancelled => "Cancelled",
+                    ExecutionState::Cancelling { .. } => "Cancelling",
+                    ExecutionState::Cancelled { .. } => "Cancelled",
+                };
+                let attempt = if cmd.max_attempts > 1 {
+                    format!(" (attempt {}/{})", cmd.attempt, cmd.max_attempts)
+                } else {
+                    String::new()
                 };
                 format!(
-                    " {} {} - {} ",
+                    " {} {} - {}{} ",
                     state_indicator,
                     cmd.command_type.display_name(),
-                    state_name
+                    state_name,
+                    attempt
                 )
             }
             None => " Output ".to_string(),
@@ -87,7 +108,8 @@ impl<'a> OutputPanelWidget<'a> {
             Some(ExecutionState::Completed { .. }) | Some(ExecutionState::Failed { .. }) => {
                 Style::default().fg(Color::Red)
             }
-            Some(ExecutionState::Cancelled) => Style::default().fg(Color::DarkGray),
+            Some(ExecutionState::Cancelling { .. }) => Style::default().fg(Color::Magenta),
+            Some(ExecutionState::Cancelled { .. }) => Style::default().fg(Color::DarkGray),
             _ => Style::default(),
         }
     }
@@ -96,26 +118,72 @@ impl<'a> OutputPanelWidget<'a> {
     fn format_lines(&self) -> Vec<Line<'a>> {
         self.lines
             .iter()
-            .map(|line| {
-                let style = if line.stream == OutputStream::Stderr {
-                    Style::default().fg(Color::Red)
-                } else {
-                    Style::default()
-                };
-
+            .enumerate()
+            .flat_map(|(idx, line)| {
                 // Add timestamp prefix
-                let prefix = format_timestamp(&line.timestamp);
-
-                Line::from(vec![
-                    Span::styled(
-                        format!("[{}] ", prefix),
-                        Style::default().fg(Color::DarkGray),
-                    ),
-                    Span::styled(line.content.clone(), style),
-                ])
+                let prefix = Span::styled(
+                    format!("[{}] ", format_timestamp(&line.timestamp)),
+                    Style::default().fg(Color::DarkGray),
+                );
+
+                match line.event {
+                    Some(ref event) => self.format_event(idx, prefix, event),
+                    None => {
+                        let style = match line.stream {
+                            OutputStream::Stderr => Style::default().fg(Color::Red),
+                            OutputStream::Stdin => Style::default().fg(Color::Cyan),
+                            OutputStream::Stdout => Style::default(),
+                        };
+                        let content = match line.stream {
+                            OutputStream::Stdin => format!("> {}", line.content),
+                            _ => line.content.clone(),
+                        };
+                        vec![Line::from(vec![prefix, Span::styled(content, style)])]
+                    }
+                }
             })
             .collect()
     }
+
+    /// Format an agent event as a block header, followed by its content when expanded
+    fn format_event(&self, idx: usize, prefix: Span<'a>, event: &AgentEvent) -> Vec<Line<'a>> {
+        let style = match event {
+            AgentEvent::ToolCall { .. } => Style::default().fg(Color::Magenta),
+            AgentEvent::ToolResult { is_error: true, .. } => Style::default().fg(Color::Red),
+            AgentEvent::ToolResult { .. } | AgentEvent::Usage(_) | AgentEvent::Session(_) => {
+                Style::default().fg(Color::DarkGray)
+            }
+            AgentEvent::Text(_) => Style::default(),
+        };
+
+        let Some(body) = event.body() else {
+            return vec![Line::from(vec![
+                prefix,
+                Span::styled(event.summary(), style),
+            ])];
+        };
+
+        let expanded = self.expanded.is_some_and(|set| set.contains(&idx));
+        let marker = if expanded { "▾ " } else { "▸ " };
+        let style = if self.selected == Some(idx) {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        };
+        let mut lines = vec![Line::from(vec![
+            prefix,
+            Span::styled(format!("{}{}", marker, event.summary()), style),
+        ])];
+        if expanded {
+            lines.extend(body.lines().map(|l| {
+                Line::from(Span::styled(
+                    format!("        │ {}", l),
+                    Style::default().fg(Color::DarkGray),
+                ))
+            }));
+        }
+        lines
+    }
 }
 
 impl Widget for OutputPanelWidget<'_> {
@@ -187,6 +255,10 @@ pub struct OutputBuffer {
     scroll_offset: usize,
     /// Is auto-scroll enabled
     auto_scroll: bool,
+    /// Line indices of expanded tool call and result blocks
+    expanded: BTreeSet<usize>,
+    /// Line index of the selected block
+    selected_block: Option<usize>,
 }
 
 impl OutputBuffer {
@@ -197,6 +269,8 @@ impl OutputBuffer {
             start_time: None,
             scroll_offset: 0,
             auto_scroll: true,
+            expanded: BTreeSet::new(),
+            selected_block: None,
         }
     }
 
@@ -206,23 +280,58 @@ impl OutputBuffer {
         self.start_time = Some(Instant::now());
         self.scroll_offset = 0;
         self.auto_scroll = true;
+        self.expanded.clear();
+        self.selected_block = None;
     }
 
     /// Add a line to the buffer
     pub fn push(&mut self, content: String, stream: OutputStream) {
-        let line = OutputLine {
+        self.push_line(OutputLine {
             content,
             stream,
             timestamp: Instant::now(),
+            event: None,
+        });
+    }
+
+    /// Add an agent event; text is split into plain lines, other events
+    /// become single lines carrying the event
+    pub fn push_event(&mut self, event: AgentEvent) {
+        if let AgentEvent::Text(ref text) = event {
+            for line in text.lines() {
+                self.push_stdout(line.to_string());
+            }
+            return;
+        }
+
+        let stream = match event {
+            AgentEvent::ToolResult { is_error: true, .. } => OutputStream::Stderr,
+            _ => OutputStream::Stdout,
         };
+        self.push_line(OutputLine {
+            content: event.summary(),
+            stream,
+            timestamp: Instant::now(),
+            event: Some(event),
+        });
+    }
+
+    fn push_line(&mut self, line: OutputLine) {
         self.lines.push(line);
 
         // Trim if too many lines
         if self.lines.len() > MAX_OUTPUT_LINES {
-            self.lines.remove(0);
-            if self.scroll_offset > 0 {
-                self.scroll_offset = self.scroll_offset.saturating_sub(1);
-            }
+            let removed = self.lines.remove(0);
+            let removed_rows = 1 + self.body_rows(0, &removed);
+            self.scroll_offset = self.scroll_offset.saturating_sub(removed_rows);
+
+            // Block state is keyed by line index, so shift it with the lines
+            self.expanded = self
+                .expanded
+                .iter()
+                .filter_map(|idx| idx.checked_sub(1))
+                .collect();
+            self.selected_block = self.selected_block.and_then(|idx| idx.checked_sub(1));
         }
     }
 
@@ -236,6 +345,11 @@ impl OutputBuffer {
         self.push(content, OutputStream::Stderr);
     }
 
+    /// Add a line sent to the process stdin
+    pub fn push_stdin(&mut self, content: String) {
+        self.push(content, OutputStream::Stdin);
+    }
+
     /// Get all lines
     pub fn lines(&self) -> &[OutputLine] {
         &self.lines
@@ -256,6 +370,8 @@ impl OutputBuffer {
         self.lines.clear();
         self.start_time = None;
         self.scroll_offset = 0;
+        self.expanded.clear();
+        self.selected_block = None;
     }
 
     /// Scroll up
@@ -266,7 +382,7 @@ impl OutputBuffer {
 
     /// Scroll down
     pub fn scroll_down(&mut self, amount: usize, visible_height: usize) {
-        let max_scroll = self.lines.len().saturating_sub(visible_height);
+        let max_scroll = self.rendered_len().saturating_sub(visible_height);
         self.scroll_offset = (self.scroll_offset + amount).min(max_scroll);
 
         // Re-enable auto-scroll if at bottom
@@ -290,6 +406,109 @@ impl OutputBuffer {
     pub fn is_auto_scroll(&self) -> bool {
         self.auto_scroll
     }
+
+    /// Number of screen lines the buffer renders to, counting the content
+    /// of expanded blocks
+    pub fn rendered_len(&self) -> usize {
+        self.lines
+            .iter()
+            .enumerate()
+            .map(|(idx, line)| 1 + self.body_rows(idx, line))
+            .sum()
+    }
+
+    /// Lines shown below a block header when it is expanded
+    fn body_rows(&self, idx: usize, line: &OutputLine) -> usize {
+        if !self.expanded.contains(&idx) {
+            return 0;
+        }
+        line.event
+            .as_ref()
+            .and_then(|e| e.body())
+            .map_or(0, |body| body.lines().count())
+    }
+
+    /// Line indices of the collapsible blocks
+    fn block_indices(&self) -> impl DoubleEndedIterator<Item = usize> + '_ {
+        self.lines
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| l.event.as_ref().is_some_and(|e| e.body().is_some()))
+            .map(|(idx, _)| idx)
+    }
+
+    /// Check if the buffer holds any collapsible blocks
+    pub fn has_blocks(&self) -> bool {
+        self.block_indices().next().is_some()
+    }
+
+    /// Select the next block, or the first one if none is selected
+    pub fn select_next_block(&mut self) {
+        let next = match self.selected_block {
+            Some(current) => self.block_indices().find(|&idx| idx > current),
+            None => self.block_indices().next(),
+        };
+        if let Some(idx) = next {
+            self.select_block(idx);
+        }
+    }
+
+    /// Select the previous block, or the last one if none is selected
+    pub fn select_previous_block(&mut self) {
+        let previous = match self.selected_block {
+            Some(current) => self.block_indices().rev().find(|&idx| idx < current),
+            None => self.block_indices().next_back(),
+        };
+        if let Some(idx) = previous {
+            self.select_block(idx);
+        }
+    }
+
+    /// Select a block and scroll its header to the top of the panel
+    fn select_block(&mut self, idx: usize) {
+        self.selected_block = Some(idx);
+        self.scroll_offset = self
+            .lines
+            .iter()
+            .take(idx)
+            .enumerate()
+            .map(|(i, line)| 1 + self.body_rows(i, line))
+            .sum();
+        self.auto_scroll = false;
+    }
+
+    /// Get the selected block
+    pub fn selected_block(&self) -> Option<usize> {
+        self.selected_block
+    }
+
+    /// Expand or collapse the selected block, or the last block if none is selected
+    pub fn toggle_block(&mut self) {
+        let Some(idx) = self
+            .selected_block
+            .or_else(|| self.block_indices().next_back())
+        else {
+            return;
+        };
+        if !self.expanded.remove(&idx) {
+            self.expanded.insert(idx);
+        }
+    }
+
+    /// Expand every block, or collapse them all if they are all expanded
+    pub fn toggle_all_blocks(&mut self) {
+        let blocks: BTreeSet<usize> = self.block_indices().collect();
+        if blocks.is_subset(&self.expanded) {
+            self.expanded.clear();
+        } else {
+            self.expanded = blocks;
+        }
+    }
+
+    /// Get the line indices of expanded blocks
+    pub fn expanded_blocks(&self) -> &BTreeSet<usize> {
+        &self.expanded
+    }
 }
 
 impl fmt::Display for OutputBuffer {
@@ -334,6 +553,78 @@ mod tests {
         assert!(buffer.is_auto_scroll());
     }
 
+    #[test]
+    fn test_output_buffer_events() {
+        let mut buffer = OutputBuffer::new();
+        buffer.start();
+
+        buffer.push_event(AgentEvent::Text("First\nSecond".to_string()));
+        assert_eq!(buffer.len(), 2);
+        assert!(!buffer.has_blocks());
+
+        buffer.push_event(AgentEvent::ToolResult {
+            output: "boom".to_string(),
+            is_error: true,
+        });
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.lines()[2].stream, OutputStream::Stderr);
+        assert_eq!(buffer.lines()[2].content, "Tool error (1 line)");
+        assert!(buffer.has_blocks());
+
+        buffer.push_event(AgentEvent::ToolResult {
+            output: "one\ntwo".to_string(),
+            is_error: false,
+        });
+        assert_eq!(buffer.rendered_len(), 4);
+
+        // Without a selection, `o` toggles the latest block only
+        buffer.toggle_block();
+        assert_eq!(buffer.rendered_len(), 6);
+        let rendered = OutputPanelWidget::new(buffer.lines(), None)
+            .blocks(buffer.expanded_blocks(), buffer.selected_block())
+            .format_lines();
+        assert_eq!(rendered.len(), buffer.rendered_len());
+
+        buffer.select_previous_block();
+        assert_eq!(buffer.selected_block(), Some(3));
+        buffer.select_previous_block();
+        assert_eq!(buffer.selected_block(), Some(2));
+        buffer.select_previous_block();
+        assert_eq!(buffer.selected_block(), Some(2));
+        buffer.toggle_block();
+        assert_eq!(buffer.rendered_len(), 7);
+
+        buffer.toggle_all_blocks();
+        assert_eq!(buffer.rendered_len(), 4);
+        buffer.toggle_all_blocks();
+        assert_eq!(buffer.rendered_len(), 7);
+    }
+
+    #[test]
+    fn test_output_buffer_scroll_expanded_blocks() {
+        let mut buffer = OutputBuffer::new();
+        buffer.start();
+
+        buffer.push_event(AgentEvent::ToolResult {
+            output: (0..30)
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            is_error: false,
+        });
+        buffer.toggle_block();
+        assert_eq!(buffer.rendered_len(), 31);
+
+        // The expanded content is scrollable even though the buffer holds one line
+        buffer.scroll_up(1);
+        buffer.scroll_down(5, 20);
+        assert_eq!(buffer.scroll_offset(), 5);
+        assert!(!buffer.is_auto_scroll());
+        buffer.scroll_down(20, 20);
+        assert_eq!(buffer.scroll_offset(), 11);
+        assert!(buffer.is_auto_scroll());
+    }
+
     #[test]
     fn test_output_buffer_max_lines() {
         let mut buffer = OutputBuffer::new();
@@ -1,11 +1,11 @@
 //! Spec list widget for displaying specifications in the overview.
 
-use crate::domain::{Specification, WorkflowPhase, Worktree, WorktreeStatus};
+use crate::domain::{SpecId, Specification, WorkflowPhase, Worktree, WorktreeStatus};
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, List, ListItem, ListState},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 /// Widget for displaying a list of specifications
@@ -14,6 +14,9 @@ pub struct SpecListWidget<'a> {
     worktrees: &'a [Worktree],
     worktree_statuses: &'a HashMap<PathBuf, WorktreeStatus>,
     selected_index: usize,
+    marked: &'a [SpecId],
+    /// Worktrees whose status is being refreshed, with the spinner frame
+    refreshing: Option<(&'a HashSet<PathBuf>, &'static str)>,
 }
 
 impl<'a> SpecListWidget<'a> {
@@ -29,9 +32,24 @@ impl<'a> SpecListWidget<'a> {
             worktrees,
             worktree_statuses,
             selected_index,
+            marked: &[],
+            refreshing: None,
         }
     }
 
+    /// Show a spinner instead of the status of worktrees being refreshed
+    pub fn refreshing(mut self, paths: &'a HashSet<PathBuf>, spinner: &'static str) -> Self {
+        self.refreshing = Some((paths, spinner));
+        self
+    }
+
+    /// Set the specs marked for a batch run; a check column is shown while
+    /// any are marked
+    pub fn marked(mut self, marked: &'a [SpecId]) -> Self {
+        self.marked = marked;
+        self
+    }
+
     /// Get phase indicator character
     fn phase_indicator(phase: &WorkflowPhase) -> &'static str {
         match phase {
@@ -61,6 +79,11 @@ impl<'a> SpecListWidget<'a> {
 
     /// Get worktree status indicator
     fn worktree_status_indicator(&self, worktree: &Worktree) -> &'static str {
+        if let Some((paths, spinner)) = self.refreshing {
+            if paths.contains(&worktree.path) {
+                return spinner;
+            }
+        }
         match self.worktree_statuses.get(&worktree.path) {
             Some(WorktreeStatus::Clean) => "✓",          // Clean
             Some(WorktreeStatus::Dirty { .. }) => "●",   // Has changes
@@ -87,9 +110,18 @@ impl<'a> SpecListWidget<'a> {
                     String::new()
                 };
 
-                // Format: [indicator] spec-id: name [phase] [worktree status]
+                let mark = if self.marked.is_empty() {
+                    ""
+                } else if self.marked.contains(&spec.id) {
+                    "[x] "
+                } else {
+                    "[ ] "
+                };
+
+                // Format: [mark] [indicator] spec-id: name [phase] [worktree status]
                 let line = format!(
-                    "{} {}: {} {}{}",
+                    "{}{} {}: {} {}{}",
+                    mark,
                     indicator,
                     spec.id.as_str(),
                     spec.name,
@@ -0,0 +1,247 @@
+//! Usage summary widget showing token and cost totals from the run history.
+
+use crate::domain::{RunRecord, UsageSummary, UsageTotals};
+use ratatui::{
+    prelude::*,
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// State for the usage summary view
+#[derive(Debug, Default, Clone)]
+pub struct UsageViewState {
+    /// Aggregated usage
+    pub summary: UsageSummary,
+    /// Index of the first run shown in the run list
+    pub scroll: usize,
+}
+
+impl UsageViewState {
+    /// Create a new state for a summary
+    pub fn new(summary: UsageSummary) -> Self {
+        Self { summary, scroll: 0 }
+    }
+
+    /// Scroll the run list up by n rows
+    pub fn scroll_up(&mut self, n: usize) {
+        self.scroll = self.scroll.saturating_sub(n);
+    }
+
+    /// Scroll the run list down by n rows
+    pub fn scroll_down(&mut self, n: usize) {
+        let max = self.summary.runs.len().saturating_sub(1);
+        self.scroll = (self.scroll + n).min(max);
+    }
+}
+
+/// Usage summary widget
+pub struct UsageSummaryWidget<'a> {
+    state: &'a UsageViewState,
+}
+
+impl<'a> UsageSummaryWidget<'a> {
+    /// Create a new usage summary widget
+    pub fn new(state: &'a UsageViewState) -> Self {
+        Self { state }
+    }
+
+    fn totals_table<'b>(
+        title: &'static str,
+        key_header: &'static str,
+        totals: impl Iterator<Item = (&'b String, &'b UsageTotals)>,
+    ) -> Table<'b> {
+        let rows = totals.map(|(key, totals)| {
+            Row::new(vec![
+                Cell::from(key.as_str()),
+                Cell::from(totals.runs.to_string()),
+                Cell::from(format_tokens(totals.input_tokens)),
+                Cell::from(format_tokens(totals.output_tokens)),
+                Cell::from(format_totals_cost(totals)),
+            ])
+        });
+
+        Table::new(
+            rows,
+            [
+                Constraint::Min(12),
+                Constraint::Length(5),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header_row(&[key_header, "Runs", "In", "Out", "Cost"]))
+        .block(Block::default().borders(Borders::ALL).title(title))
+    }
+
+    fn run_row(record: &RunRecord, now: u64) -> Row<'static> {
+        let (model, input, output) = match &record.usage {
+            Some(usage) => (
+                usage.model.clone().unwrap_or_else(|| "-".to_string()),
+                usage.input_tokens,
+                usage.output_tokens,
+            ),
+            None => ("-".to_string(), 0, 0),
+        };
+
+        Row::new(vec![
+            Cell::from(format_age(now.saturating_sub(record.finished_at))),
+            Cell::from(record.spec_id.clone()),
+            Cell::from(record.command.clone()),
+            Cell::from(model),
+            Cell::from(format_tokens(input)),
+            Cell::from(format_tokens(output)),
+            Cell::from(format_cost(record.cost_usd)),
+        ])
+    }
+}
+
+impl Widget for UsageSummaryWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let summary = &self.state.summary;
+
+        if summary.is_empty() {
+            Paragraph::new("No token usage recorded yet")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(Block::default().borders(Borders::ALL).title("Usage"))
+                .render(area, buf);
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Percentage(40),
+                Constraint::Min(0),
+            ])
+            .split(area);
+
+        let total = &summary.total;
+        Paragraph::new(Line::from(vec![
+            Span::styled("Total: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!(
+                "{} runs, {} in / {} out, {}",
+                total.runs,
+                format_tokens(total.input_tokens),
+                format_tokens(total.output_tokens),
+                format_totals_cost(total),
+            )),
+        ]))
+        .render(chunks[0], buf);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(chunks[1]);
+        Widget::render(
+            Self::totals_table(" By spec ", "Spec", summary.by_spec.iter()),
+            columns[0],
+            buf,
+        );
+        Widget::render(
+            Self::totals_table(" By phase ", "Phase", summary.by_command.iter()),
+            columns[1],
+            buf,
+        );
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let rows = summary
+            .runs
+            .iter()
+            .skip(self.state.scroll)
+            .map(|record| Self::run_row(record, now));
+        let runs = Table::new(
+            rows,
+            [
+                Constraint::Length(9),
+                Constraint::Min(12),
+                Constraint::Length(10),
+                Constraint::Min(12),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(10),
+            ],
+        )
+        .header(header_row(&[
+            "When", "Spec", "Phase", "Model", "In", "Out", "Cost",
+        ]))
+        .block(Block::default().borders(Borders::ALL).title(" Runs "));
+        Widget::render(runs, chunks[2], buf);
+    }
+}
+
+fn header_row(titles: &[&'static str]) -> Row<'static> {
+    Row::new(titles.iter().map(|title| Cell::from(*title))).style(
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD),
+    )
+}
+
+/// Format a token count compactly, e.g. `12.3k` or `1.2M`
+fn format_tokens(tokens: u64) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{:.1}k", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
+/// Format a cost in USD, or `-` if unknown
+fn format_cost(cost_usd: Option<f64>) -> String {
+    match cost_usd {
+        Some(cost) => format!("${:.4}", cost),
+        None => "-".to_string(),
+    }
+}
+
+/// Format a cost total, marking totals that leave out unpriced runs
+fn format_totals_cost(totals: &UsageTotals) -> String {
+    if totals.unpriced_runs == totals.runs {
+        "-".to_string()
+    } else if totals.unpriced_runs > 0 {
+        format!("{}+", format_cost(Some(totals.cost_usd)))
+    } else {
+        format_cost(Some(totals.cost_usd))
+    }
+}
+
+/// Format an age in seconds, e.g. `5m ago`
+pub(crate) fn format_age(secs: u64) -> String {
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86_399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_formatting() {
+        assert_eq!(format_tokens(950), "950");
+        assert_eq!(format_tokens(12_345), "12.3k");
+        assert_eq!(format_tokens(1_500_000), "1.5M");
+        assert_eq!(format_cost(Some(0.01234)), "$0.0123");
+        assert_eq!(format_cost(None), "-");
+        assert_eq!(format_age(30), "just now");
+        assert_eq!(format_age(7200), "2h ago");
+
+        let totals = UsageTotals {
+            runs: 2,
+            cost_usd: 0.5,
+            unpriced_runs: 1,
+            ..UsageTotals::default()
+        };
+        assert_eq!(format_totals_cost(&totals), "$0.5000+");
+    }
+}
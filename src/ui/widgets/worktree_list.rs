@@ -1,11 +1,11 @@
 //! Worktree list widget for managing git worktrees.
 
-use crate::domain::{Worktree, WorktreeStatus, WorktreeSyncStatus};
+use crate::domain::{BranchSafety, Worktree, WorktreeStatus, WorktreeSyncStatus};
 use ratatui::{
     prelude::*,
     widgets::{Block, Borders, Clear, Paragraph},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
 /// Widget for displaying and managing worktrees
@@ -20,6 +20,8 @@ pub struct WorktreeListWidget<'a> {
     selected_index: usize,
     /// Currently active worktree path (if any)
     active_worktree: Option<&'a PathBuf>,
+    /// Worktrees and branches being refreshed, with the spinner frame
+    refreshing: Option<(&'a HashSet<PathBuf>, &'a HashSet<String>, &'static str)>,
 }
 
 impl<'a> WorktreeListWidget<'a> {
@@ -40,6 +42,7 @@ impl<'a> WorktreeListWidget<'a> {
             sync_statuses: empty,
             selected_index,
             active_worktree: None,
+            refreshing: None,
         }
     }
 
@@ -54,6 +57,17 @@ impl<'a> WorktreeListWidget<'a> {
         self.active_worktree = path;
         self
     }
+
+    /// Show a spinner on rows whose status or sync status is being refreshed
+    pub fn refreshing(
+        mut self,
+        paths: &'a HashSet<PathBuf>,
+        branches: &'a HashSet<String>,
+        spinner: &'static str,
+    ) -> Self {
+        self.refreshing = Some((paths, branches, spinner));
+        self
+    }
 }
 
 impl Widget for WorktreeListWidget<'_> {
@@ -117,8 +131,27 @@ impl Widget for WorktreeListWidget<'_> {
             buf.set_string(x, y, &branch_display, branch_style);
             x += branch_display.len() as u16 + 1;
 
-            // Working directory status
-            if let Some(status) = status {
+            // Refresh in progress; the previous status stays visible
+            if let Some((paths, branches, spinner)) = self.refreshing {
+                if paths.contains(&wt.path) || branches.contains(&wt.branch) {
+                    buf.set_string(x, y, spinner, Style::default().fg(Color::DarkGray));
+                    x += 2;
+                }
+            }
+
+            // Detached, locked and prunable states
+            for flag in wt.flags() {
+                let color = match flag {
+                    "locked" => Color::Blue,
+                    _ => Color::Red,
+                };
+                let flag_text = format!("[{}]", flag);
+                buf.set_string(x, y, &flag_text, Style::default().fg(color));
+                x += flag_text.len() as u16 + 1;
+            }
+
+            // Working directory status; a prunable worktree's directory is gone
+            if let Some(status) = status.filter(|_| wt.prunable.is_none()) {
                 let (status_text, style) = match status {
                     WorktreeStatus::Clean => ("✓".to_string(), Style::default().fg(Color::Green)),
                     WorktreeStatus::Dirty {
@@ -299,6 +332,14 @@ pub struct WorktreeManagementState {
     pub confirm_yes_selected: bool,
     /// Worktree pending deletion
     pub pending_delete: Option<PathBuf>,
+    /// Merged worktrees pending removal by a cleanup
+    pub pending_cleanup: Vec<Worktree>,
+    /// Branch of the worktree pending deletion that may be deleted with it
+    pub pending_branch: Option<String>,
+    /// What deleting the pending branch would lose, if it could be checked
+    pub branch_safety: Option<BranchSafety>,
+    /// Whether to delete the pending branch along with the worktree
+    pub delete_branch: bool,
 }
 
 impl WorktreeManagementState {
@@ -328,6 +369,45 @@ impl WorktreeManagementState {
         self.confirm_yes_selected = false; // Default to "No" for safety
     }
 
+    /// Offer deleting a branch along with the worktree pending deletion
+    pub fn offer_branch_delete(&mut self, branch: String, safety: Option<BranchSafety>) {
+        self.pending_branch = Some(branch);
+        self.branch_safety = safety;
+        self.delete_branch = false;
+    }
+
+    /// Toggle deleting the pending branch along with the worktree
+    pub fn toggle_delete_branch(&mut self) {
+        self.delete_branch = self.pending_branch.is_some() && !self.delete_branch;
+    }
+
+    /// Take the branch to delete after its worktree, if that was chosen
+    pub fn take_branch_delete(&mut self) -> Option<String> {
+        let branch = self.pending_branch.take().filter(|_| self.delete_branch);
+        self.branch_safety = None;
+        self.delete_branch = false;
+        branch
+    }
+
+    /// Show confirmation for removing worktrees merged into the main branch
+    pub fn request_cleanup(&mut self, worktrees: Vec<Worktree>) {
+        self.pending_cleanup = worktrees;
+        self.showing_confirm = true;
+        self.confirm_yes_selected = false;
+    }
+
+    /// Confirm the cleanup (returns the worktrees to remove if confirmed)
+    pub fn confirm_cleanup(&mut self) -> Vec<Worktree> {
+        if self.confirm_yes_selected {
+            self.showing_confirm = false;
+            self.confirm_yes_selected = false;
+            std::mem::take(&mut self.pending_cleanup)
+        } else {
+            self.cancel_confirm();
+            Vec::new()
+        }
+    }
+
     /// Toggle confirmation selection
     pub fn toggle_confirm_selection(&mut self) {
         self.confirm_yes_selected = !self.confirm_yes_selected;
@@ -337,6 +417,10 @@ impl WorktreeManagementState {
     pub fn cancel_confirm(&mut self) {
         self.showing_confirm = false;
         self.pending_delete = None;
+        self.pending_cleanup.clear();
+        self.pending_branch = None;
+        self.branch_safety = None;
+        self.delete_branch = false;
         self.confirm_yes_selected = false;
     }
 
@@ -382,11 +466,25 @@ mod tests {
         assert!(!state.confirm_yes_selected);
         assert!(state.pending_delete.is_some());
 
+        state.toggle_delete_branch();
+        assert!(!state.delete_branch);
+        state.offer_branch_delete("003-foo".to_string(), None);
+        state.toggle_delete_branch();
+        assert!(state.delete_branch);
+
         state.toggle_confirm_selection();
         assert!(state.confirm_yes_selected);
 
         let deleted = state.confirm_delete();
         assert!(deleted.is_some());
         assert!(!state.showing_confirm);
+        assert_eq!(state.take_branch_delete().as_deref(), Some("003-foo"));
+        assert_eq!(state.take_branch_delete(), None);
+
+        let merged = Worktree::new(PathBuf::from("/test/003"), "003-foo".to_string(), false);
+        state.request_cleanup(vec![merged]);
+        assert!(state.showing_confirm);
+        assert!(state.confirm_cleanup().is_empty());
+        assert!(state.pending_cleanup.is_empty());
     }
 }